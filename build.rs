@@ -0,0 +1,432 @@
+// Generates the registries of rustc error codes from `assets/error_codes`, of lints from
+// `assets/lints`, of feature gates from `assets/features`, of tips from `assets/tips` and the
+// section index of the books in `assets/books`, see `src/error_codes.rs`, `src/lints.rs`,
+// `src/features.rs`, `src/tips.rs` and `src/books.rs`
+
+use std::{
+    env,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+const ERROR_CODES_DIR: &str = "assets/error_codes";
+const LINTS_DIR: &str = "assets/lints";
+const FEATURES_DIR: &str = "assets/features";
+const BOOKS_DIR: &str = "assets/books";
+const TIPS_DIR: &str = "assets/tips";
+
+/// The tools whose lints are bundled, and the prefix of their lint names
+const TOOLS: [(&str, &str); 2] = [("rustc", ""), ("clippy", "clippy::")];
+
+fn main() {
+    error_codes();
+    lints();
+    features();
+    books();
+    tips();
+}
+
+fn error_codes() {
+    println!("cargo:rerun-if-changed={ERROR_CODES_DIR}");
+
+    let dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(ERROR_CODES_DIR);
+    let mut files = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension().is_some_and(|extension| extension == "md")
+                && path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .is_some_and(is_code)
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let mut registry = String::from("pub static ERROR_CODES: &[ErrorCode] = &[\n");
+    for path in files {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let code = path.file_stem().unwrap().to_str().unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let summary = summary(&content);
+
+        writeln!(
+            registry,
+            "    ErrorCode {{ code: {code:?}, title: {:?}, summary: {summary:?}, removed: {}, explanation: include_str!({:?}), examples: &{:?} }},",
+            title(&summary),
+            is_removed(&content),
+            path.display(),
+            examples(&content),
+        )
+        .unwrap();
+    }
+    registry.push_str("];\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("error_codes.rs");
+    fs::write(out, registry).unwrap();
+}
+
+fn lints() {
+    let root = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(LINTS_DIR);
+
+    let mut lints = Vec::new();
+    for (tool, prefix) in TOOLS {
+        let dir = root.join(tool);
+        println!("cargo:rerun-if-changed={}", dir.display());
+        for path in markdown_files(&dir) {
+            println!("cargo:rerun-if-changed={}", path.display());
+            let name = format!("{prefix}{}", path.file_stem().unwrap().to_str().unwrap());
+            let content = fs::read_to_string(&path).unwrap();
+            lints.push((name, content));
+        }
+    }
+    lints.sort();
+
+    let mut registry = String::from("pub static LINTS: &[Lint] = &[\n");
+    for (name, content) in lints {
+        let (metadata, explanation) =
+            front_matter(&content).unwrap_or_else(|| panic!("the lint {name} has no front matter"));
+        let field = |key: &str| {
+            metadata
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, value)| *value)
+        };
+        let level = match field("level") {
+            Some("allow") => "Allow",
+            Some("warn") => "Warn",
+            Some("deny") => "Deny",
+            Some("forbid") => "Forbid",
+            level => panic!("the lint {name} has an invalid level {level:?}"),
+        };
+
+        writeln!(
+            registry,
+            "    Lint {{ name: {name:?}, group: {:?}, level: Level::{level}, description: {:?}, since: {:?}, explanation: {:?}, example: {:?} }},",
+            field("group"),
+            field("description").unwrap_or_default(),
+            field("since"),
+            explanation,
+            lint_example(explanation),
+        )
+        .unwrap();
+    }
+    registry.push_str("];\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("lints.rs");
+    fs::write(out, registry).unwrap();
+}
+
+fn features() {
+    let dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(FEATURES_DIR);
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut registry = String::from("pub static FEATURES: &[Feature] = &[\n");
+    for path in markdown_files(&dir) {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let (metadata, explanation) = front_matter(&content)
+            .unwrap_or_else(|| panic!("the feature {name} has no front matter"));
+        let field = |key: &str| {
+            metadata
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, value)| *value)
+        };
+        let kind = match field("kind") {
+            Some("language") => "Language",
+            Some("library") => "Library",
+            kind => panic!("the feature {name} has an invalid kind {kind:?}"),
+        };
+        let status = match (field("status"), field("since")) {
+            (Some("unstable"), _) => "Unstable".to_owned(),
+            (Some("stable"), Some(since)) => format!("Stable({since:?})"),
+            (Some("removed"), _) => "Removed".to_owned(),
+            status => panic!("the feature {name} has an invalid status {status:?}"),
+        };
+        let issue = field("issue").map(|issue| {
+            issue
+                .parse::<u32>()
+                .unwrap_or_else(|_| panic!("the feature {name} has an invalid issue {issue}"))
+        });
+
+        writeln!(
+            registry,
+            "    Feature {{ name: {name:?}, kind: Kind::{kind}, status: Status::{status}, issue: {issue:?}, description: {:?}, explanation: {:?}, example: {:?} }},",
+            field("description").unwrap_or_default(),
+            explanation,
+            first_code_block(explanation),
+        )
+        .unwrap();
+    }
+    registry.push_str("];\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("features.rs");
+    fs::write(out, registry).unwrap();
+}
+
+fn books() {
+    let dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(BOOKS_DIR);
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut registry = String::from("pub static BOOKS: &[Book] = &[\n");
+    for path in markdown_files(&dir) {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let (metadata, toc) =
+            front_matter(&content).unwrap_or_else(|| panic!("the book {name} has no front matter"));
+        let field = |key: &str| {
+            metadata
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, value)| *value)
+                .unwrap_or_else(|| panic!("the book {name} has no {key}"))
+        };
+
+        writeln!(
+            registry,
+            "    Book {{ name: {name:?}, title: {:?}, url: {:?}, sections: &[",
+            field("title"),
+            field("url"),
+        )
+        .unwrap();
+        let mut parents = Vec::new();
+        for entry in toc_entries(toc) {
+            parents.truncate(entry.depth);
+            writeln!(
+                registry,
+                "        Section {{ title: {:?}, parents: &{parents:?}, link: {:?}, keywords: &{:?}, excerpt: {:?} }},",
+                entry.title, entry.link, entry.keywords, entry.excerpt,
+            )
+            .unwrap();
+            parents.push(entry.title);
+        }
+        registry.push_str("    ] },\n");
+    }
+    registry.push_str("];\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("books.rs");
+    fs::write(out, registry).unwrap();
+}
+
+fn tips() {
+    let dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join(TIPS_DIR);
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut registry = String::from("pub static TIPS: &[Tip] = &[\n");
+    for path in markdown_files(&dir) {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let name = path.file_stem().unwrap().to_str().unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let (metadata, explanation) =
+            front_matter(&content).unwrap_or_else(|| panic!("the tip {name} has no front matter"));
+        let field = |key: &str| {
+            metadata
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, value)| *value)
+                .unwrap_or_else(|| panic!("the tip {name} has no {key}"))
+        };
+        let difficulty = match field("difficulty") {
+            "beginner" => "Beginner",
+            "intermediate" => "Intermediate",
+            "advanced" => "Advanced",
+            difficulty => panic!("the tip {name} has an invalid difficulty {difficulty}"),
+        };
+        let tags = field("tags").split(", ").collect::<Vec<_>>();
+        let example = first_code_block(explanation)
+            .unwrap_or_else(|| panic!("the tip {name} has no example"));
+        // the example is shown with its button to run it, not as part of the text
+        let explanation = explanation
+            .split("```rust")
+            .next()
+            .unwrap_or_default()
+            .trim();
+
+        writeln!(
+            registry,
+            "    Tip {{ name: {name:?}, topic: {:?}, tags: &{tags:?}, difficulty: Difficulty::{difficulty}, explanation: {explanation:?}, example: {example:?} }},",
+            field("topic"),
+        )
+        .unwrap();
+    }
+    registry.push_str("];\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("tips.rs");
+    fs::write(out, registry).unwrap();
+}
+
+/// A section in the table of contents of a book
+struct TocEntry<'a> {
+    depth: usize,
+    title: String,
+    link: &'a str,
+    keywords: Vec<&'a str>,
+    excerpt: &'a str,
+}
+
+/// The sections of a table of contents, a nested list of `- [title](link)` items each followed by
+/// indented `keywords: a, b` and excerpt lines
+fn toc_entries(toc: &str) -> Vec<TocEntry<'_>> {
+    let mut entries: Vec<TocEntry> = Vec::new();
+    for line in toc.lines() {
+        let content = line.trim_start();
+        let indent = line.len() - content.len();
+        if let Some(item) = content.strip_prefix("- [") {
+            let (title, link) = item
+                .rsplit_once("](")
+                .unwrap_or_else(|| panic!("invalid table of contents entry {line}"));
+            entries.push(TocEntry {
+                depth: indent / 2,
+                title: title.replace("\\[", "[").replace("\\]", "]"),
+                link: link.trim_end_matches(')'),
+                keywords: Vec::new(),
+                excerpt: "",
+            });
+        } else if let Some(entry) = entries.last_mut() {
+            match content.strip_prefix("keywords: ") {
+                Some(keywords) => entry.keywords = keywords.split(", ").collect(),
+                None if !content.is_empty() => entry.excerpt = content,
+                None => {}
+            }
+        }
+    }
+    entries
+}
+
+fn markdown_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "md"))
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+/// The `key: value` lines between the leading `---` lines and the markdown after them
+fn front_matter(content: &str) -> Option<(Vec<(&str, &str)>, &str)> {
+    let rest = content.strip_prefix("---\n")?;
+    let (metadata, body) = rest.split_once("\n---\n")?;
+    let metadata = metadata
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect();
+    Some((metadata, body.trim()))
+}
+
+/// The first code block under an example heading
+fn lint_example(explanation: &str) -> Option<String> {
+    let mut lines = explanation
+        .lines()
+        .skip_while(|line| !(line.starts_with('#') && line.contains("Example")))
+        .skip_while(|line| !line.starts_with("```"))
+        .skip(1);
+    let example = lines
+        .by_ref()
+        .take_while(|line| !line.starts_with("```"))
+        .collect::<Vec<_>>();
+    (!example.is_empty()).then(|| example.join("\n"))
+}
+
+/// The first rust code block
+fn first_code_block(markdown: &str) -> Option<String> {
+    let mut lines = markdown
+        .lines()
+        .skip_while(|line| !line.starts_with("```rust"))
+        .skip(1);
+    let block = lines
+        .by_ref()
+        .take_while(|line| !line.starts_with("```"))
+        .collect::<Vec<_>>();
+    (!block.is_empty()).then(|| block.join("\n"))
+}
+
+fn is_code(name: &str) -> bool {
+    name.len() == 5 && name.starts_with('E') && name[1..].bytes().all(|b| b.is_ascii_digit())
+}
+
+/// The first paragraph, without the headings above it and the introduction of the example below,
+/// or the last heading for explanations which are only a note
+fn summary(explanation: &str) -> String {
+    let lines = explanation.lines().map(str::trim);
+    let summary = lines
+        .clone()
+        .skip_while(|line| line.is_empty() || line.starts_with('#'))
+        .take_while(|line| !line.is_empty() && !line.starts_with("```"))
+        .map(|line| match line.strip_suffix("example:") {
+            Some(rest) => rest.rfind(". ").map_or("", |end| &line[..=end]),
+            None => line,
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if !summary.is_empty() {
+        return summary;
+    }
+
+    lines
+        .take_while(|line| line.is_empty() || line.starts_with('#'))
+        .filter_map(|line| {
+            Some(line.trim_start_matches('#').trim()).filter(|line| !line.is_empty())
+        })
+        .last()
+        .unwrap_or_default()
+        .to_owned()
+}
+
+/// The first sentence of the summary
+fn title(summary: &str) -> &str {
+    summary
+        .find(". ")
+        .map_or(summary, |end| &summary[..=end])
+        .trim_end_matches('.')
+}
+
+/// Explanations of removed codes start with a note saying so
+fn is_removed(explanation: &str) -> bool {
+    explanation
+        .lines()
+        .take_while(|line| line.trim().is_empty() || line.starts_with('#'))
+        .any(|line| line.contains("no longer emitted"))
+}
+
+/// The code of the examples which fail to compile, either marked `compile_fail` or introduced as
+/// erroneous since the conversion for Discord turned the marker into `rust`
+fn examples(explanation: &str) -> Vec<String> {
+    let mut examples = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    let mut erroneous = false;
+    let mut in_fence = false;
+
+    for line in explanation.lines() {
+        let trimmed = line.trim();
+        if let Some(info) = trimmed.strip_prefix("```") {
+            if in_fence {
+                if let Some(lines) = current.take() {
+                    examples.push(lines.join("\n"));
+                }
+            } else if info.contains("compile_fail") || (erroneous && !info.contains("ignore")) {
+                current = Some(Vec::new());
+            }
+            in_fence = !in_fence;
+            erroneous = false;
+        } else if let Some(lines) = &mut current {
+            // the lines rustdoc hides are still compiled
+            let line = match line.strip_prefix('#') {
+                Some("") => "",
+                Some(rest) if rest.starts_with(' ') => &rest[1..],
+                _ => line,
+            };
+            lines.push(line);
+        } else if !in_fence && !trimmed.is_empty() {
+            erroneous = trimmed.starts_with("Erroneous code example");
+        }
+    }
+
+    examples
+}
@@ -0,0 +1,513 @@
+use chrono::{DateTime, Utc};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, fs, io::Read, path::Path};
+
+pub const ADVISORIES_PATH: &str = "./assets/advisories.json";
+
+/// Export of the RustSec database in the OSV format
+pub const OSV_EXPORT_URL: &str =
+    "https://osv-vulnerabilities.storage.googleapis.com/crates.io/all.zip";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdvisoryDb {
+    /// When the newest advisory of the export was modified, which dates the export
+    pub updated_at: Option<DateTime<Utc>>,
+    advisories: HashMap<String, Vec<Advisory>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Advisory {
+    pub id: String,
+    pub package: String,
+    pub title: String,
+    pub aliases: Vec<String>,
+    pub cvss: Option<String>,
+    pub informational: Option<String>,
+    pub ranges: Vec<AffectedRange>,
+}
+
+/// Versions in `introduced..fixed` are affected, a missing `fixed` means no patch exists yet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AffectedRange {
+    pub introduced: String,
+    pub fixed: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    None,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Severity::None => "none",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        };
+        f.write_str(name)
+    }
+}
+
+impl AdvisoryDb {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AdvisoryError> {
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AdvisoryError> {
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Builds the database from the zipped OSV export, skipping withdrawn and non-RustSec entries
+    pub fn from_osv_zip(bytes: &[u8]) -> Result<Self, AdvisoryError> {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))?;
+        let mut advisories: HashMap<String, Vec<Advisory>> = HashMap::new();
+        let mut updated_at = None;
+
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i)?;
+            if !file.name().starts_with("RUSTSEC-") {
+                continue;
+            }
+
+            let mut content = String::new();
+            file.read_to_string(&mut content)?;
+            let entry: osv::Entry = serde_json::from_str(&content)?;
+            updated_at = updated_at.max(entry.modified());
+
+            for advisory in entry.into_advisories() {
+                advisories
+                    .entry(advisory.package.clone())
+                    .or_default()
+                    .push(advisory);
+            }
+        }
+
+        for list in advisories.values_mut() {
+            list.sort_by(|a, b| b.id.cmp(&a.id));
+        }
+
+        Ok(Self {
+            updated_at,
+            advisories,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.advisories.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+
+    /// All advisories filed against `package`, regardless of the version
+    pub fn for_package(&self, package: &str) -> &[Advisory] {
+        self.advisories
+            .get(package)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Advisories which affect `version` of `package`
+    pub fn check(&self, package: &str, version: &Version) -> Vec<&Advisory> {
+        self.for_package(package)
+            .iter()
+            .filter(|advisory| advisory.affects(version))
+            .collect()
+    }
+}
+
+impl Advisory {
+    pub fn url(&self) -> String {
+        format!("https://rustsec.org/advisories/{}.html", self.id)
+    }
+
+    pub fn affects(&self, version: &Version) -> bool {
+        self.ranges.iter().any(|range| {
+            let introduced = Version::parse(&range.introduced).unwrap_or(Version::new(0, 0, 0));
+            let fixed = range
+                .fixed
+                .as_deref()
+                .and_then(|fixed| Version::parse(fixed).ok());
+
+            // pre-releases like `0.0.0-0` would otherwise sort above stable versions of `0.0.0`
+            let after_introduced = range.introduced.starts_with("0.0.0") || *version >= introduced;
+            let before_fixed = fixed.is_none_or(|fixed| *version < fixed);
+
+            after_introduced && before_fixed
+        })
+    }
+
+    pub fn patched(&self) -> Vec<String> {
+        self.ranges
+            .iter()
+            .enumerate()
+            .filter_map(|(i, range)| {
+                let fixed = range.fixed.as_ref()?;
+                Some(match self.ranges.get(i + 1) {
+                    Some(next) => format!(">= {fixed}, < {}", next.introduced),
+                    None => format!(">= {fixed}"),
+                })
+            })
+            .collect()
+    }
+
+    pub fn severity(&self) -> Option<Severity> {
+        let score = cvss_base_score(self.cvss.as_deref()?)?;
+        Some(match score {
+            s if s <= 0.0 => Severity::None,
+            s if s < 4.0 => Severity::Low,
+            s if s < 7.0 => Severity::Medium,
+            s if s < 9.0 => Severity::High,
+            _ => Severity::Critical,
+        })
+    }
+}
+
+/// Computes the CVSS v3 base score of a vector like `CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H`
+pub fn cvss_base_score(vector: &str) -> Option<f64> {
+    let metrics = vector
+        .split('/')
+        .skip(1)
+        .filter_map(|metric| metric.split_once(':'))
+        .collect::<HashMap<_, _>>();
+
+    let changed = match *metrics.get("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+    let impact_value = |key| match *metrics.get(key)? {
+        "H" => Some(0.56),
+        "L" => Some(0.22),
+        "N" => Some(0.0),
+        _ => None,
+    };
+
+    let av = match *metrics.get("AV")? {
+        "N" => 0.85,
+        "A" => 0.62,
+        "L" => 0.55,
+        "P" => 0.2,
+        _ => return None,
+    };
+    let ac = match *metrics.get("AC")? {
+        "L" => 0.77,
+        "H" => 0.44,
+        _ => return None,
+    };
+    let pr = match (*metrics.get("PR")?, changed) {
+        ("N", _) => 0.85,
+        ("L", false) => 0.62,
+        ("L", true) => 0.68,
+        ("H", false) => 0.27,
+        ("H", true) => 0.5,
+        _ => return None,
+    };
+    let ui = match *metrics.get("UI")? {
+        "N" => 0.85,
+        "R" => 0.62,
+        _ => return None,
+    };
+
+    let iss =
+        1.0 - (1.0 - impact_value("C")?) * (1.0 - impact_value("I")?) * (1.0 - impact_value("A")?);
+    let impact = if changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02f64).powi(15)
+    } else {
+        6.42 * iss
+    };
+    let exploitability = 8.22 * av * ac * pr * ui;
+
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+
+    let score = if changed {
+        (1.08 * (impact + exploitability)).min(10.0)
+    } else {
+        (impact + exploitability).min(10.0)
+    };
+
+    Some(round_up(score))
+}
+
+/// The `Roundup` function of the CVSS v3.1 specification
+fn round_up(value: f64) -> f64 {
+    let int_input = (value * 100_000.0).round() as u64;
+    if int_input.is_multiple_of(10_000) {
+        int_input as f64 / 100_000.0
+    } else {
+        ((int_input / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+mod osv {
+    use super::{Advisory, AffectedRange};
+    use chrono::{DateTime, Utc};
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    pub struct Entry {
+        id: String,
+        #[serde(default)]
+        summary: String,
+        #[serde(default)]
+        aliases: Vec<String>,
+        withdrawn: Option<String>,
+        modified: Option<DateTime<Utc>>,
+        #[serde(default)]
+        affected: Vec<Affected>,
+        #[serde(default)]
+        severity: Vec<SeverityScore>,
+        #[serde(default)]
+        database_specific: DatabaseSpecific,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Affected {
+        package: Package,
+        #[serde(default)]
+        ranges: Vec<Range>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Package {
+        name: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Range {
+        #[serde(rename = "type")]
+        kind: String,
+        #[serde(default)]
+        events: Vec<Event>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Event {
+        introduced: Option<String>,
+        fixed: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct SeverityScore {
+        #[serde(rename = "type")]
+        kind: String,
+        score: String,
+    }
+
+    #[derive(Debug, Default, Deserialize)]
+    struct DatabaseSpecific {
+        informational: Option<String>,
+    }
+
+    impl Entry {
+        pub fn modified(&self) -> Option<DateTime<Utc>> {
+            self.modified
+        }
+
+        pub fn into_advisories(self) -> Vec<Advisory> {
+            if self.withdrawn.is_some() {
+                return Vec::new();
+            }
+
+            let cvss = self
+                .severity
+                .into_iter()
+                .find(|severity| severity.kind.starts_with("CVSS_V3"))
+                .map(|severity| severity.score);
+
+            self.affected
+                .into_iter()
+                .map(|affected| Advisory {
+                    id: self.id.clone(),
+                    package: affected.package.name,
+                    title: self.summary.clone(),
+                    aliases: self.aliases.clone(),
+                    cvss: cvss.clone(),
+                    informational: self.database_specific.informational.clone(),
+                    ranges: affected
+                        .ranges
+                        .into_iter()
+                        .filter(|range| range.kind == "SEMVER")
+                        .flat_map(|range| to_ranges(range.events))
+                        .collect(),
+                })
+                .collect()
+        }
+    }
+
+    /// Pairs up the flat `introduced`/`fixed` event list into ranges
+    fn to_ranges(events: Vec<Event>) -> Vec<AffectedRange> {
+        let mut ranges = Vec::new();
+        let mut introduced = None;
+
+        for event in events {
+            if let Some(version) = event.introduced {
+                introduced = Some(version);
+            } else if let Some(fixed) = event.fixed {
+                ranges.push(AffectedRange {
+                    introduced: introduced.take().unwrap_or_else(|| "0.0.0-0".to_owned()),
+                    fixed: Some(fixed),
+                });
+            }
+        }
+
+        if let Some(introduced) = introduced {
+            ranges.push(AffectedRange {
+                introduced,
+                fixed: None,
+            });
+        }
+
+        ranges
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AdvisoryError {
+    #[error("Error from Serde: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Error reading the advisory archive: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("Error accessing the advisory database file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regex_advisory() -> Advisory {
+        Advisory {
+            id: "RUSTSEC-2022-0013".to_owned(),
+            package: "regex".to_owned(),
+            title: "Regexes with large repetitions on empty sub-expressions take a very long time to parse".to_owned(),
+            aliases: vec!["CVE-2022-24713".to_owned()],
+            cvss: Some("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:H".to_owned()),
+            informational: None,
+            ranges: vec![AffectedRange {
+                introduced: "0.0.0-0".to_owned(),
+                fixed: Some("1.5.5".to_owned()),
+            }],
+        }
+    }
+
+    #[test]
+    fn affects() {
+        let advisory = regex_advisory();
+
+        assert!(advisory.affects(&Version::new(0, 0, 1)));
+        assert!(advisory.affects(&Version::new(1, 5, 4)));
+        assert!(!advisory.affects(&Version::new(1, 5, 5)));
+        assert!(!advisory.affects(&Version::new(1, 11, 1)));
+        assert_eq!(advisory.patched(), [">= 1.5.5"]);
+    }
+
+    #[test]
+    fn severity() {
+        assert_eq!(regex_advisory().severity(), Some(Severity::High));
+        assert_eq!(
+            cvss_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"),
+            Some(9.8)
+        );
+        assert_eq!(
+            cvss_base_score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H"),
+            Some(10.0)
+        );
+        assert_eq!(
+            cvss_base_score("CVSS:3.1/AV:L/AC:L/PR:L/UI:N/S:U/C:H/I:H/A:H"),
+            Some(7.8)
+        );
+        assert_eq!(cvss_base_score("garbage"), None);
+    }
+
+    #[test]
+    fn osv_entry() {
+        let entry: osv::Entry = serde_json::from_str(
+            r#"{
+                "id": "RUSTSEC-2021-0124",
+                "summary": "Data race when sending and receiving after closing a `oneshot` channel",
+                "aliases": ["CVE-2021-45710"],
+                "affected": [{
+                    "package": { "ecosystem": "crates.io", "name": "tokio" },
+                    "ranges": [{
+                        "type": "SEMVER",
+                        "events": [
+                            { "introduced": "0.1.14" },
+                            { "fixed": "1.8.4" },
+                            { "introduced": "1.9.0" },
+                            { "fixed": "1.13.1" }
+                        ]
+                    }]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let advisories = entry.into_advisories();
+        assert_eq!(advisories.len(), 1);
+
+        let advisory = &advisories[0];
+        assert_eq!(advisory.package, "tokio");
+        assert_eq!(advisory.patched(), [">= 1.8.4, < 1.9.0", ">= 1.13.1"]);
+        assert!(advisory.affects(&Version::new(1, 10, 0)));
+        assert!(!advisory.affects(&Version::new(1, 8, 5)));
+        assert!(!advisory.affects(&Version::new(1, 45, 0)));
+        assert!(!advisory.affects(&Version::new(0, 1, 0)));
+    }
+
+    #[test]
+    fn osv_export() {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let mut archive = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let files = [
+            (
+                "RUSTSEC-2022-0013.json",
+                r#"{"id": "RUSTSEC-2022-0013", "modified": "2023-06-13T13:10:24Z",
+                    "summary": "Regexes with large repetitions take a very long time to parse",
+                    "severity": [{"type": "CVSS_V3", "score": "CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:N/I:N/A:H"}],
+                    "affected": [{"package": {"name": "regex"}, "ranges": [{"type": "SEMVER",
+                        "events": [{"introduced": "0.0.0-0"}, {"fixed": "1.5.5"}]}]}]}"#,
+            ),
+            (
+                "RUSTSEC-2020-0001.json",
+                r#"{"id": "RUSTSEC-2020-0001", "modified": "2024-02-01T00:00:00Z",
+                    "withdrawn": "2024-02-01T00:00:00Z", "summary": "Withdrawn",
+                    "affected": [{"package": {"name": "regex"}}]}"#,
+            ),
+            ("GHSA-xxxx.json", r#"{"id": "GHSA-xxxx"}"#),
+        ];
+        for (name, content) in files {
+            archive
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            archive.write_all(content.as_bytes()).unwrap();
+        }
+        let bytes = archive.finish().unwrap().into_inner();
+
+        let db = AdvisoryDb::from_osv_zip(&bytes).unwrap();
+        assert_eq!(db.len(), 1);
+        assert_eq!(
+            db.updated_at.map(|date| date.to_rfc3339()).as_deref(),
+            Some("2024-02-01T00:00:00+00:00")
+        );
+        let advisories = db.check("regex", &Version::new(1, 5, 4));
+        assert_eq!(advisories[0].severity(), Some(Severity::High));
+    }
+}
@@ -0,0 +1,37 @@
+// Generates `assets/advisories.json` from a download of the RustSec database in the OSV format,
+// dated by its most recently modified advisory
+//
+// Usage: advisories <all.zip>
+//
+// The export is at https://osv-vulnerabilities.storage.googleapis.com/crates.io/all.zip
+
+use corro::advisories::{ADVISORIES_PATH, AdvisoryDb};
+use std::{env, fs, process::ExitCode};
+
+fn main() -> ExitCode {
+    let Some(path) = env::args().nth(1) else {
+        eprintln!("usage: advisories <all.zip>");
+        return ExitCode::FAILURE;
+    };
+
+    let db = match fs::read(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| AdvisoryDb::from_osv_zip(&bytes).map_err(|e| e.to_string()))
+    {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("failed to read the export {path}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = db.save(ADVISORIES_PATH) {
+        eprintln!("failed to write {ADVISORIES_PATH}: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    match db.updated_at {
+        Some(date) => println!("wrote {} advisories exported on {date}", db.len()),
+        None => println!("wrote {} advisories", db.len()),
+    }
+    ExitCode::SUCCESS
+}
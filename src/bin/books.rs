@@ -0,0 +1,609 @@
+// Syncs the section index in `assets/books` with a toolchain. Every chapter in the table of
+// contents of a book is listed with the headings of its page up to `###`, each with the terms
+// emphasized below it as keywords and its first paragraph as the excerpt.
+//
+// Usage: books <toolchain sysroot> [assets dir] [--write]
+//
+// The toolchain needs the rust-docs component, `rustc --print sysroot` prints where it is
+// installed. Without `--write` only the added, changed and removed books are reported.
+
+#[path = "common/html.rs"]
+mod html;
+
+use html::Token;
+use regex::Regex;
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::LazyLock,
+};
+
+const ASSETS_DIR: &str = "assets/books";
+
+/// The books of the rust-docs, relative to the sysroot
+const DOCS_DIR: &str = "share/doc/rust/html";
+
+/// The directory, title and url of each indexed book
+const BOOKS: [(&str, &str, &str); 4] = [
+    (
+        "book",
+        "The Rust Programming Language",
+        "https://doc.rust-lang.org/book/",
+    ),
+    (
+        "reference",
+        "The Rust Reference",
+        "https://doc.rust-lang.org/reference/",
+    ),
+    (
+        "rust-by-example",
+        "Rust by Example",
+        "https://doc.rust-lang.org/rust-by-example/",
+    ),
+    (
+        "edition-guide",
+        "The Rust Edition Guide",
+        "https://doc.rust-lang.org/edition-guide/",
+    ),
+];
+
+const MAX_EXCERPT: usize = 200;
+const MAX_KEYWORDS: usize = 8;
+const MAX_HEADING_LEVEL: usize = 3;
+
+/// Words too common to be searched for, besides the titles of the books
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "can", "cannot", "could", "do", "does",
+    "for", "from", "has", "have", "how", "if", "in", "into", "is", "it", "its", "may", "might",
+    "must", "no", "not", "now", "of", "on", "only", "or", "should", "so", "such", "than", "that",
+    "the", "their", "then", "there", "these", "this", "those", "to", "very", "was", "we", "were",
+    "what", "when", "where", "which", "while", "who", "why", "will", "with", "would", "you",
+    "your", "all", "any", "both", "each", "every", "more", "most", "much", "other", "same", "some",
+    "also", "always", "never", "really", "actually", "exactly", "just", "note", "example",
+];
+
+/// Elements left out of the sections together with their content
+const SKIPPED_TAGS: [&str; 5] = ["div", "span", "aside", "table", "nav"];
+/// Classes of the skipped elements, matched anywhere in their `class`
+const SKIPPED_CLASSES: [&str; 7] = [
+    "warning", "rule", "sidebar", "nav", "hidden", "ferris", "footnote",
+];
+const EMPHASIS_TAGS: [&str; 3] = ["em", "strong", "dfn"];
+
+static KEYWORD_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"^[A-Za-z_][A-Za-z0-9_:!#&'<>\[\]. -]*[A-Za-z0-9_!>\]]$").unwrap_unchecked()
+});
+static NUMBERED_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"^(chapter|listing|figure|table) [\d-]+$").unwrap_unchecked()
+});
+static SECTION_NUMBER_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"^(\d+\.)+\s*").unwrap_unchecked() });
+static PARAGRAPH_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"\n\n+").unwrap_unchecked() });
+
+/// A chapter linked from the table of contents
+#[derive(Debug, PartialEq, Eq)]
+struct Chapter {
+    depth: usize,
+    title: String,
+    href: String,
+}
+
+/// The chapters of a book in the order of its `toc.html`, without their section numbers
+fn chapters(toc: &str) -> Vec<Chapter> {
+    let mut chapters = Vec::new();
+    let mut depth = 0_usize;
+    let mut link: Option<(String, String)> = None;
+    let mut in_number = 0;
+
+    for token in html::tokens(toc) {
+        match &token {
+            Token::Start { name, .. } if name == "ol" => depth += 1,
+            Token::End(name) if name == "ol" => depth = depth.saturating_sub(1),
+            Token::Start { name, .. } if name == "a" => {
+                if let Some(href) = token.attr("href").filter(|href| !href.is_empty()) {
+                    link = Some((href.to_owned(), String::new()));
+                }
+            }
+            Token::End(name) if name == "a" => {
+                if let Some((href, title)) = link.take() {
+                    chapters.push(Chapter {
+                        depth: depth.saturating_sub(1),
+                        title: html::collapse_whitespace(&title).trim().to_owned(),
+                        href,
+                    });
+                }
+            }
+            // the section numbers of mdbook
+            Token::Start { name, .. } if name == "strong" && link.is_some() => in_number += 1,
+            Token::End(name) if name == "strong" && in_number > 0 => in_number -= 1,
+            Token::Text(text) if in_number == 0 => {
+                if let Some((_, title)) = &mut link {
+                    title.push_str(text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    chapters
+}
+
+/// A heading of a page with the text below it, the part before the first heading has level 0
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Section {
+    level: usize,
+    id: Option<String>,
+    title: String,
+    text: String,
+    /// Inline code and emphasized terms, the latter in lowercase
+    terms: Vec<String>,
+}
+
+/// The sections of the content of an mdbook page, leaving out code blocks, notes and sidebars
+fn sections(main: &str) -> Vec<Section> {
+    let mut sections = vec![Section::default()];
+    let mut heading: Option<Section> = None;
+    let mut pre = 0_usize;
+    let mut code: Option<String> = None;
+    let mut emphasis: Option<String> = None;
+    let mut skip = 0;
+
+    // the text goes to the title of an open heading, or below the last one outside code blocks
+    let emit = |text: &str, heading: &mut Option<Section>, sections: &mut Vec<Section>, pre| {
+        if let Some(heading) = heading {
+            heading.title.push_str(text);
+        } else if pre == 0
+            && let Some(section) = sections.last_mut()
+        {
+            section.text.push_str(text);
+        }
+    };
+
+    for token in html::tokens(main) {
+        match &token {
+            Token::Start { name, .. } if skip > 0 => {
+                if SKIPPED_TAGS.contains(&name.as_str()) {
+                    skip += 1;
+                }
+            }
+            Token::End(name) if skip > 0 => {
+                if SKIPPED_TAGS.contains(&name.as_str()) {
+                    skip -= 1;
+                }
+            }
+            Token::Text(_) if skip > 0 => {}
+
+            Token::Start { name, .. } => {
+                let class = token.attr("class").unwrap_or_default();
+                if ["div", "aside", "table", "nav"].contains(&name.as_str())
+                    && SKIPPED_CLASSES
+                        .iter()
+                        .any(|skipped| class.contains(skipped))
+                {
+                    skip = 1;
+                } else if let Some(level) = html::heading_level(name) {
+                    heading = Some(Section {
+                        level,
+                        id: token.attr("id").map(str::to_owned),
+                        ..Section::default()
+                    });
+                } else if name == "pre" {
+                    pre += 1;
+                } else if name == "code" && pre == 0 {
+                    code = Some(String::new());
+                    if heading.is_none() {
+                        emit("`", &mut heading, &mut sections, pre);
+                    }
+                } else if EMPHASIS_TAGS.contains(&name.as_str()) && heading.is_none() {
+                    emphasis = Some(String::new());
+                } else if name == "p" || name == "li" {
+                    emit("\n\n", &mut heading, &mut sections, pre);
+                }
+            }
+            Token::End(name) => {
+                if html::heading_level(name).is_some()
+                    && let Some(mut heading) = heading.take()
+                {
+                    heading.title = html::collapse_whitespace(&heading.title).trim().to_owned();
+                    sections.push(heading);
+                } else if name == "pre" {
+                    pre = pre.saturating_sub(1);
+                } else if name == "code" && pre == 0 {
+                    if let Some(term) = code.take()
+                        && heading.is_none()
+                    {
+                        emit("`", &mut heading, &mut sections, pre);
+                        let term = term.trim();
+                        if !term.is_empty()
+                            && let Some(section) = sections.last_mut()
+                        {
+                            section.terms.push(term.to_owned());
+                        }
+                    }
+                } else if EMPHASIS_TAGS.contains(&name.as_str())
+                    && let Some(term) = emphasis.take()
+                {
+                    let term = html::collapse_whitespace(&term).trim().to_lowercase();
+                    if !term.is_empty()
+                        && let Some(section) = sections.last_mut()
+                    {
+                        section.terms.push(term);
+                    }
+                }
+            }
+            Token::Text(text) => {
+                if let Some(code) = &mut code {
+                    code.push_str(text);
+                    if let Some(heading) = &mut heading {
+                        heading.title.push_str(text);
+                        continue;
+                    }
+                }
+                if let Some(emphasis) = &mut emphasis {
+                    emphasis.push_str(text);
+                }
+                emit(text, &mut heading, &mut sections, pre);
+            }
+        }
+    }
+
+    sections
+}
+
+/// The first paragraph of a section long enough to tell what it's about, shortened to a sentence
+/// or a word boundary
+fn excerpt(section: &Section) -> String {
+    for paragraph in PARAGRAPH_RE.split(&section.text) {
+        let mut paragraph = html::collapse_whitespace(paragraph)
+            .trim()
+            .replace("``", "");
+        if paragraph.chars().count() < 20
+            || paragraph.starts_with("Filename:")
+            || paragraph.starts_with("Listing ")
+        {
+            continue;
+        }
+        if paragraph.matches('`').count() % 2 == 1 {
+            paragraph = paragraph.replace('`', "");
+        }
+        if paragraph.chars().count() > MAX_EXCERPT {
+            let end = paragraph
+                .char_indices()
+                .nth(MAX_EXCERPT)
+                .map_or(paragraph.len(), |(i, _)| i);
+            let cut = &paragraph[..end];
+            let sentence = cut
+                .rfind(". ")
+                .filter(|&end| cut[..end].chars().count() > MAX_EXCERPT / 2);
+            paragraph = match sentence {
+                Some(end) => cut[..=end].to_owned(),
+                None => {
+                    let mut cut = cut
+                        .rfind(' ')
+                        .map_or(cut, |space| &cut[..space])
+                        .trim_end_matches([',', ';', ':']);
+                    if cut.matches('`').count() % 2 == 1 {
+                        cut = cut.rfind('`').map_or(cut, |tick| cut[..tick].trim_end());
+                    }
+                    format!("{cut}…")
+                }
+            };
+        }
+        return paragraph;
+    }
+    String::new()
+}
+
+fn is_stopword(word: &str) -> bool {
+    STOPWORDS.contains(&word)
+        || BOOKS
+            .iter()
+            .any(|(_, title, _)| title.to_lowercase() == word)
+}
+
+/// The first distinct terms of a section which aren't already in its title
+fn keywords(section: &Section, title: &str) -> Vec<String> {
+    let title = title.to_lowercase();
+    let mut keywords: Vec<String> = Vec::new();
+
+    for term in &section.terms {
+        let term = html::collapse_whitespace(term);
+        let term = term.trim().trim_matches(['.', ',', ':', ';']);
+        let key = term.to_lowercase();
+        let length = term.chars().count();
+        if term.is_empty()
+            || length > 30
+            || keywords.iter().any(|keyword| keyword.to_lowercase() == key)
+            || title.contains(&key)
+            || length < 3
+            || !KEYWORD_RE.is_match(term)
+            || is_stopword(&key)
+            || NUMBERED_RE.is_match(&key)
+        {
+            continue;
+        }
+        keywords.push(term.to_owned());
+        if keywords.len() == MAX_KEYWORDS {
+            break;
+        }
+    }
+
+    keywords
+}
+
+/// The lines of a chapter or heading in the asset
+fn entry(lines: &mut Vec<String>, depth: usize, title: &str, href: &str, section: &Section) {
+    let indent = "  ".repeat(depth);
+    let title = title.replace('[', "\\[").replace(']', "\\]");
+    lines.push(format!("{indent}- [{title}]({href})"));
+    let keywords = keywords(section, &title);
+    if !keywords.is_empty() {
+        lines.push(format!("{indent}  keywords: {}", keywords.join(", ")));
+    }
+    let excerpt = excerpt(section);
+    if !excerpt.is_empty() {
+        lines.push(format!("{indent}  {excerpt}"));
+    }
+}
+
+/// The asset of a book, with an entry for every chapter and heading
+fn render(docs: &Path, book: &str, title: &str, url: &str) -> io::Result<String> {
+    let dir = docs.join(book);
+    let toc = fs::read_to_string(dir.join("toc.html"))?;
+    let mut lines = vec![format!("---\ntitle: {title}\nurl: {url}\n---\n")];
+
+    for chapter in chapters(&toc) {
+        let path = dir.join(chapter.href.split('#').next().unwrap_or_default());
+        let Ok(page) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let mut sections =
+            html::main_content(&page).map_or_else(|| vec![Section::default()], sections);
+        let title = SECTION_NUMBER_RE.replace(&chapter.title, "");
+        let title = title.trim();
+
+        let mut intro = sections.remove(0);
+        // the first heading is the title of the page, its text is the intro
+        let top = sections
+            .first()
+            .is_some_and(|top| !top.title.is_empty())
+            .then(|| sections.remove(0));
+        // headings without an id can't be linked to
+        let headings = sections
+            .into_iter()
+            .filter(|section| {
+                section.id.as_deref().is_some_and(|id| !id.is_empty()) && !section.title.is_empty()
+            })
+            .collect::<Vec<_>>();
+        let top_level = match top {
+            Some(top) => {
+                intro.text.push_str(&top.text);
+                intro.terms.extend(top.terms);
+                top.level
+            }
+            None => {
+                let min = headings.iter().map(|section| section.level).min();
+                min.unwrap_or(1).saturating_sub(1)
+            }
+        };
+
+        entry(&mut lines, chapter.depth, title, &chapter.href, &intro);
+        for heading in &headings {
+            let relative = heading.level.saturating_sub(top_level);
+            if relative < 1 || heading.level > MAX_HEADING_LEVEL {
+                continue;
+            }
+            let href = format!(
+                "{}#{}",
+                chapter.href,
+                heading.id.as_deref().unwrap_or_default()
+            );
+            entry(
+                &mut lines,
+                chapter.depth + relative,
+                &heading.title,
+                &href,
+                heading,
+            );
+        }
+    }
+
+    Ok(format!("{}\n", lines.join("\n").trim_end()))
+}
+
+/// Every book in `assets` by the name of its file
+fn read_books(assets: &Path) -> io::Result<BTreeMap<String, String>> {
+    let mut books = BTreeMap::new();
+    for entry in fs::read_dir(assets)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "md") {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+            books.insert(name.to_owned(), fs::read_to_string(&path)?);
+        }
+    }
+    Ok(books)
+}
+
+/// The difference between the generated book index and the assets
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Report {
+    added: Vec<String>,
+    changed: Vec<String>,
+    removed: Vec<String>,
+}
+
+impl Report {
+    fn new(upstream: &BTreeMap<String, String>, assets: &BTreeMap<String, String>) -> Self {
+        let mut report = Self::default();
+        for (name, content) in upstream {
+            match assets.get(name) {
+                None => report.added.push(name.clone()),
+                Some(asset) if asset != content => report.changed.push(name.clone()),
+                Some(_) => {}
+            }
+        }
+        report.removed = assets
+            .keys()
+            .filter(|name| !upstream.contains_key(*name))
+            .cloned()
+            .collect();
+        report
+    }
+
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error + Send + Sync>> {
+    let mut write = false;
+    let mut paths = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--write" => write = true,
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let mut paths = paths.into_iter();
+    let Some(sysroot) = paths.next() else {
+        eprintln!("Usage: books <toolchain sysroot> [assets dir] [--write]");
+        return Ok(ExitCode::FAILURE);
+    };
+    let assets = paths.next().unwrap_or_else(|| PathBuf::from(ASSETS_DIR));
+
+    let docs = sysroot.join(DOCS_DIR);
+    let mut generated = BTreeMap::new();
+    for (book, title, url) in BOOKS {
+        generated.insert(book.to_owned(), render(&docs, book, title, url)?);
+    }
+    let existing = read_books(&assets)?;
+    let report = Report::new(&generated, &existing);
+
+    for (label, names) in [
+        ("added", &report.added),
+        ("changed", &report.changed),
+        ("removed", &report.removed),
+    ] {
+        println!("{} {label}: {}", names.len(), names.join(", "));
+    }
+
+    if write && !report.is_empty() {
+        for name in report.added.iter().chain(&report.changed) {
+            fs::write(assets.join(format!("{name}.md")), &generated[name])?;
+        }
+        for name in &report.removed {
+            fs::remove_file(assets.join(format!("{name}.md")))?;
+        }
+        println!("updated {}", assets.display());
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_toc() {
+        let toc = r#"<ol class="chapter"><li class="chapter-item"><a href="title-page.html">The Rust Programming Language</a></li>
+<li class="chapter-item"><a href="ch01-00-getting-started.html"><strong aria-hidden="true">1.</strong> Getting
+Started</a></li><li><ol class="section"><li class="chapter-item"><a href="ch01-01-installation.html"><strong aria-hidden="true">1.1.</strong> Installation</a></li></ol></li>
+<li class="part-title">Appendix</li></ol>"#;
+
+        assert_eq!(
+            chapters(toc),
+            [
+                Chapter {
+                    depth: 0,
+                    title: "The Rust Programming Language".to_owned(),
+                    href: "title-page.html".to_owned(),
+                },
+                Chapter {
+                    depth: 0,
+                    title: "Getting Started".to_owned(),
+                    href: "ch01-00-getting-started.html".to_owned(),
+                },
+                Chapter {
+                    depth: 1,
+                    title: "Installation".to_owned(),
+                    href: "ch01-01-installation.html".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_sections() {
+        let main = r##"<main><h1 id="installation"><a class="header" href="#installation">Installation</a></h1>
+<p>The first step is to install Rust with <code>rustup</code>, a <em>command line
+tool</em> for managing Rust versions.</p>
+<div class="warning"><p>Hidden</p></div>
+<pre><code>$ rustup update</code></pre>
+<h2 id="updating"><a class="header" href="#updating">Updating with <code>rustup</code></a></h2>
+<p>Run <code>rustup update</code>.</p></main>"##;
+        let sections = sections(main);
+
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[1].title, "Installation");
+        assert_eq!(
+            sections[1].terms,
+            ["rustup".to_owned(), "command line tool".to_owned()]
+        );
+        assert_eq!(
+            excerpt(&sections[1]),
+            "The first step is to install Rust with `rustup`, a command line tool for managing \
+             Rust versions."
+        );
+        assert_eq!(sections[2].id.as_deref(), Some("updating"));
+        assert_eq!(sections[2].title, "Updating with rustup");
+        assert_eq!(
+            keywords(&sections[2], &sections[2].title),
+            ["rustup update"]
+        );
+    }
+
+    #[test]
+    fn shortens_excerpts() {
+        let sentence = "A sentence which is long enough to be an excerpt on its own. ";
+        let section = Section {
+            text: sentence.repeat(5),
+            ..Section::default()
+        };
+        assert_eq!(excerpt(&section), sentence.repeat(3).trim_end());
+
+        let section = Section {
+            text: "word, ".repeat(40),
+            ..Section::default()
+        };
+        assert_eq!(excerpt(&section), format!("{}word…", "word, ".repeat(32)));
+    }
+
+    #[test]
+    fn picks_keywords() {
+        let section = Section {
+            terms: [
+                "Vec<T>",
+                "vec<t>",
+                "the",
+                "Listing 8-1",
+                "x",
+                "ownership",
+                "mod.rs",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            ..Section::default()
+        };
+
+        assert_eq!(
+            keywords(&section, "Understanding Ownership"),
+            ["Vec<T>", "mod.rs"]
+        );
+    }
+}
@@ -0,0 +1,496 @@
+// Reads the HTML of the toolchain docs for the asset generators, which include this file with
+// `#[path]`. It only understands as much HTML as mdbook and rustdoc write.
+
+// each generator only uses a part of it
+#![allow(dead_code)]
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Start {
+        name: String,
+        attrs: Vec<(String, String)>,
+    },
+    End(String),
+    Text(String),
+}
+
+/// Elements which never have an end tag
+const VOID: [&str; 6] = ["br", "hr", "img", "input", "link", "meta"];
+
+/// Elements whose content isn't HTML and is left out
+const RAW: [&str; 2] = ["script", "style"];
+
+impl Token {
+    pub fn is_start(&self, tag: &str) -> bool {
+        matches!(self, Token::Start { name, .. } if name == tag)
+    }
+
+    pub fn is_end(&self, tag: &str) -> bool {
+        matches!(self, Token::End(name) if name == tag)
+    }
+
+    /// The value of an attribute of a start tag
+    pub fn attr(&self, attr: &str) -> Option<&str> {
+        match self {
+            Token::Start { attrs, .. } => attrs
+                .iter()
+                .find(|(name, _)| name == attr)
+                .map(|(_, value)| value.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Whether a start tag has `class` among its classes
+    pub fn has_class(&self, class: &str) -> bool {
+        self.attr("class")
+            .is_some_and(|classes| classes.split_whitespace().any(|c| c == class))
+    }
+}
+
+/// The part of an mdbook page between `<main>` and `</main>`
+pub fn main_content(page: &str) -> Option<&str> {
+    let start = page.find("<main>")?;
+    let end = page[start..].find("</main>")?;
+    Some(&page[start..start + end])
+}
+
+/// Splits `html` into tags and text with the character references resolved. Void elements get an
+/// end tag right away, comments, declarations and the content of scripts and styles are dropped.
+pub fn tokens(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(open) = rest.find('<') else {
+            tokens.push(Token::Text(unescape(rest)));
+            break;
+        };
+        if open > 0 {
+            tokens.push(Token::Text(unescape(&rest[..open])));
+            rest = &rest[open..];
+        }
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+
+        let closing = rest[1..].starts_with('/');
+        let name_start = if closing { 2 } else { 1 };
+        if !rest[name_start..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            // a lone `<` is text
+            tokens.push(Token::Text("<".to_owned()));
+            rest = &rest[1..];
+            continue;
+        }
+
+        let Some(end) = tag_end(rest) else {
+            tokens.push(Token::Text(unescape(rest)));
+            break;
+        };
+        let tag = &rest[name_start..end];
+        rest = &rest[end + 1..];
+
+        let name_end = tag
+            .find(|c: char| c.is_whitespace() || c == '/')
+            .unwrap_or(tag.len());
+        let name = tag[..name_end].to_ascii_lowercase();
+        if closing {
+            tokens.push(Token::End(name));
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let attrs = attributes(tag[name_end..].trim_end_matches('/'));
+        tokens.push(Token::Start {
+            name: name.clone(),
+            attrs,
+        });
+
+        if RAW.contains(&name.as_str()) {
+            let end = format!("</{name}");
+            rest = rest.find(&end).map_or("", |i| &rest[i..]);
+        } else if self_closing || VOID.contains(&name.as_str()) {
+            tokens.push(Token::End(name));
+        }
+    }
+
+    tokens
+}
+
+/// The position of the `>` closing the tag at the start of `rest`, skipping quoted values
+fn tag_end(rest: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in rest.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return Some(i),
+            (None, _) => {}
+        }
+    }
+    None
+}
+
+fn attributes(mut rest: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return attrs;
+        }
+
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+
+        let Some(value) = rest.strip_prefix('=') else {
+            attrs.push((name, String::new()));
+            continue;
+        };
+        let value = value.trim_start();
+        let (raw, after) = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let end = value[1..].find(quote).map_or(value.len(), |end| end + 1);
+                (&value[1..end], value.get(end + 1..).unwrap_or_default())
+            }
+            _ => {
+                let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                (&value[..end], &value[end..])
+            }
+        };
+        attrs.push((name, unescape(raw)));
+        rest = after;
+    }
+}
+
+/// Resolves character references, unknown ones are kept as they are
+pub fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let resolved = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let reference = &rest[1..end];
+            let c = match reference.strip_prefix('#') {
+                Some(hex) if hex.starts_with(['x', 'X']) => {
+                    char::from_u32(u32::from_str_radix(&hex[1..], 16).ok()?)?
+                }
+                Some(decimal) => char::from_u32(decimal.parse().ok()?)?,
+                None => named(reference)?,
+            };
+            Some((c, end))
+        });
+        match resolved {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn named(reference: &str) -> Option<char> {
+    Some(match reference {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "rarr" => '→',
+        "larr" => '←',
+        "times" => '×',
+        "copy" => '©',
+        _ => return None,
+    })
+}
+
+/// How [`Markdown`] writes what the generators need
+pub struct Options {
+    /// The URL relative links are resolved against, they become plain text without one
+    pub base: Option<&'static str>,
+    /// Leaves out the lines of examples which rustdoc hides
+    pub skip_hidden: bool,
+    /// The markdown heading level of an HTML heading
+    pub heading: fn(usize) -> usize,
+}
+
+/// Converts the content of a docs page to the markdown discord understands
+pub struct Markdown {
+    options: Options,
+    out: String,
+    stack: Vec<String>,
+    /// The targets of the open links and where their text starts
+    links: Vec<(String, usize)>,
+    /// Where the text of the open blockquotes starts
+    quotes: Vec<usize>,
+    pre: bool,
+    /// The element whose content is left out, like hidden lines, an `h1` or footnotes, and how
+    /// deep it's nested in itself
+    skipped: Option<(String, usize)>,
+}
+
+impl Markdown {
+    pub fn new(options: Options) -> Self {
+        Self {
+            options,
+            out: String::new(),
+            stack: Vec::new(),
+            links: Vec::new(),
+            quotes: Vec::new(),
+            pre: false,
+            skipped: None,
+        }
+    }
+
+    pub fn push(&mut self, token: &Token) {
+        match token {
+            Token::Start { name, .. } => self.start(name, token),
+            Token::End(name) => self.end(name),
+            Token::Text(text) => self.text(text),
+        }
+    }
+
+    fn emit(&mut self, text: &str) {
+        if self.skipped.is_none() {
+            self.out.push_str(text);
+        }
+    }
+
+    fn skips(&self, name: &str, token: &Token) -> bool {
+        match name {
+            "h1" => true,
+            "span" => self.options.skip_hidden && token.has_class("boring"),
+            "ol" => token.has_class("footnote-definition"),
+            "sup" => token.has_class("footnote-reference"),
+            _ => false,
+        }
+    }
+
+    fn in_heading(&self) -> bool {
+        self.stack.iter().any(|tag| heading_level(tag).is_some())
+    }
+
+    fn start(&mut self, name: &str, token: &Token) {
+        self.stack.push(name.to_owned());
+        if let Some((skipped, depth)) = &mut self.skipped {
+            if skipped == name {
+                *depth += 1;
+            }
+            return;
+        }
+        if self.skips(name, token) {
+            self.skipped = Some((name.to_owned(), 1));
+            return;
+        }
+
+        match name {
+            "p" => self.emit("\n\n"),
+            "pre" => self.pre = true,
+            "code" if self.pre => {
+                let lang = token
+                    .attr("class")
+                    .and_then(|classes| classes.split_whitespace().next())
+                    .map(|class| class.trim_start_matches("language-"))
+                    .unwrap_or_default();
+                self.emit(&format!("\n\n```{lang}\n"));
+            }
+            "code" => self.emit("`"),
+            "a" => {
+                let href = token.attr("href").unwrap_or_default().to_owned();
+                self.links.push((href, self.out.len()));
+                if !self.in_heading() {
+                    self.emit("[");
+                }
+            }
+            "ul" | "ol" => self.emit("\n"),
+            "li" => self.emit("\n- "),
+            "em" | "i" => self.emit("*"),
+            "strong" | "b" => self.emit("**"),
+            "blockquote" => {
+                self.emit("\n\n");
+                self.quotes.push(self.out.len());
+            }
+            "hr" => self.emit("\n\n"),
+            _ => {
+                if let Some(level) = heading_level(name) {
+                    let hashes = "#".repeat((self.options.heading)(level));
+                    self.emit(&format!("\n\n{hashes} "));
+                }
+            }
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        while let Some(open) = self.stack.pop() {
+            if open == name {
+                break;
+            }
+        }
+        if let Some((skipped, depth)) = &mut self.skipped {
+            if skipped == name {
+                *depth -= 1;
+                if *depth == 0 {
+                    self.skipped = None;
+                }
+            }
+            return;
+        }
+
+        match name {
+            "code" if self.pre => {
+                if !self.out.ends_with('\n') {
+                    self.emit("\n");
+                }
+                self.emit("```");
+            }
+            "code" => self.emit("`"),
+            "pre" => self.pre = false,
+            "a" => {
+                let Some((href, start)) = self.links.pop() else {
+                    return;
+                };
+                if self.in_heading() {
+                    return;
+                }
+                match self.resolve(&href) {
+                    Some(url) => self.emit(&format!("]({url})")),
+                    None => {
+                        // links within the page or without a base are only kept as text
+                        self.out.remove(start);
+                    }
+                }
+            }
+            "blockquote" => {
+                let start = self.quotes.pop().unwrap_or(self.out.len());
+                let quoted = self.out.split_off(start);
+                let quoted = quoted
+                    .trim()
+                    .lines()
+                    .map(|line| format!("> {line}").trim_end().to_owned())
+                    .collect::<Vec<_>>();
+                self.out.push_str(&quoted.join("\n"));
+            }
+            "em" | "i" => self.emit("*"),
+            "strong" | "b" => self.emit("**"),
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.pre {
+            self.emit(text);
+        } else if self.stack.iter().any(|tag| tag == "li") {
+            self.emit(&collapse_whitespace(text));
+        } else {
+            self.emit(&join_lines(text));
+        }
+    }
+
+    /// The absolute URL of a link, if it leads outside of the page
+    fn resolve(&self, href: &str) -> Option<String> {
+        if href.is_empty() || href.starts_with('#') {
+            return None;
+        }
+        if href.starts_with("http") {
+            return Some(href.to_owned());
+        }
+
+        let mut url = self.options.base?.trim_end_matches('/').to_owned();
+        let mut rest = href;
+        loop {
+            if let Some(after) = rest.strip_prefix("./") {
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix("../") {
+                url.truncate(url.rfind('/').unwrap_or(url.len()));
+                rest = after;
+            } else {
+                break;
+            }
+        }
+        Some(format!("{url}/{rest}"))
+    }
+
+    /// The markdown with trailing whitespace and runs of blank lines removed
+    pub fn finish(self) -> String {
+        let mut text = String::new();
+        let mut blank = 0;
+        for line in self.out.lines() {
+            let line = line.trim_end_matches([' ', '\t']);
+            if line.is_empty() {
+                blank += 1;
+                continue;
+            }
+            if !text.is_empty() {
+                text.push_str(if blank > 0 { "\n\n" } else { "\n" });
+            }
+            blank = 0;
+            text.push_str(line);
+        }
+        text.trim().to_owned()
+    }
+}
+
+pub fn heading_level(tag: &str) -> Option<usize> {
+    match tag.as_bytes() {
+        [b'h', level @ b'1'..=b'6'] => Some(usize::from(level - b'0')),
+        _ => None,
+    }
+}
+
+/// Line breaks in running text, with the whitespace around them, become a single space
+fn join_lines(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, line) in text.split('\n').enumerate() {
+        if i == 0 {
+            out.push_str(line);
+        } else {
+            out.truncate(out.trim_end().len());
+            out.push(' ');
+            out.push_str(line.trim_start());
+        }
+    }
+    out
+}
+
+pub fn collapse_whitespace(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !space {
+                out.push(' ');
+            }
+            space = true;
+        } else {
+            out.push(c);
+            space = false;
+        }
+    }
+    out
+}
@@ -1,4 +1,22 @@
-// Converts error code markdown files to markdown supported by discord
+// Syncs the error code explanations in `assets/error_codes` with an upstream `rustc_error_codes`
+// checkout, converting them to markdown supported by discord
+//
+// Usage: error_codes <rustc_error_codes dir> [assets dir] [--write]
+//
+// Without `--write` only the added, changed and removed codes are reported.
+
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+const ASSETS_DIR: &str = "assets/error_codes";
+
+/// Discord's limits, see `src/common/split_content.rs`
+const EMBED_DESCRIPTION_SIZE: usize = 4096;
+const EMBED_FIELD_SIZE: usize = 1024;
 
 fn transform_text_general(input: &str) -> String {
     let mut output = String::new();
@@ -86,16 +104,259 @@ fn transform_text_general(input: &str) -> String {
     output
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    use std::fs;
+/// The difference between the converted upstream explanations and the assets
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Report {
+    added: Vec<String>,
+    changed: Vec<String>,
+    removed: Vec<String>,
+}
 
-    let file = "E0801";
+impl Report {
+    fn new(upstream: &BTreeMap<String, String>, assets: &BTreeMap<String, String>) -> Self {
+        let mut report = Self::default();
+        for (code, content) in upstream {
+            match assets.get(code) {
+                None => report.added.push(code.clone()),
+                Some(asset) if asset != content => report.changed.push(code.clone()),
+                Some(_) => {}
+            }
+        }
+        report.removed = assets
+            .keys()
+            .filter(|code| !upstream.contains_key(*code))
+            .cloned()
+            .collect();
+        report
+    }
 
-    let content = fs::read_to_string(format!("error_codes/{file}.md"))?;
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
 
-    let transformed = transform_text_general(&content);
+/// Everything in a converted explanation which doesn't fit the embeds `/explain` sends
+fn problems(content: &str) -> Vec<String> {
+    let mut problems = Vec::new();
 
-    fs::write(format!("error_codes/{file}_transformed.md"), transformed)?;
+    let summary = summary(content);
+    if summary.len() > EMBED_FIELD_SIZE {
+        problems.push(format!(
+            "summary is {} bytes, an embed field holds {EMBED_FIELD_SIZE}",
+            summary.len()
+        ));
+    }
+
+    let mut in_code_block = false;
+    let mut block_length = 0;
+    for (number, line) in content.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            if in_code_block && block_length > EMBED_DESCRIPTION_SIZE {
+                problems.push(format!(
+                    "code block ending on line {} is {block_length} bytes and is split across pages",
+                    number + 1
+                ));
+            }
+            in_code_block = !in_code_block;
+            block_length = 0;
+        } else if in_code_block {
+            block_length += line.len() + 1;
+        }
 
-    Ok(())
+        if line.len() > EMBED_DESCRIPTION_SIZE {
+            problems.push(format!(
+                "line {} is {} bytes, an embed description holds {EMBED_DESCRIPTION_SIZE}",
+                number + 1,
+                line.len()
+            ));
+        }
+    }
+    if in_code_block {
+        problems.push("code block is never closed".to_owned());
+    }
+
+    problems
+}
+
+/// The first paragraph, which `/explain` shows in an embed field
+fn summary(content: &str) -> String {
+    content
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty() || line.starts_with('#'))
+        .take_while(|line| !line.is_empty() && !line.starts_with("```"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_code(name: &str) -> bool {
+    name.len() == 5 && name.starts_with('E') && name[1..].bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Every `E####.md` in `dir` by code
+fn read_codes(dir: &Path) -> io::Result<BTreeMap<String, String>> {
+    let mut codes = BTreeMap::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "md") {
+            continue;
+        }
+        if let Some(code) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .filter(|stem| is_code(stem))
+        {
+            codes.insert(code.to_owned(), fs::read_to_string(&path)?);
+        }
+    }
+    Ok(codes)
+}
+
+/// The explanations live in `src/error_codes` of the `rustc_error_codes` crate, either may be
+/// passed
+fn upstream_dir(path: PathBuf) -> PathBuf {
+    let nested = path.join("src/error_codes");
+    if nested.is_dir() { nested } else { path }
+}
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error + Send + Sync>> {
+    let mut write = false;
+    let mut paths = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--write" => write = true,
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let mut paths = paths.into_iter();
+    let Some(upstream) = paths.next().map(upstream_dir) else {
+        eprintln!("Usage: error_codes <rustc_error_codes dir> [assets dir] [--write]");
+        return Ok(ExitCode::FAILURE);
+    };
+    let assets = paths.next().unwrap_or_else(|| PathBuf::from(ASSETS_DIR));
+
+    let converted = read_codes(&upstream)?
+        .into_iter()
+        .map(|(code, content)| {
+            let content = transform_text_general(&content);
+            (code, content)
+        })
+        .collect::<BTreeMap<_, _>>();
+    if converted.is_empty() {
+        return Err(format!("no error codes in {}", upstream.display()).into());
+    }
+    let report = Report::new(&converted, &read_codes(&assets)?);
+
+    for (label, codes) in [
+        ("added", &report.added),
+        ("changed", &report.changed),
+        ("removed", &report.removed),
+    ] {
+        println!("{} {label}: {}", codes.len(), codes.join(", "));
+    }
+
+    let mut valid = true;
+    for (code, content) in &converted {
+        for problem in problems(content) {
+            eprintln!("{code}: {problem}");
+            valid = false;
+        }
+    }
+
+    if write && !report.is_empty() {
+        for code in report.added.iter().chain(&report.changed) {
+            fs::write(assets.join(format!("{code}.md")), &converted[code])?;
+        }
+        for code in &report.removed {
+            fs::remove_file(assets.join(format!("{code}.md")))?;
+        }
+        println!("updated {}", assets.display());
+    }
+
+    Ok(if valid {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_fences() {
+        let input = "Erroneous code example:\n\n```compile_fail,E0382\nlet y = x;\n```\n\n```\nfn main() {}\n```";
+        assert_eq!(
+            transform_text_general(input),
+            "Erroneous code example:\n\n```rust\nlet y = x;\n```\n\n```\nfn main() {}\n```\n"
+        );
+    }
+
+    #[test]
+    fn collapses_blank_lines_outside_code() {
+        let input = "First.\n\n\n\nSecond.   \n```\na\n\n\nb\n```\n\n\n";
+        assert_eq!(
+            transform_text_general(input),
+            "First.\n\nSecond.\n```\na\n\n\nb\n```\n"
+        );
+    }
+
+    #[test]
+    fn wraps_reference_links() {
+        let input = "See [the book].\n\n[the book]: https://doc.rust-lang.org/book/\n[not a link]: maybe\n[local]: ../E0001.md";
+        assert_eq!(
+            transform_text_general(input),
+            "See [the book].\n\n[the book]: <https://doc.rust-lang.org/book/>\n[not a link]: maybe\n[local]: ../E0001.md\n"
+        );
+    }
+
+    #[test]
+    fn reports_differences() {
+        let codes = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(code, content)| (code.to_string(), content.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let upstream = codes(&[("E0001", "same"), ("E0002", "new text"), ("E0004", "added")]);
+        let assets = codes(&[("E0001", "same"), ("E0002", "old text"), ("E0003", "gone")]);
+
+        assert_eq!(
+            Report::new(&upstream, &assets),
+            Report {
+                added: vec!["E0004".to_owned()],
+                changed: vec!["E0002".to_owned()],
+                removed: vec!["E0003".to_owned()],
+            }
+        );
+        assert!(Report::new(&upstream, &upstream).is_empty());
+    }
+
+    #[test]
+    fn validates_embed_limits() {
+        assert!(problems("A summary.\n\n```rust\nlet x = 1;\n```\n").is_empty());
+
+        let long_summary = "word ".repeat(300);
+        assert!(problems(&long_summary)[0].starts_with("summary is 1499 bytes"));
+
+        let long_block = format!("Summary.\n\n```rust\n{}```\n", "let x = 1;\n".repeat(400));
+        assert_eq!(
+            problems(&long_block),
+            ["code block ending on line 404 is 4400 bytes and is split across pages"]
+        );
+        assert_eq!(
+            problems("Summary.\n\n```rust\nlet x = 1;\n"),
+            ["code block is never closed"]
+        );
+    }
+
+    #[test]
+    fn assets_fit_the_embeds() {
+        let assets = read_codes(Path::new(ASSETS_DIR)).unwrap();
+        assert!(assets.contains_key("E0382"));
+        for (code, content) in &assets {
+            assert_eq!(problems(content), Vec::<String>::new(), "{code}");
+        }
+    }
 }
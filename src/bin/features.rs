@@ -0,0 +1,1599 @@
+// Syncs the feature docs in `assets/features` with a nightly toolchain. Unstable features are
+// documented by its Unstable Book, stable library features by the `#[stable]` attributes of its
+// standard library sources, and library features list the items carrying their attributes with the
+// first example of their docs.
+//
+// Usage: features <toolchain sysroot> [assets dir] [--write]
+//
+// The toolchain needs the rust-docs component, `rustc +nightly --print sysroot` prints where it is
+// installed. Stable language features and removed features aren't in the docs, their assets are
+// written by hand and kept, as are those of the library features whose items are written by macros
+// (`HAND_WRITTEN`). Without `--write` only the added, changed and removed features are reported.
+
+#[path = "common/html.rs"]
+mod html;
+
+use html::{Markdown, Options};
+use regex::{Captures, Regex};
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+    sync::LazyLock,
+};
+
+const ASSETS_DIR: &str = "assets/features";
+
+/// The docs of the toolchain, relative to the sysroot
+const DOCS_DIR: &str = "share/doc/rust/html";
+
+/// The directories of the Unstable Book by feature kind with the URL their links are relative to
+const BOOK_DIRS: [(&str, &str); 2] = [
+    (
+        "language",
+        "https://doc.rust-lang.org/nightly/unstable-book/language-features/",
+    ),
+    (
+        "library",
+        "https://doc.rust-lang.org/nightly/unstable-book/library-features/",
+    ),
+];
+
+/// The crates of the standard library whose sources are read
+const CRATES: [&str; 5] = ["core", "alloc", "std", "proc_macro", "std_detect"];
+
+/// Library features whose items are implemented for many types by macros, their assets list the
+/// items for a few of them
+const HAND_WRITTEN: [&str; 19] = [
+    "array_default",
+    "array_tuple_conv",
+    "atomic_min_max",
+    "bool_try_from_int",
+    "cmp_os_str",
+    "cmp_path",
+    "float_from_bool",
+    "from_bool",
+    "integer_exp_format",
+    "no_more_cas",
+    "nz_int_conv",
+    "nzint_try_from_nzint_conv",
+    "partialeq_path",
+    "saturating_iter_arith",
+    "token_stream_extend_ts_items",
+    "vec_deque_partial_eq_slice",
+    "wrapping_iter_arith",
+    "wrapping_ref_ops",
+    "rust1",
+];
+
+/// Items listed under `## APIs` before the rest is only counted
+const MAX_APIS: usize = 30;
+const MAX_DESCRIPTION: usize = 300;
+
+/// Written in the sources for the version being released
+const CURRENT_RUSTC_VERSION: &str = "CURRENT_RUSTC_VERSION";
+
+static TRACKING_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"The tracking issue for this feature is:? (?:\[?#?(\d+)\]?(?:\([^)]*\))?|None)\.?")
+        .unwrap_unchecked()
+});
+static INTERNAL_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(
+        r"This feature has no tracking issue,? and is therefore (?:likely )?internal to the compiler,? not being intended for general use\.?|This feature is internal to the Rust compiler and is not intended for general use\.?",
+    )
+    .unwrap_unchecked()
+});
+
+static LINE_NUMBER_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"^<a href=#\d+ id=\d+ data-nosnippet>\d+</a>").unwrap_unchecked()
+});
+static TAG_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"<[^>]+>").unwrap_unchecked() });
+
+static STABILITY_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"#(!?)\[(stable|unstable|rustc_const_stable|rustc_const_unstable)\((.*?)\)\]")
+        .unwrap_unchecked()
+});
+static FEATURE_ARG_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r#"feature\s*=\s*"([A-Za-z0-9_]+)""#).unwrap_unchecked()
+});
+static SINCE_ARG_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r#"since\s*=\s*"([^"]+)""#).unwrap_unchecked() });
+static ISSUE_ARG_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r#"issue\s*=\s*"(\d+)""#).unwrap_unchecked() });
+static VERSION_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"^(\d+\.\d+\.\d+|CURRENT_RUSTC_VERSION)$").unwrap_unchecked()
+});
+
+static ITEM_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(
+        r#"^(?:pub(?:\([^)]*\))?\s+)?(?:(?:const|unsafe|async|extern "[^"]*"|default|safe|auto)\s+)*(fn|struct|enum|trait|type|union|mod|const|static|macro_rules!|macro)\s+(\$?[A-Za-z_][A-Za-z0-9_]*)"#,
+    )
+    .unwrap_unchecked()
+});
+static IMPL_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"^(?:unsafe\s+)?impl\b").unwrap_unchecked() });
+static USE_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"^(?:pub(?:\([^)]*\))?\s+)?use\s+([^;]+);?").unwrap_unchecked()
+});
+static MEMBER_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"^(?:pub\s+)?([A-Za-z_][A-Za-z0-9_]*)\b").unwrap_unchecked()
+});
+static WHERE_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"\s+where\b").unwrap_unchecked() });
+static SELF_QUALIFIERS_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"^(?:mut |const |'\w+ )+").unwrap_unchecked() });
+static SELF_NAME_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"^(?:[a-z_]+::)*([A-Za-z_][A-Za-z0-9_]*|\[.*\]|\(.*\))").unwrap_unchecked()
+});
+
+static DOC_ATTR_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"^#\[doc\s*=\s*(.*)\]$").unwrap_unchecked() });
+static STRING_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r#"^"((?:[^"\\]|\\.)*)""#).unwrap_unchecked() });
+static STRINGIFY_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"^stringify!\(\$(?:SelfT|ActualT|Ty|T)\)").unwrap_unchecked()
+});
+
+static MACRO_RULES_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"^\s*macro_rules!\s+(\w+)").unwrap_unchecked() });
+static MACRO_PATTERN_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"\{\s*\((.*?)\)\s*=>").unwrap_unchecked() });
+static META_PARAM_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"\$(\w+):meta").unwrap_unchecked() });
+static META_ATTR_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"^#\[\$(\w+)\]$").unwrap_unchecked() });
+static MACRO_CALL_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"^(\w+)!\s*\{$").unwrap_unchecked() });
+static META_ARG_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"^(cfg|stable|rustc_const_stable|unstable|rustc_const_unstable)\((.*)\),?$")
+        .unwrap_unchecked()
+});
+static MACRO_TYPE_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"^[a-z0-9]+\s+([A-Z][A-Za-z0-9]*)$").unwrap_unchecked()
+});
+static CONVERSION_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(
+        r"^(?:impl_from|impl_from_bool|impl_try_from\w*)!\s*\(\s*([^=,]+?)\s*=>\s*([^,]+?)\s*,",
+    )
+    .unwrap_unchecked()
+});
+static SLICE_EQ_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"^__impl_slice_eq1!\s*\{\s*(?:const,\s*)?(?:\[[^\]]*\]\s*)?(.*?)#\[")
+        .unwrap_unchecked()
+});
+static MACRO_NAME_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"@MACRO_NAME:\s*(\w+)").unwrap_unchecked() });
+static TARGET_FEATURE_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r#"^@FEATURE:.*\]\s*\w+\s*:\s*"([^"]+)""#).unwrap_unchecked()
+});
+static VEC_ALLOCATOR_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"Vec<(\w), A>").unwrap_unchecked() });
+
+static METAVARIABLE_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"\$(?:SelfT|ActualT|Int|Ty|T|t|ty|int_type|Self)\b").unwrap_unchecked()
+});
+static RELATIVE_PATH_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"\b(?:crate|self|super)::(?:[a-z_][a-z0-9_]*::)*").unwrap_unchecked()
+});
+static CONST_IMPL_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"^(impl(?:<.*?>)?) const ").unwrap_unchecked() });
+static IMPL_GENERICS_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"^impl(?:<.*?>)? ").unwrap_unchecked() });
+static BLANK_LINES_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"\n{3,}").unwrap_unchecked() });
+static WHITESPACE_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"\s+").unwrap_unchecked() });
+
+static CODE_LINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"\[(`[^`]*`)\]\([^)]*\)").unwrap_unchecked() });
+static INLINE_LINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"\[([^\]]+)\]\(([^)]*)\)").unwrap_unchecked() });
+static REFERENCE_LINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"\[([^\]]+)\]\[[^\]]*\]").unwrap_unchecked() });
+static SHORTCUT_LINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"\[([^\]]+)\]([^(\[]|$)").unwrap_unchecked() });
+
+/// A feature page of the Unstable Book
+#[derive(Debug, PartialEq, Eq)]
+struct Page {
+    kind: &'static str,
+    issue: Option<u32>,
+    internal: bool,
+    text: String,
+}
+
+/// Reads a page of the Unstable Book, without its title and the note on its tracking issue
+fn book_page(kind: &'static str, base: &'static str, page: &str) -> Page {
+    let mut markdown = Markdown::new(Options {
+        base: Some(base),
+        skip_hidden: true,
+        heading: |level| level.saturating_sub(1).max(2),
+    });
+    for token in html::tokens(html::main_content(page).unwrap_or_default()) {
+        markdown.push(&token);
+    }
+    let text = markdown.finish();
+
+    let issue = TRACKING_RE
+        .captures(&text)
+        .and_then(|tracking| tracking.get(1)?.as_str().parse().ok());
+    let internal = INTERNAL_RE.is_match(&text);
+    let text = TRACKING_RE.replace(&text, "");
+    let text = INTERNAL_RE.replace(&text, "");
+    let text = BLANK_LINES_RE.replace_all(text.trim(), "\n\n").into_owned();
+
+    Page {
+        kind,
+        issue,
+        internal,
+        text,
+    }
+}
+
+/// A stability attribute like `#[stable(feature = "rust1", since = "1.0.0")]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Stability {
+    stable: bool,
+    /// `rustc_const_stable` and `rustc_const_unstable`, which are about calls in constants
+    in_const: bool,
+    feature: String,
+    since: Option<String>,
+    issue: Option<u32>,
+}
+
+impl Stability {
+    fn parse(attr: &str, args: &str) -> Option<Self> {
+        let stable = !attr.ends_with("unstable");
+        let since = SINCE_ARG_RE.captures(args).map(|since| since[1].to_owned());
+        if stable && since.is_none() {
+            return None;
+        }
+        Some(Self {
+            stable,
+            in_const: attr.starts_with("rustc_const"),
+            feature: FEATURE_ARG_RE.captures(args)?[1].to_owned(),
+            since,
+            issue: ISSUE_ARG_RE
+                .captures(args)
+                .and_then(|issue| issue[1].parse().ok())
+                .filter(|&issue| issue != 0),
+        })
+    }
+
+    /// The stability attributes anywhere in `line`, or only the inner ones like `#![stable(..)]`
+    fn find(line: &str, inner: bool) -> Vec<Self> {
+        STABILITY_RE
+            .captures_iter(line)
+            .filter(|attr| attr[1].is_empty() != inner)
+            .filter_map(|attr| Self::parse(&attr[2], &attr[3]))
+            .collect()
+    }
+}
+
+/// An item of the standard library with a stability attribute
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Item {
+    /// `fn`, `struct`, `impl`, `use`, `variant`, ..
+    kind: String,
+    /// Like `Vec::into_raw_parts`, the whole header for impls
+    name: String,
+    /// The lines of its docs, `None` for a `#[doc = ..]` which can't be evaluated
+    doc: Vec<Option<String>>,
+    in_const: bool,
+    /// A member of an impl which has the attribute instead of the member
+    inherited: bool,
+}
+
+/// The items of a library feature
+#[derive(Debug, Default)]
+struct LibraryFeature {
+    since: Option<String>,
+    issue: Option<u32>,
+    items: Vec<Item>,
+}
+
+/// The library features of the standard library sources, stable and unstable apart
+#[derive(Debug, Default)]
+struct Sources {
+    stable: BTreeMap<String, LibraryFeature>,
+    unstable: BTreeMap<String, LibraryFeature>,
+}
+
+impl Sources {
+    fn add(&mut self, stability: &Stability, item: &Item) {
+        let features = if stability.stable {
+            &mut self.stable
+        } else {
+            &mut self.unstable
+        };
+        let feature = features.entry(stability.feature.clone()).or_default();
+        feature.since = feature.since.take().or_else(|| stability.since.clone());
+        feature.issue = feature.issue.or(stability.issue);
+        feature.items.push(Item {
+            in_const: stability.in_const,
+            ..item.clone()
+        });
+    }
+
+    /// Reads every source file of the standard library crates in the rust-docs
+    fn read(docs: &Path) -> io::Result<Self> {
+        let mut sources = Self::default();
+        for krate in CRATES {
+            let dir = docs.join("src").join(krate);
+            let mut files = Vec::new();
+            source_files(&dir, &mut files)?;
+            // in the order of their paths as text, so `cell.rs` comes before `cell/once.rs`
+            files.sort_by_key(|path| path.to_string_lossy().into_owned());
+            for path in files {
+                let relative = path.strip_prefix(&dir).unwrap_or(&path);
+                let module = module_path(krate, relative);
+                let page = fs::read_to_string(&path)?;
+                sources.scan(&module, &source_lines(&page));
+            }
+        }
+        Ok(sources)
+    }
+
+    /// Collects the items with stability attributes of a source file, following the items of
+    /// impls, traits, enums and structs, and the `#[$meta]` parameters of macros
+    fn scan(&mut self, module: &str, lines: &[String]) {
+        let file = module.rsplit("::").next().unwrap_or_default();
+        let self_ty = match file {
+            "int_macros" => "i32",
+            "uint_macros" => "u32",
+            _ => "T",
+        };
+        let macros = macro_params(lines);
+
+        let mut doc = Vec::new();
+        let mut attrs = Vec::<Stability>::new();
+        let mut stack = Vec::<Scope>::new();
+        let mut module_doc = Vec::new();
+        let mut pending_attr: Option<String> = None;
+        let mut invocation: Option<&str> = None;
+        let mut macro_args = Vec::new();
+        let mut detection_macro = "is_feature_detected".to_owned();
+
+        for (i, line) in lines.iter().enumerate() {
+            let mut s = line.trim().to_owned();
+            let indent = line.len() - line.trim_start().len();
+            if let Some(mut attr) = pending_attr.take() {
+                attr.push(' ');
+                attr.push_str(&s);
+                if !balanced(&attr) {
+                    pending_attr = Some(attr);
+                    continue;
+                }
+                s = attr;
+            } else if (s.starts_with("#[") || s.starts_with("#![")) && !balanced(&s) {
+                pending_attr = Some(s);
+                continue;
+            }
+
+            if s.is_empty() || s.starts_with("////") {
+                continue;
+            }
+            if let Some(text) = s.strip_prefix("///") {
+                doc.push(Some(doc_line(text)));
+                continue;
+            }
+            if is_doc_attr(&s) {
+                doc.push(doc_attr(&s, self_ty));
+                continue;
+            }
+            if let Some(text) = s.strip_prefix("//!") {
+                module_doc.push(Some(doc_line(text)));
+                continue;
+            }
+            if s.starts_with("#![") {
+                let item = Item {
+                    kind: "mod".to_owned(),
+                    name: module.to_owned(),
+                    doc: module_doc.clone(),
+                    in_const: false,
+                    inherited: false,
+                };
+                for stability in Stability::find(&s, true) {
+                    self.add(&stability, &item);
+                }
+                continue;
+            }
+
+            if let Some(call) = MACRO_CALL_RE.captures(&s)
+                && let Some((name, _)) = macros.get_key_value(&call[1])
+            {
+                invocation = Some(name);
+                macro_args.clear();
+                continue;
+            }
+            if let Some(name) = invocation {
+                if let Some(arg) = META_ARG_RE.captures(&s) {
+                    macro_args.push((arg[1].to_owned(), arg[2].to_owned()));
+                    continue;
+                }
+                if let Some(ty) = MACRO_TYPE_RE.captures(&s) {
+                    let (params, items) = &macros[name];
+                    for (param, (attr, args)) in params.iter().zip(&macro_args) {
+                        let Some(stability) = Stability::parse(attr, args) else {
+                            continue;
+                        };
+                        for item in items.get(param).into_iter().flatten() {
+                            let name = match item.name.starts_with('$') {
+                                true => ty[1].to_owned(),
+                                false => format!("{}::{}", &ty[1], item.name),
+                            };
+                            self.add(
+                                &stability,
+                                &Item {
+                                    name,
+                                    ..item.clone()
+                                },
+                            );
+                        }
+                    }
+                    invocation = None;
+                    continue;
+                }
+                if s == "}" {
+                    invocation = None;
+                }
+            }
+
+            if let Some(name) = MACRO_NAME_RE.captures(&s) {
+                detection_macro = name[1].to_owned();
+            }
+            if !s.starts_with('#') && !s.starts_with("//") && s.contains("#[") {
+                let found = Stability::find(&s, false);
+                if !found.is_empty() {
+                    // attributes passed to macros implementing traits or detecting target features
+                    let (kind, head) = if let Some(target_feature) = TARGET_FEATURE_RE.captures(&s)
+                    {
+                        let name = format!("{detection_macro}!(\"{}\")", &target_feature[1]);
+                        ("other", name)
+                    } else if let Some(conversion) = CONVERSION_RE.captures(&s) {
+                        let head = format!("impl From<{}> for {}", &conversion[1], &conversion[2]);
+                        ("impl", head)
+                    } else if let Some(eq) = SLICE_EQ_RE.captures(&s) {
+                        let args = split_args(&eq[1])
+                            .iter()
+                            .map(|arg| VEC_ALLOCATOR_RE.replace_all(arg, "Vec<$1>").into_owned())
+                            .collect::<Vec<_>>();
+                        let rhs = args
+                            .get(1)
+                            .map_or("", |arg| arg.split(" where").next().unwrap_or_default());
+                        ("impl", format!("impl PartialEq<{rhs}> for {}", args[0]))
+                    } else {
+                        ("impl", s.clone())
+                    };
+                    let item = Item {
+                        kind: kind.to_owned(),
+                        name: head,
+                        doc: Vec::new(),
+                        in_const: false,
+                        inherited: false,
+                    };
+                    for stability in &found {
+                        self.add(stability, &item);
+                    }
+                    doc.clear();
+                    attrs.clear();
+                    continue;
+                }
+            }
+            if s.starts_with("#[") {
+                attrs.extend(Stability::find(&s, false).into_iter().filter(|stability| {
+                    stability
+                        .since
+                        .as_deref()
+                        .is_none_or(|since| VERSION_RE.is_match(since))
+                }));
+                continue;
+            }
+            if s.starts_with("//") {
+                continue;
+            }
+
+            // leave finished impl and trait blocks
+            while let Some(scope) = stack.last()
+                && (indent < scope.indent || indent == scope.indent && s.starts_with('}'))
+            {
+                stack.pop();
+            }
+            if s.starts_with('}') {
+                doc.clear();
+                attrs.clear();
+                continue;
+            }
+            let scope = stack.last();
+            let is_impl = IMPL_RE.is_match(&s);
+            let item = ITEM_RE.captures(&s);
+
+            if !is_impl && item.is_none() {
+                if !attrs.is_empty() {
+                    // re-exports, enum variants, fields and macro invocations
+                    let member = MEMBER_RE.captures(&s);
+                    let (kind, name) = match (USE_RE.captures(&s), scope, member) {
+                        (Some(used), _, _) => ("use", used[1].trim().to_owned()),
+                        (None, Some(scope), Some(member)) if scope.kind == "enum" => {
+                            ("variant", format!("{}::{}", scope.name, &member[1]))
+                        }
+                        (None, Some(scope), Some(member)) if scope.kind == "struct" => {
+                            ("field", format!("{}::{}", scope.name, &member[1]))
+                        }
+                        _ => (
+                            "other",
+                            s.trim_end_matches(['{', ';', ',']).trim().to_owned(),
+                        ),
+                    };
+                    // the trait of a macro implementing it for a list of types
+                    let (kind, name) = match name.strip_suffix(" for") {
+                        Some(implemented) if kind == "other" => {
+                            ("impl", format!("impl {implemented}"))
+                        }
+                        _ => (kind, name),
+                    };
+                    // the rest of a header spanning lines or a pattern of a macro
+                    let fragment = !name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                        || name.ends_with([':', '>']);
+                    if kind == "other" && fragment {
+                        doc.clear();
+                        attrs.clear();
+                        continue;
+                    }
+                    let item = Item {
+                        kind: kind.to_owned(),
+                        name,
+                        doc: doc.clone(),
+                        in_const: false,
+                        inherited: false,
+                    };
+                    for stability in &attrs {
+                        self.add(stability, &item);
+                    }
+                }
+                doc.clear();
+                attrs.clear();
+                continue;
+            }
+
+            let head = header(&lines[i..]);
+            let (kind, name) = match &item {
+                None => ("impl".to_owned(), head.clone()),
+                Some(item) => {
+                    let kind = item[1].trim_end_matches('!').to_owned();
+                    let name = &item[2];
+                    let in_scope = ["fn", "const", "type"].contains(&kind.as_str());
+                    let name = match scope {
+                        Some(scope) if scope.kind == "impl" && in_scope => {
+                            format!("{}::{name}", self_type(&scope.head))
+                        }
+                        None if self_ty != "T" && (kind == "fn" || kind == "const") => {
+                            format!("{self_ty}::{name}")
+                        }
+                        Some(scope) if scope.kind == "trait" && in_scope => {
+                            format!("{}::{name}", scope.name)
+                        }
+                        _ => name.to_owned(),
+                    };
+                    (kind, name)
+                }
+            };
+            let record = Item {
+                kind: kind.clone(),
+                name: name.clone(),
+                doc: doc.clone(),
+                in_const: false,
+                inherited: false,
+            };
+            // the stability of an item is listed before its stability in constants
+            let (in_const, plain): (Vec<_>, Vec<_>) =
+                attrs.iter().partition(|stability| stability.in_const);
+            for stability in plain.into_iter().chain(in_const) {
+                self.add(stability, &record);
+            }
+            let own = attrs
+                .iter()
+                .filter(|stability| !stability.in_const)
+                .cloned()
+                .collect::<Vec<_>>();
+
+            // members of a stabilized impl share its feature
+            if attrs.is_empty()
+                && kind == "fn"
+                && let Some(scope) = scope
+            {
+                for stability in &scope.features {
+                    let inherited = Item {
+                        inherited: true,
+                        ..record.clone()
+                    };
+                    self.add(stability, &inherited);
+                }
+            }
+
+            let following = lines[i..lines.len().min(i + 12)].join(" ");
+            if (is_impl || kind == "trait") && !head.ends_with('}') {
+                if let Some((before, after)) = following.split_once('{') {
+                    let opening = format!("{before}{{{}", after.chars().next().unwrap_or(' '));
+                    let closes = WHITESPACE_RE.replace_all(&opening, "").contains("{}");
+                    if !closes {
+                        stack.push(Scope {
+                            indent,
+                            kind: if is_impl { "impl" } else { "trait" },
+                            head,
+                            name,
+                            features: own,
+                        });
+                    }
+                }
+            } else if (kind == "enum" || kind == "struct") && s.ends_with('{') {
+                stack.push(Scope {
+                    indent,
+                    kind: if kind == "enum" { "enum" } else { "struct" },
+                    head,
+                    name,
+                    features: Vec::new(),
+                });
+            }
+            doc.clear();
+            attrs.clear();
+        }
+    }
+}
+
+/// An impl, trait, enum or struct whose members are being read
+struct Scope {
+    indent: usize,
+    kind: &'static str,
+    head: String,
+    name: String,
+    /// The features of an impl, which its members without attributes belong to
+    features: Vec<Stability>,
+}
+
+fn source_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            source_files(&path, files)?;
+        } else if path.to_string_lossy().ends_with(".rs.html") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// `core::num` for `num/mod.rs.html` in `core`
+fn module_path(krate: &str, relative: &Path) -> String {
+    let relative = relative.to_string_lossy();
+    let relative = relative.trim_end_matches(".rs.html");
+    let mut parts = vec![krate];
+    parts.extend(
+        relative
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>(),
+    );
+    if parts.len() > 1 && matches!(parts.last(), Some(&("mod" | "lib"))) {
+        parts.pop();
+    }
+    parts.join("::")
+}
+
+/// The lines of code on a source page of rustdoc, without line numbers and highlighting
+fn source_lines(page: &str) -> Vec<String> {
+    let code = page.find("<code>").map_or(page, |start| &page[start + 6..]);
+    code.lines()
+        .map(|line| {
+            let line = LINE_NUMBER_RE.replace(line, "");
+            html::unescape(&TAG_RE.replace_all(&line, ""))
+        })
+        .collect()
+}
+
+/// The text of a doc comment without the space after `///`
+fn doc_line(text: &str) -> String {
+    text.strip_prefix(' ').unwrap_or(text).to_owned()
+}
+
+/// Whether the brackets of an attribute spanning lines are closed, ignoring those in strings
+fn balanced(attr: &str) -> bool {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut previous = ' ';
+    for c in attr.chars() {
+        if in_string {
+            in_string = !(c == '"' && previous != '\\');
+        } else {
+            match c {
+                '"' => in_string = true,
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            }
+        }
+        previous = c;
+    }
+    depth <= 0 && !in_string
+}
+
+fn is_doc_attr(line: &str) -> bool {
+    line.strip_prefix("#[doc")
+        .is_some_and(|rest| rest.trim_start().starts_with('='))
+}
+
+/// The text of `#[doc = "..."]` or `#[doc = concat!(...)]` with the type of the integer macros
+/// stringified, `None` if it uses anything else
+fn doc_attr(attr: &str, self_ty: &str) -> Option<String> {
+    let expr = DOC_ATTR_RE.captures(attr)?.get(1)?.as_str().trim();
+    let mut rest = expr
+        .strip_prefix("concat!(")
+        .and_then(|args| args.strip_suffix(')'))
+        .unwrap_or(expr);
+
+    let mut text = String::new();
+    loop {
+        rest = rest.trim_start_matches([' ', ',']);
+        if rest.is_empty() {
+            return Some(text);
+        }
+        if let Some(string) = STRING_RE.captures(rest) {
+            text.push_str(&unescape_string(&string[1]));
+            rest = &rest[string[0].len()..];
+        } else if let Some(stringified) = STRINGIFY_RE.find(rest)
+            && self_ty != "T"
+        {
+            text.push_str(self_ty);
+            rest = &rest[stringified.end()..];
+        } else {
+            return None;
+        }
+    }
+}
+
+fn unescape_string(string: &str) -> String {
+    let mut out = String::with_capacity(string.len());
+    let mut chars = string.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(c) => out.push(c),
+            None => {}
+        }
+    }
+    out
+}
+
+/// Splits at commas outside of brackets
+fn split_args(args: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+    for c in args.chars() {
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+        if c == ',' && depth == 0 {
+            parts.push(current.trim().to_owned());
+            current.clear();
+        } else {
+            current.push(c);
+        }
+    }
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_owned());
+    }
+    parts
+}
+
+/// The header of the item starting the lines, up to its body or where clause
+fn header(lines: &[String]) -> String {
+    let mut text = Vec::new();
+    for line in lines.iter().take(12) {
+        text.push(line.trim());
+        if line.contains('{') || line.trim_end().ends_with(';') {
+            break;
+        }
+    }
+    let text = text.join(" ");
+    let text = text.split('{').next().unwrap_or_default();
+    let text = WHERE_RE.split(text).next().unwrap_or_default();
+    let text = WHITESPACE_RE.replace_all(text, " ");
+    text.trim().trim_end_matches(';').trim().to_owned()
+}
+
+/// The name of the type an impl is for, `Bar` for `impl<T> Foo<T> for Bar<T>`
+fn self_type(impl_head: &str) -> String {
+    let head = IMPL_RE.replace(impl_head, "");
+    let mut head = head.trim_start();
+    if head.starts_with('<') {
+        let mut depth = 0;
+        for (i, c) in head.char_indices() {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                head = &head[i + 1..];
+                break;
+            }
+        }
+    }
+    let mut head = head.trim();
+    if let Some((_, target)) = head.split_once(" for ") {
+        head = target;
+    }
+    let head = head.trim_start_matches(['!', '&', '*']).trim();
+    let head = SELF_QUALIFIERS_RE.replace(head, "");
+    SELF_NAME_RE
+        .captures(&head)
+        .map_or_else(|| head.to_string(), |name| name[1].to_owned())
+}
+
+/// The items of the `macro_rules!` in a file which get a `#[$meta]` parameter as their
+/// attribute, by macro and parameter
+type MacroParams = HashMap<String, (Vec<String>, HashMap<String, Vec<Item>>)>;
+
+fn macro_params(lines: &[String]) -> MacroParams {
+    let mut macros = HashMap::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(name) = MACRO_RULES_RE.captures(line) else {
+            continue;
+        };
+        let header = lines[i..lines.len().min(i + 40)]
+            .iter()
+            .map(|line| line.trim())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let Some(pattern) = MACRO_PATTERN_RE.captures(&header) else {
+            continue;
+        };
+        let params = META_PARAM_RE
+            .captures_iter(&pattern[1])
+            .map(|param| param[1].to_owned())
+            .collect::<Vec<_>>();
+        if params.is_empty() {
+            continue;
+        }
+
+        let mut items = HashMap::<String, Vec<Item>>::new();
+        let mut pending = Vec::new();
+        let mut doc = Vec::new();
+        let indent = line.len() - line.trim_start().len();
+        let mut joined: Option<String> = None;
+        for body in &lines[i + 1..] {
+            let mut s = body.trim().to_owned();
+            if s == "}" && body.len() - body.trim_start().len() == indent {
+                break;
+            }
+            if let Some(mut attr) = joined.take() {
+                attr.push(' ');
+                attr.push_str(&s);
+                if !balanced(&attr) {
+                    joined = Some(attr);
+                    continue;
+                }
+                s = attr;
+            } else if s.starts_with("#[") && !balanced(&s) {
+                joined = Some(s);
+                continue;
+            }
+
+            if let Some(text) = s.strip_prefix("///") {
+                doc.push(Some(doc_line(text)));
+                continue;
+            }
+            if is_doc_attr(&s) {
+                doc.push(None);
+                continue;
+            }
+            if let Some(attr) = META_ATTR_RE.captures(&s) {
+                pending.push(attr[1].to_owned());
+                continue;
+            }
+            if s.starts_with("#[") {
+                continue;
+            }
+            if let Some(item) = ITEM_RE.captures(&s)
+                && !pending.is_empty()
+            {
+                for param in &pending {
+                    items.entry(param.clone()).or_default().push(Item {
+                        kind: item[1].trim_end_matches('!').to_owned(),
+                        name: item[2].to_owned(),
+                        doc: doc.clone(),
+                        in_const: false,
+                        inherited: false,
+                    });
+                }
+            }
+            if !s.is_empty() {
+                pending.clear();
+                doc.clear();
+            }
+        }
+        macros.insert(name[1].to_owned(), (params, items));
+    }
+    macros
+}
+
+/// An item name without the metavariables of macros and the paths of the crate
+fn clean(name: &str) -> String {
+    let name = METAVARIABLE_RE.replace_all(name, "T");
+    let name = RELATIVE_PATH_RE.replace_all(&name, "");
+    let name = CONST_IMPL_RE.replace(&name, "$1 ");
+    WHITESPACE_RE.replace_all(&name, " ").trim().to_owned()
+}
+
+/// How an item is listed under `## APIs`
+fn api(item: &Item) -> String {
+    let name = clean(&item.name);
+    match item.kind.as_str() {
+        "impl" | "other" | "variant" | "field" => name,
+        "use" => format!("use {name}"),
+        "macro_rules" | "macro" => format!("macro {name}!"),
+        "fn" if item.in_const => format!("const fn {name}"),
+        kind => format!("{kind} {name}"),
+    }
+}
+
+/// `a`, `a and b` or `a, b, c and 2 more`
+fn listing(names: &[String]) -> String {
+    const LIMIT: usize = 3;
+    match names {
+        [] => String::new(),
+        [name] => name.clone(),
+        _ if names.len() <= LIMIT => format!(
+            "{} and {}",
+            names[..names.len() - 1].join(", "),
+            names[names.len() - 1]
+        ),
+        _ => format!(
+            "{} and {} more",
+            names[..LIMIT].join(", "),
+            names.len() - LIMIT
+        ),
+    }
+}
+
+/// Markdown on a single line without links, cut at a sentence if it's too long
+fn one_line(text: &str) -> String {
+    let text = WHITESPACE_RE.replace_all(text, " ");
+    let text = CODE_LINK_RE.replace_all(text.trim(), "$1");
+    let text = INLINE_LINK_RE.replace_all(&text, |link: &Captures| match &link[2] {
+        // links relative to the docs of an item lead nowhere from discord
+        url if url.starts_with("http") => link[0].to_owned(),
+        _ => link[1].to_owned(),
+    });
+    let text = REFERENCE_LINK_RE.replace_all(&text, "$1");
+    let text = SHORTCUT_LINK_RE.replace_all(&text, "$1$2");
+    if text.len() <= MAX_DESCRIPTION {
+        return text.into_owned();
+    }
+
+    let mut end = MAX_DESCRIPTION;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let cut = &text[..end];
+    match cut.rfind(". ") {
+        Some(sentence) if sentence > 80 => cut[..=sentence].to_owned(),
+        _ => format!("{}…", cut.trim_end()),
+    }
+}
+
+/// A description ending like a sentence
+fn sentence(text: &str) -> String {
+    let text = one_line(text);
+    if let Some(text) = text.strip_suffix(':') {
+        format!("{text}.")
+    } else if text.ends_with(['.', '!', '?', '…']) || text.is_empty() {
+        text
+    } else {
+        format!("{text}.")
+    }
+}
+
+/// The first paragraph of markdown, skipping headings, quotes and code
+fn first_paragraph<'a>(lines: impl IntoIterator<Item = Option<&'a str>>) -> Option<String> {
+    let mut paragraph = Vec::new();
+    let mut in_code = false;
+    for line in lines {
+        let Some(line) = line else {
+            // a line of the docs which couldn't be read
+            return None;
+        };
+        let skipped = line.starts_with('#') || line.starts_with('>') || line.starts_with('<');
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        } else if !in_code && !line.trim().is_empty() && !skipped {
+            paragraph.push(line.trim());
+            continue;
+        }
+        if !paragraph.is_empty() {
+            break;
+        }
+    }
+    (!paragraph.is_empty()).then(|| paragraph.join(" "))
+}
+
+fn summary(doc: &[Option<String>]) -> Option<String> {
+    first_paragraph(doc.iter().map(Option::as_deref)).map(|paragraph| sentence(&paragraph))
+}
+
+/// The first runnable example of the docs without its hidden lines. Examples of unstable
+/// features have to enable the feature, the others mustn't enable any.
+fn doctest(doc: &[Option<String>], unstable: Option<&str>) -> Option<String> {
+    let mut blocks = Vec::new();
+    let mut current = Vec::new();
+    let mut info = "";
+    let mut in_code = false;
+    for line in doc {
+        let line = line.as_deref();
+        match line
+            .map(str::trim)
+            .and_then(|line| line.strip_prefix("```"))
+        {
+            Some(fence_info) => {
+                if in_code {
+                    blocks.push((info, std::mem::take(&mut current)));
+                } else {
+                    info = fence_info.trim();
+                }
+                in_code = !in_code;
+            }
+            None if in_code => current.push(line),
+            None => {}
+        }
+    }
+
+    blocks.into_iter().find_map(|(info, block)| {
+        let runnable = info
+            .split(',')
+            .map(str::trim)
+            .filter(|word| !word.is_empty())
+            .all(|word| word == "rust" || word == "no_run" || word.starts_with("edition"));
+        let lines = block.into_iter().collect::<Option<Vec<_>>>()?;
+        let enables = |line: &&str| line.contains("#![feature");
+        let enabled = match unstable {
+            Some(feature) => lines
+                .iter()
+                .any(|line| enables(line) && line.contains(feature)),
+            None => !lines.iter().any(enables),
+        };
+        if !runnable || !enabled {
+            return None;
+        }
+        let code = lines
+            .into_iter()
+            .filter(|line| !line.starts_with("# ") && line.trim() != "#")
+            .collect::<Vec<_>>()
+            .join("\n");
+        let code = code.trim();
+        (!code.is_empty()).then(|| code.to_owned())
+    })
+}
+
+/// The description of a library feature from its items, and their listing with an example
+fn library_docs(name: &str, feature: &LibraryFeature, unstable: bool) -> (String, String) {
+    let own = feature
+        .items
+        .iter()
+        .filter(|item| !item.inherited)
+        .collect::<Vec<_>>();
+    let items = if own.is_empty() {
+        feature.items.iter().collect()
+    } else {
+        own
+    };
+    let without_metavariables = items
+        .iter()
+        .copied()
+        .filter(|item| !item.name.contains('$'))
+        .collect::<Vec<_>>();
+    let plain = if without_metavariables.is_empty() {
+        items.clone()
+    } else {
+        without_metavariables
+    };
+    let not_reexported = plain
+        .iter()
+        .copied()
+        .filter(|item| item.kind != "use")
+        .collect::<Vec<_>>();
+    let plain = if not_reexported.is_empty() {
+        plain
+    } else {
+        not_reexported
+    };
+
+    let mut apis = Vec::new();
+    for item in &plain {
+        let api = api(item);
+        if !apis.contains(&api) {
+            apis.push(api);
+        }
+    }
+    let mut names = Vec::new();
+    for item in &plain {
+        let name = format!("`{}`", clean(&item.name));
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    let is_type =
+        |item: &&&Item| ["struct", "enum", "union", "trait", "type"].contains(&item.kind.as_str());
+    let types = plain.iter().filter(is_type).collect::<Vec<_>>();
+    let described = if types.is_empty() {
+        plain.iter().collect::<Vec<_>>()
+    } else {
+        types.clone()
+    };
+    let is_member = |item: &Item| item.kind != "use" && item.kind != "mod";
+    let documented = described
+        .iter()
+        .filter(|item| is_member(item))
+        .find_map(|item| summary(&item.doc));
+    // a module declared for a single item doesn't need its own description
+    let single = names.len() == 1 || plain.iter().filter(|item| is_member(item)).count() == 1;
+    let all = |kind: &str| plain.iter().all(|item| item.kind == kind);
+
+    let description = if plain.iter().all(|item| item.in_const) {
+        if all("fn") {
+            format!("Allows calling {} in constants.", listing(&names))
+        } else {
+            format!("Makes {} usable in constants.", listing(&names))
+        }
+    } else if let Some(documented) = documented.filter(|_| !types.is_empty() || single) {
+        documented
+    } else if all("impl") {
+        let mut heads = Vec::new();
+        for item in &plain {
+            let head = format!("`{}`", IMPL_GENERICS_RE.replace(&clean(&item.name), ""));
+            if !heads.contains(&head) {
+                heads.push(head);
+            }
+        }
+        format!("Implements {}.", listing(&heads))
+    } else if all("mod") {
+        summary(&plain[0].doc).unwrap_or_else(|| format!("Adds the `{}` module.", plain[0].name))
+    } else if all("use") {
+        format!("Re-exports {}.", listing(&names))
+    } else {
+        let added = names
+            .iter()
+            .zip(&plain)
+            .filter(|(_, item)| item.kind != "impl")
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        let added = if added.is_empty() { names } else { added };
+        format!("Adds {}.", listing(&added))
+    };
+
+    let mut examples = items.clone();
+    examples.sort_by_key(|item| item.kind == "use");
+    examples.extend(feature.items.iter().filter(|item| item.inherited));
+    let example = examples
+        .iter()
+        .find_map(|item| doctest(&item.doc, unstable.then_some(name)));
+
+    let mut body = vec!["## APIs".to_owned(), String::new()];
+    body.extend(apis.iter().take(MAX_APIS).map(|api| format!("- `{api}`")));
+    if apis.len() > MAX_APIS {
+        body.push(format!("- and {} more", apis.len() - MAX_APIS));
+    }
+    if let Some(example) = example {
+        body.extend([
+            String::new(),
+            "## Example".to_owned(),
+            String::new(),
+            "```rust".to_owned(),
+            example,
+            "```".to_owned(),
+        ]);
+    }
+    (description, body.join("\n"))
+}
+
+/// The front matter fields of an asset and the docs below them
+struct Docs<'a> {
+    kind: &'a str,
+    status: &'a str,
+    since: Option<&'a str>,
+    issue: Option<u32>,
+    description: &'a str,
+    body: &'a str,
+}
+
+impl Docs<'_> {
+    fn render(&self) -> String {
+        let mut front_matter = vec![
+            format!("kind: {}", self.kind),
+            format!("status: {}", self.status),
+        ];
+        if let Some(since) = self.since {
+            front_matter.push(format!("since: {since}"));
+        }
+        if let Some(issue) = self.issue {
+            front_matter.push(format!("issue: {issue}"));
+        }
+        front_matter.push(format!("description: {}", self.description));
+
+        let mut content = format!("---\n{}\n---\n", front_matter.join("\n"));
+        if !self.body.trim().is_empty() {
+            content.push('\n');
+            content.push_str(self.body.trim());
+            content.push('\n');
+        }
+        content
+    }
+}
+
+fn unstable_docs(name: &str, page: &Page, sources: &Sources) -> String {
+    let library = sources.unstable.get(name);
+    let mut description = first_paragraph(page.text.lines().map(Some)).map(|text| sentence(&text));
+    let mut body = page.text.clone();
+
+    if let Some(library) =
+        library.filter(|library| page.kind == "library" && !library.items.is_empty())
+    {
+        let (from_items, listing) = library_docs(name, library, true);
+        description = description.or(Some(from_items));
+        let listing = match listing.split_once("\n\n## Example") {
+            // the book has an example already
+            Some((apis, _)) if body.contains("```") => apis.to_owned(),
+            _ => listing,
+        };
+        if !body.is_empty() {
+            body.push_str("\n\n");
+        }
+        body.push_str(&listing);
+    }
+
+    let description = description.unwrap_or_else(|| {
+        if page.internal {
+            "Has no tracking issue and is likely internal to the compiler.".to_owned()
+        } else {
+            "Undocumented.".to_owned()
+        }
+    });
+    Docs {
+        kind: page.kind,
+        status: "unstable",
+        since: None,
+        issue: page.issue.or(library.and_then(|library| library.issue)),
+        description: &description,
+        body: &body,
+    }
+    .render()
+}
+
+fn stable_docs(name: &str, feature: &LibraryFeature, version: &str) -> String {
+    let (description, body) = library_docs(name, feature, false);
+    let since = feature.since.as_deref().map(|since| {
+        if since == CURRENT_RUSTC_VERSION {
+            version
+        } else {
+            since
+        }
+    });
+    Docs {
+        kind: "library",
+        status: "stable",
+        since,
+        issue: None,
+        description: &description,
+        body: &body,
+    }
+    .render()
+}
+
+/// A front matter field of an asset
+fn field<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    content
+        .strip_prefix("---\n")?
+        .split("\n---")
+        .next()?
+        .lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(": "))
+}
+
+/// Whether an asset is written by hand instead of generated
+fn is_hand_written(name: &str, content: &str) -> bool {
+    let status = field(content, "status");
+    HAND_WRITTEN.contains(&name)
+        || status == Some("removed")
+        || status == Some("stable") && field(content, "kind") == Some("language")
+}
+
+/// Every feature in `assets` by its name
+fn read_features(assets: &Path) -> io::Result<BTreeMap<String, String>> {
+    let mut features = BTreeMap::new();
+    for entry in fs::read_dir(assets)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "md") {
+            continue;
+        }
+        if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+            features.insert(name.to_owned(), fs::read_to_string(&path)?);
+        }
+    }
+    Ok(features)
+}
+
+/// The version of the toolchain, which the sources call `CURRENT_RUSTC_VERSION`
+fn rustc_version(sysroot: &Path) -> Option<String> {
+    let output = Command::new(sysroot.join("bin/rustc"))
+        .arg("--version")
+        .output()
+        .ok()?;
+    let version = String::from_utf8(output.stdout).ok()?;
+    let version = version.split_whitespace().nth(1)?;
+    Some(version.split('-').next()?.to_owned())
+}
+
+/// The difference between the generated feature docs and the assets
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Report {
+    added: Vec<String>,
+    changed: Vec<String>,
+    removed: Vec<String>,
+}
+
+impl Report {
+    fn new(upstream: &BTreeMap<String, String>, assets: &BTreeMap<String, String>) -> Self {
+        let mut report = Self::default();
+        for (name, content) in upstream {
+            match assets.get(name) {
+                None => report.added.push(name.clone()),
+                Some(asset) if asset != content => report.changed.push(name.clone()),
+                Some(_) => {}
+            }
+        }
+        report.removed = assets
+            .keys()
+            .filter(|name| !upstream.contains_key(*name))
+            .cloned()
+            .collect();
+        report
+    }
+
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error + Send + Sync>> {
+    let mut write = false;
+    let mut paths = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--write" => write = true,
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let mut paths = paths.into_iter();
+    let Some(sysroot) = paths.next() else {
+        eprintln!("Usage: features <toolchain sysroot> [assets dir] [--write]");
+        return Ok(ExitCode::FAILURE);
+    };
+    let assets = paths.next().unwrap_or_else(|| PathBuf::from(ASSETS_DIR));
+    let docs = sysroot.join(DOCS_DIR);
+    let version =
+        rustc_version(&sysroot).ok_or_else(|| format!("no rustc in {}", sysroot.display()))?;
+
+    let mut pages = BTreeMap::new();
+    for (kind, base) in BOOK_DIRS {
+        let dir = docs.join(format!("unstable-book/{kind}-features"));
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let name = stem.replace('-', "_");
+            pages.insert(name, book_page(kind, base, &fs::read_to_string(&path)?));
+        }
+    }
+    if pages.is_empty() {
+        return Err(format!("no Unstable Book in {}", docs.display()).into());
+    }
+    let sources = Sources::read(&docs)?;
+
+    let existing = read_features(&assets)?;
+    let mut generated = existing
+        .iter()
+        .filter(|(name, content)| is_hand_written(name, content))
+        .map(|(name, content)| (name.clone(), content.clone()))
+        .collect::<BTreeMap<_, _>>();
+    for (name, page) in &pages {
+        generated.insert(name.clone(), unstable_docs(name, page, &sources));
+    }
+    // the Unstable Book leaves out the features of `core::arch`
+    let unlisted = Page {
+        kind: "library",
+        issue: None,
+        internal: false,
+        text: String::new(),
+    };
+    for name in sources.unstable.keys() {
+        if !generated.contains_key(name) {
+            generated.insert(name.clone(), unstable_docs(name, &unlisted, &sources));
+        }
+    }
+    for (name, feature) in &sources.stable {
+        if !generated.contains_key(name) {
+            generated.insert(name.clone(), stable_docs(name, feature, &version));
+        }
+    }
+    let report = Report::new(&generated, &existing);
+
+    for (label, names) in [
+        ("added", &report.added),
+        ("changed", &report.changed),
+        ("removed", &report.removed),
+    ] {
+        println!("{} {label}: {}", names.len(), names.join(", "));
+    }
+
+    if write && !report.is_empty() {
+        for name in report.added.iter().chain(&report.changed) {
+            fs::write(assets.join(format!("{name}.md")), &generated[name])?;
+        }
+        for name in &report.removed {
+            fs::remove_file(assets.join(format!("{name}.md")))?;
+        }
+        println!("updated {}", assets.display());
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(code: &str) -> Vec<String> {
+        code.lines().map(str::to_owned).collect()
+    }
+
+    #[test]
+    fn reads_book_pages() {
+        let page = r##"<main><h1 id="box_patterns"><a class="header" href="#box_patterns"><code>box_patterns</code></a></h1>
+<p>The tracking issue for this feature is: <a href="https://github.com/rust-lang/rust/issues/29641">#29641</a></p>
+<hr>
+<blockquote>
+<p><strong>Note</strong>: This feature will be superseded by <a href="./deref-patterns.html"><code>deref_patterns</code></a> in the future.</p>
+</blockquote>
+<p>Box patterns let you match on <code>Box&lt;T&gt;</code>s:</p>
+<pre class="playground"><code class="language-rust"><span class="boring">#![allow(unused)]
+</span>#![feature(box_patterns)]
+</code></pre>
+</main>"##;
+        let page = book_page("language", BOOK_DIRS[0].1, page);
+
+        assert_eq!(page.issue, Some(29641));
+        assert_eq!(
+            page.text,
+            "> **Note**: This feature will be superseded by \
+             [`deref_patterns`](https://doc.rust-lang.org/nightly/unstable-book/language-features/deref-patterns.html) \
+             in the future.\n\nBox patterns let you match on `Box<T>`s:\n\n```rust\n#![feature(box_patterns)]\n```"
+        );
+        assert_eq!(
+            first_paragraph(page.text.lines().map(Some)).map(|text| sentence(&text)),
+            Some("Box patterns let you match on `Box<T>`s.".to_owned())
+        );
+    }
+
+    #[test]
+    fn reads_internal_pages() {
+        let page = r#"<main><h1 id="num_internals">num_internals</h1>
+<p>This feature has no tracking issue, and is therefore likely internal to the compiler, not being intended for general use.</p>
+<hr>
+</main>"#;
+        let page = book_page("library", BOOK_DIRS[1].1, page);
+
+        assert_eq!(page.issue, None);
+        assert!(page.internal);
+        assert!(page.text.is_empty());
+    }
+
+    #[test]
+    fn scans_items() {
+        let code = lines(
+            r#"impl<T> Vec<T> {
+    /// Decomposes a `Vec<T>` into its raw components.
+    ///
+    /// ```
+    /// let v = vec![1];
+    /// # let _ = 1;
+    /// let (ptr, len, cap) = v.into_raw_parts();
+    /// ```
+    #[stable(feature = "vec_into_raw_parts", since = "1.93.0")]
+    pub fn into_raw_parts(self) -> (*mut T, usize, usize) {
+        todo!()
+    }
+
+    #[unstable(feature = "vec_peek_mut", issue = "122742")]
+    #[rustc_const_unstable(feature = "const_vec_peek", issue = "none")]
+    pub const fn peek_mut(&mut self) -> Option<PeekMut<'_, T>> {
+        todo!()
+    }
+}
+
+#[stable(feature = "rust1", since = "1.0.0")]
+impl<T> Clone for Vec<T> {
+    fn clone(&self) -> Self {
+        todo!()
+    }
+}"#,
+        );
+        let mut sources = Sources::default();
+        sources.scan("alloc::vec", &code);
+
+        let stable = &sources.stable["vec_into_raw_parts"];
+        assert_eq!(stable.since.as_deref(), Some("1.93.0"));
+        assert_eq!(stable.items[0].name, "Vec::into_raw_parts");
+        let (description, body) = library_docs("vec_into_raw_parts", stable, false);
+        assert_eq!(
+            description,
+            "Decomposes a `Vec<T>` into its raw components."
+        );
+        assert_eq!(
+            body,
+            "## APIs\n\n- `fn Vec::into_raw_parts`\n\n## Example\n\n```rust\nlet v = vec![1];\nlet (ptr, len, cap) = v.into_raw_parts();\n```"
+        );
+
+        let unstable = &sources.unstable["vec_peek_mut"];
+        assert_eq!(unstable.issue, Some(122742));
+        assert_eq!(unstable.items[0].name, "Vec::peek_mut");
+        let (description, _) =
+            library_docs("const_vec_peek", &sources.unstable["const_vec_peek"], true);
+        assert_eq!(description, "Allows calling `Vec::peek_mut` in constants.");
+
+        let rust1 = &sources.stable["rust1"];
+        assert_eq!(rust1.items[0].name, "impl<T> Clone for Vec<T>");
+        assert!(rust1.items[1].inherited);
+        assert_eq!(rust1.items[1].name, "Vec::clone");
+    }
+
+    #[test]
+    fn reads_doc_attributes() {
+        assert_eq!(
+            doc_attr(
+                r#"#[doc = concat!("Returns the `", stringify!($SelfT), "`.")]"#,
+                "i32"
+            ),
+            Some("Returns the `i32`.".to_owned())
+        );
+        assert_eq!(doc_attr(r#"#[doc = concat!("See ", $link)]"#, "i32"), None);
+    }
+
+    #[test]
+    fn finds_self_types() {
+        assert_eq!(self_type("impl<T: ?Sized> Deref for &mut Box<T>"), "Box");
+        assert_eq!(self_type("unsafe impl<T> Send for [T; N]"), "[T; N]");
+        assert_eq!(self_type("impl Option<u8>"), "Option");
+    }
+
+    #[test]
+    fn shortens_descriptions() {
+        assert_eq!(
+            sentence(
+                "Uses [`Vec`](../vec/struct.Vec.html) and [the book](https://doc.rust-lang.org/book/)"
+            ),
+            "Uses `Vec` and [the book](https://doc.rust-lang.org/book/)."
+        );
+        assert!(one_line(&"word ".repeat(100)).ends_with('…'));
+    }
+}
@@ -0,0 +1,443 @@
+// Syncs the lint docs in `assets/lints` with a toolchain. rustc lints are explained by the lint
+// listing of its rustc book and clippy lints by `cargo clippy --explain`, the level, group and
+// description of both come from `clippy-driver -W help`.
+//
+// Usage: lints <toolchain sysroot> [assets dir] [--write]
+//
+// The toolchain needs the clippy and rust-docs components, `rustc --print sysroot` prints where it
+// is installed. Clippy doesn't tell which version added a lint, the `since` of the bundled clippy
+// lints is kept and new ones need it added from the `#[clippy::version]` of their declaration.
+// Without `--write` only the added, changed and removed lints are reported.
+
+#[path = "common/html.rs"]
+mod html;
+
+use html::{Markdown, Options};
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::{Command, ExitCode},
+};
+
+const ASSETS_DIR: &str = "assets/lints";
+
+/// The pages of the rustc book which explain the lints, relative to the sysroot
+const LISTING_DIR: &str = "share/doc/rust/html/rustc/lints/listing";
+const LISTING_PAGES: [&str; 3] = [
+    "allowed-by-default.html",
+    "warn-by-default.html",
+    "deny-by-default.html",
+];
+const LISTING_URL: &str = "https://doc.rust-lang.org/rustc/lints/listing/";
+
+const CLIPPY_PREFIX: &str = "clippy::";
+
+/// Code block languages of rustdoc which mean the block is rust
+const RUST_FENCE_WORDS: [&str; 5] = ["rust", "compile_fail", "no_run", "ignore", "should_panic"];
+
+/// A lint as listed by `-W help`
+#[derive(Debug, PartialEq, Eq)]
+struct Lint {
+    level: String,
+    description: String,
+}
+
+/// The lints and lint groups listed by `-W help`, with `_` in the names like in the assets
+#[derive(Debug, Default)]
+struct Help {
+    lints: BTreeMap<String, Lint>,
+    groups: Vec<(String, Vec<String>)>,
+}
+
+impl Help {
+    fn parse(text: &str) -> Self {
+        let mut help = Self::default();
+        let mut in_groups = false;
+
+        for line in text.lines() {
+            if line.starts_with("Lint checks") {
+                in_groups = false;
+                continue;
+            }
+            if line.starts_with("Lint groups") {
+                in_groups = true;
+                continue;
+            }
+
+            let Some((name, rest)) = line.trim_start().split_once("  ") else {
+                continue;
+            };
+            let name = name.replace('-', "_");
+            let rest = rest.trim();
+            if in_groups {
+                if ["name", "----", "warnings", "clippy::all"].contains(&name.as_str()) {
+                    continue;
+                }
+                let members = rest
+                    .split(',')
+                    .map(|member| member.trim().replace('-', "_"))
+                    .collect();
+                help.groups.push((name, members));
+            } else if let Some((level, description)) = rest.split_once("  ")
+                && ["allow", "warn", "deny", "forbid"].contains(&level)
+            {
+                help.lints.insert(
+                    name,
+                    Lint {
+                        level: level.to_owned(),
+                        description: description.trim().to_owned(),
+                    },
+                );
+            }
+        }
+
+        help
+    }
+
+    /// The first group listing the lint, without the `clippy::` prefix
+    fn group(&self, lint: &str) -> Option<&str> {
+        self.groups
+            .iter()
+            .find(|(_, members)| members.iter().any(|member| member == lint))
+            .map(|(group, _)| group.trim_start_matches(CLIPPY_PREFIX))
+    }
+}
+
+/// The markdown of every lint section of a page of the lint listing by lint name
+fn listing_sections(page: &str) -> BTreeMap<String, String> {
+    let mut sections = BTreeMap::new();
+    let Some(main) = html::main_content(page) else {
+        return sections;
+    };
+
+    let options = || Options {
+        base: Some(LISTING_URL),
+        skip_hidden: false,
+        heading: |level| level,
+    };
+    let mut current: Option<(String, Markdown)> = None;
+    let mut in_title = false;
+    for token in html::tokens(main) {
+        if token.is_start("h2") {
+            if let Some((name, markdown)) = current.take() {
+                sections.insert(name, markdown.finish());
+            }
+            let name = token.attr("id").unwrap_or_default().replace('-', "_");
+            current = Some((name, Markdown::new(options())));
+            in_title = true;
+        } else if token.is_end("h2") {
+            in_title = false;
+        } else if !in_title && let Some((_, markdown)) = &mut current {
+            markdown.push(&token);
+        }
+    }
+    if let Some((name, markdown)) = current {
+        sections.insert(name, markdown.finish());
+    }
+
+    sections.retain(|name, text| !name.is_empty() && !text.is_empty());
+    sections
+}
+
+fn is_rust_fence(info: &str) -> bool {
+    if info.is_empty() {
+        return true;
+    }
+    info.bytes()
+        .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b"_,E".contains(&b))
+        && info
+            .split(',')
+            .any(|word| RUST_FENCE_WORDS.contains(&word) || word.starts_with("edition"))
+}
+
+/// Marks rust examples as such and drops their hidden lines, and the blank lines before the end
+/// of a code block
+fn clean_fences(text: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code = false;
+
+    for line in text.lines() {
+        if let Some(info) = line.trim().strip_prefix("```") {
+            let opening = !in_code;
+            in_code = !in_code;
+            if opening && is_rust_fence(info) {
+                lines.push("```rust");
+                continue;
+            }
+            if line == "```" {
+                while lines.last() == Some(&"") {
+                    lines.pop();
+                }
+            }
+        } else if in_code && (line.starts_with("# ") || line == "#") {
+            continue;
+        }
+        lines.push(line);
+    }
+
+    format!("{}\n", lines.join("\n").trim())
+}
+
+/// The explanation of a clippy lint up to its configuration options
+fn clippy_explanation(sysroot: &Path, name: &str) -> Option<String> {
+    let output = Command::new(sysroot.join("bin/cargo-clippy"))
+        .args(["clippy", "--explain", name])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let explanation = String::from_utf8(output.stdout).ok()?;
+    let explanation = explanation
+        .split("### Configuration for")
+        .next()
+        .unwrap_or_default();
+    (!explanation.trim().is_empty()).then(|| clean_fences(explanation))
+}
+
+fn render(group: Option<&str>, lint: &Lint, since: Option<&str>, explanation: &str) -> String {
+    let mut front_matter = Vec::new();
+    if let Some(group) = group {
+        front_matter.push(format!("group: {group}"));
+    }
+    front_matter.push(format!("level: {}", lint.level));
+    front_matter.push(format!("description: {}", lint.description));
+    if let Some(since) = since {
+        front_matter.push(format!("since: {since}"));
+    }
+    format!("---\n{}\n---\n\n{explanation}", front_matter.join("\n"))
+}
+
+/// The `since` in the front matter of an asset
+fn since(content: &str) -> Option<&str> {
+    content
+        .strip_prefix("---\n")?
+        .split("\n---")
+        .next()?
+        .lines()
+        .find_map(|line| line.strip_prefix("since: "))
+}
+
+/// The asset of a lint, clippy lints live in `clippy` and rustc lints in `rustc`
+fn asset_path(assets: &Path, name: &str) -> PathBuf {
+    match name.strip_prefix(CLIPPY_PREFIX) {
+        Some(name) => assets.join("clippy").join(format!("{name}.md")),
+        None => assets.join("rustc").join(format!("{name}.md")),
+    }
+}
+
+/// Every lint in `assets` by its name
+fn read_lints(assets: &Path) -> io::Result<BTreeMap<String, String>> {
+    let mut lints = BTreeMap::new();
+    for (tool, prefix) in [("rustc", ""), ("clippy", CLIPPY_PREFIX)] {
+        for entry in fs::read_dir(assets.join(tool))? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|extension| extension != "md") {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+                lints.insert(format!("{prefix}{name}"), fs::read_to_string(&path)?);
+            }
+        }
+    }
+    Ok(lints)
+}
+
+/// The difference between the generated lint docs and the assets
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Report {
+    added: Vec<String>,
+    changed: Vec<String>,
+    removed: Vec<String>,
+}
+
+impl Report {
+    fn new(upstream: &BTreeMap<String, String>, assets: &BTreeMap<String, String>) -> Self {
+        let mut report = Self::default();
+        for (name, content) in upstream {
+            match assets.get(name) {
+                None => report.added.push(name.clone()),
+                Some(asset) if asset != content => report.changed.push(name.clone()),
+                Some(_) => {}
+            }
+        }
+        report.removed = assets
+            .keys()
+            .filter(|name| !upstream.contains_key(*name))
+            .cloned()
+            .collect();
+        report
+    }
+
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error + Send + Sync>> {
+    let mut write = false;
+    let mut paths = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--write" => write = true,
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let mut paths = paths.into_iter();
+    let Some(sysroot) = paths.next() else {
+        eprintln!("Usage: lints <toolchain sysroot> [assets dir] [--write]");
+        return Ok(ExitCode::FAILURE);
+    };
+    let assets = paths.next().unwrap_or_else(|| PathBuf::from(ASSETS_DIR));
+
+    let help = Command::new(sysroot.join("bin/clippy-driver"))
+        .args(["-W", "help"])
+        .output()?;
+    let help = Help::parse(&String::from_utf8(help.stdout)?);
+    if help.lints.is_empty() {
+        return Err(format!("clippy-driver of {} listed no lints", sysroot.display()).into());
+    }
+
+    let mut listing = BTreeMap::new();
+    for page in LISTING_PAGES {
+        let page = fs::read_to_string(sysroot.join(LISTING_DIR).join(page))?;
+        listing.extend(listing_sections(&page));
+    }
+
+    let existing = read_lints(&assets)?;
+    let mut generated = BTreeMap::new();
+    let mut undocumented = Vec::new();
+    for (name, lint) in &help.lints {
+        let explanation = match name.strip_prefix(CLIPPY_PREFIX) {
+            Some(short) => clippy_explanation(&sysroot, short),
+            None => listing.get(name).map(|section| clean_fences(section)),
+        };
+        let Some(explanation) = explanation else {
+            undocumented.push(name.clone());
+            continue;
+        };
+
+        let since = existing.get(name).and_then(|content| since(content));
+        let content = render(help.group(name), lint, since, &explanation);
+        generated.insert(name.clone(), content);
+    }
+    let report = Report::new(&generated, &existing);
+
+    for (label, names) in [
+        ("added", &report.added),
+        ("changed", &report.changed),
+        ("removed", &report.removed),
+        ("without docs", &undocumented),
+    ] {
+        println!("{} {label}: {}", names.len(), names.join(", "));
+    }
+    let missing_since = report
+        .added
+        .iter()
+        .filter(|name| name.starts_with(CLIPPY_PREFIX))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !missing_since.is_empty() {
+        eprintln!("without `since`: {}", missing_since.join(", "));
+    }
+
+    if write && !report.is_empty() {
+        for name in report.added.iter().chain(&report.changed) {
+            fs::write(asset_path(&assets, name), &generated[name])?;
+        }
+        for name in &report.removed {
+            fs::remove_file(asset_path(&assets, name))?;
+        }
+        println!("updated {}", assets.display());
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HELP: &str = "
+Lint checks provided by rustc:
+
+                                  name  default  meaning
+                                  ----  -------  -------
+                      unused-variables  warn     detect variables which are not used in any way
+
+Lint groups provided by rustc:
+
+                                  name  sub-lints
+                                  ----  ---------
+                              warnings  all lints that are set to issue warnings
+                                unused  unused-imports, unused-variables
+
+Lint checks loaded by this crate:
+
+          clippy::needless-range-loop  warn     for-looping over a range of indices where an iterator over items would do
+
+Lint groups loaded by this crate:
+
+                          clippy::all  clippy::needless-range-loop
+                        clippy::style  clippy::needless-range-loop
+";
+
+    #[test]
+    fn parses_help() {
+        let help = Help::parse(HELP);
+
+        assert_eq!(
+            help.lints.keys().collect::<Vec<_>>(),
+            ["clippy::needless_range_loop", "unused_variables"]
+        );
+        assert_eq!(help.lints["unused_variables"].level, "warn");
+        assert_eq!(help.group("unused_variables"), Some("unused"));
+        assert_eq!(help.group("clippy::needless_range_loop"), Some("style"));
+    }
+
+    #[test]
+    fn reads_the_listing() {
+        let page = r##"<main><h1 id="warn-by-default-lints">Warn-by-default Lints</h1>
+<h2 id="unused-variables"><a class="header" href="#unused-variables">unused_variables</a></h2>
+<p>The <code>unused_variables</code> lint detects variables which are not used
+in any way. See <a href="allowed-by-default.html#unused-results">unused_results</a>.</p>
+<h3 id="example"><a class="header" href="#example">Example</a></h3>
+<pre><code class="language-rust edition2021"><span class="boring">#![allow(unused)]
+</span>let x = 5;
+# let y = 1;
+
+</code></pre>
+</main>"##;
+        let sections = listing_sections(page);
+
+        assert_eq!(
+            clean_fences(&sections["unused_variables"]),
+            "The `unused_variables` lint detects variables which are not used in any way. See \
+             [unused_results](https://doc.rust-lang.org/rustc/lints/listing/allowed-by-default.html#unused-results).\n\n\
+             ### Example\n\n```rust\n#![allow(unused)]\nlet x = 5;\n```\n"
+        );
+    }
+
+    #[test]
+    fn renders_front_matter() {
+        let lint = Lint {
+            level: "warn".to_owned(),
+            description: "detect unused variables".to_owned(),
+        };
+        let content = render(Some("style"), &lint, Some("1.29.0"), "Explained.\n");
+
+        assert_eq!(
+            content,
+            "---\ngroup: style\nlevel: warn\ndescription: detect unused variables\nsince: 1.29.0\n---\n\nExplained.\n"
+        );
+        assert_eq!(since(&content), Some("1.29.0"));
+        assert_eq!(since("---\nlevel: warn\n---\n\nsince: 1.0.0\n"), None);
+    }
+
+    #[test]
+    fn keeps_non_rust_fences() {
+        assert_eq!(
+            clean_fences("```text\nwarning: unused\n```\n```no_run\nfn f() {}\n```"),
+            "```text\nwarning: unused\n```\n```rust\nfn f() {}\n```\n"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use corro::{Context, Data, Error, commands, on_error, setup_logging};
+use corro::{Context, Data, Error, commands, event_handler, on_error, setup_logging};
 use dotenv::dotenv;
 use log::{debug, info};
 use poise::{
@@ -36,12 +36,26 @@ async fn main() -> Result<(), Box<Error>> {
             commands::run_alias(),
             commands::version(),
             commands::explain(),
+            commands::explain_message(),
             commands::crates(),
             commands::krate(),
+            commands::lint(),
+            commands::feature(),
+            commands::book(),
+            commands::tip(),
+            commands::why(),
             commands::docs(),
+            commands::unsafety(),
+            commands::audit(),
+            commands::refresh_advisories(),
+            commands::reload_docs(),
+            commands::settings(),
         ],
         owners,
         on_error: |err| Box::pin(on_error(err)),
+        event_handler: |ctx, event, framework, data| {
+            Box::pin(event_handler(ctx, event, framework, data))
+        },
         ..Default::default()
     };
     debug!("Configured framework options");
@@ -49,9 +63,10 @@ async fn main() -> Result<(), Box<Error>> {
     // Build and start the Poise framework with the options
     let framework = Framework::builder()
         .options(options)
-        .setup(|_ctx, ready, _framework| {
+        .setup(|ctx, ready, _framework| {
             Box::pin(async move {
                 info!("{} is connected!", ready.user.name);
+                tokio::spawn(commands::post_daily_tips(ctx.http.clone()));
                 Ok(Data::default())
             })
         })
@@ -0,0 +1,194 @@
+//! The tables of contents and headings of The Rust Programming Language, the Reference, Rust by
+//! Example and the Edition Guide, generated from `assets/books` by the build script
+
+use crate::common::fuzzy_score;
+use std::ptr;
+
+/// A book with every section in the order of its table of contents
+#[derive(Debug, PartialEq, Eq)]
+pub struct Book {
+    /// The directory of the book on doc.rust-lang.org, like `rust-by-example`
+    pub name: &'static str,
+    pub title: &'static str,
+    pub url: &'static str,
+    pub sections: &'static [Section],
+}
+
+/// A chapter of a book or a heading inside one
+#[derive(Debug, PartialEq, Eq)]
+pub struct Section {
+    pub title: &'static str,
+    /// The titles of the enclosing chapters and headings, outermost first
+    pub parents: &'static [&'static str],
+    /// The page relative to the book, with the anchor of the heading
+    pub link: &'static str,
+    /// The terms the section introduces in code or emphasis
+    pub keywords: &'static [&'static str],
+    /// The beginning of its first paragraph
+    pub excerpt: &'static str,
+}
+
+include!(concat!(env!("OUT_DIR"), "/books.rs"));
+
+/// A section found by [`search`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hit {
+    pub book: &'static Book,
+    pub section: &'static Section,
+}
+
+impl Hit {
+    pub fn url(&self) -> String {
+        format!("{}{}", self.book.url, self.section.link)
+    }
+
+    /// Like "The Rust Programming Language › Understanding Ownership › What is Ownership?"
+    pub fn breadcrumbs(&self) -> String {
+        let mut path = vec![self.book.title];
+        path.extend(self.section.parents);
+        path.push(self.section.title);
+        path.join(" › ")
+    }
+
+    /// A unique id of the section which fits into an autocomplete choice, unlike most links
+    pub fn id(&self) -> String {
+        let index = self
+            .book
+            .sections
+            .iter()
+            .position(|section| ptr::eq(section, self.section))
+            .unwrap_or_default();
+        format!("{}:{index}", self.book.name)
+    }
+}
+
+/// The book with the directory `name`
+pub fn get(name: &str) -> Option<&'static Book> {
+    BOOKS.iter().find(|book| book.name == name)
+}
+
+/// The section with the id of a [`Hit`]
+pub fn by_id(id: &str) -> Option<Hit> {
+    let (name, index) = id.trim().split_once(':')?;
+    let book = get(name)?;
+    let section = book.sections.get(index.parse::<usize>().ok()?)?;
+    Some(Hit { book, section })
+}
+
+/// The `limit` sections whose titles or keywords match `query` best, in all books or only in the
+/// book called `book`
+pub fn search(query: &str, book: Option<&str>, limit: usize) -> Vec<Hit> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut hits = BOOKS
+        .iter()
+        .enumerate()
+        .filter(|(_, candidate)| book.is_none_or(|name| candidate.name == name))
+        .flat_map(|(rank, book)| {
+            book.sections
+                .iter()
+                .enumerate()
+                .map(move |(position, section)| (rank, position, Hit { book, section }))
+        })
+        .filter_map(|(rank, position, hit)| {
+            Some((score(hit.section, &query)?, rank, position, hit))
+        })
+        .collect::<Vec<_>>();
+    hits.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+    hits.into_iter()
+        .take(limit)
+        .map(|(_, _, _, hit)| hit)
+        .collect()
+}
+
+/// Titles count most, then keywords, then titles or keywords which contain every word of the
+/// query, and chapters count more than the headings inside them
+fn score(section: &Section, query: &str) -> Option<i64> {
+    let title = section.title.to_lowercase();
+    let keywords = section
+        .keywords
+        .iter()
+        .map(|keyword| keyword.to_lowercase())
+        .collect::<Vec<_>>();
+
+    let by_title = fuzzy_score(&title, query);
+    // only exact keywords and prefixes of them, which rank below titles containing the query
+    let by_keyword = keywords
+        .iter()
+        .filter_map(|keyword| fuzzy_score(keyword, query))
+        .filter(|&score| score >= 800)
+        .max()
+        .map(|score| score - 450);
+    let by_words = query
+        .split_whitespace()
+        .all(|word| title.contains(word) || keywords.iter().any(|keyword| keyword.contains(word)))
+        .then_some(500);
+
+    let score = [by_title, by_keyword, by_words]
+        .into_iter()
+        .flatten()
+        .max()?;
+    Some(score - section.parents.len() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexes_every_book() {
+        let names = BOOKS.iter().map(|book| book.name).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["book", "edition-guide", "reference", "rust-by-example"]
+        );
+        for book in BOOKS {
+            assert!(book.url.starts_with("https://doc.rust-lang.org/"));
+            assert!(book.sections.len() > 50, "{}", book.name);
+            for section in book.sections {
+                assert!(!section.title.is_empty(), "{}", book.name);
+                assert!(section.link.contains(".html"), "{}", section.link);
+            }
+        }
+    }
+
+    #[test]
+    fn builds_breadcrumbs() {
+        let hit = search("ownership rules", Some("book"), 1)[0];
+        assert_eq!(hit.section.title, "Ownership Rules");
+        assert_eq!(
+            hit.breadcrumbs(),
+            "The Rust Programming Language › Understanding Ownership › What is Ownership? › Ownership Rules"
+        );
+        assert_eq!(
+            hit.url(),
+            "https://doc.rust-lang.org/book/ch04-01-what-is-ownership.html#ownership-rules"
+        );
+        assert_eq!(by_id(&hit.id()), Some(hit));
+        assert_eq!(by_id("book:100000"), None);
+        assert_eq!(by_id("ch04-01-what-is-ownership.html"), None);
+    }
+
+    #[test]
+    fn ranks_titles_then_keywords() {
+        let titles = |query: &str, book: Option<&str>| {
+            search(query, book, 3)
+                .into_iter()
+                .map(|hit| hit.section.title)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(titles("what is ownership", None)[0], "What is Ownership?");
+        assert_eq!(titles("lifetimes", Some("rust-by-example"))[0], "Lifetimes");
+        assert!(
+            search("rustup", None, 25)
+                .iter()
+                .any(|hit| hit.section.keywords.contains(&"rustup"))
+        );
+        assert!(titles("lifetimes", Some("no-such-book")).is_empty());
+        assert!(titles("  ", None).is_empty());
+    }
+}
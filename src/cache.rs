@@ -21,6 +21,19 @@ impl Client {
         Ok(())
     }
 
+    /// Like [`Client::set`] but the value never expires, used for configuration
+    pub async fn set_persistent<T>(&self, key: &str, value: T) -> Result<(), CacheError>
+    where
+        T: Serialize,
+    {
+        let value = to_string(&value)?;
+
+        let mut conn = self.redis_client.get_multiplexed_tokio_connection().await?;
+        conn.set::<&str, String, ()>(key, value).await?;
+
+        Ok(())
+    }
+
     pub async fn get<U>(&self, key: &str) -> Result<Option<U>, CacheError>
     where
         U: for<'de> Deserialize<'de> + Debug,
@@ -36,6 +49,36 @@ impl Client {
             None => Ok(None),
         }
     }
+
+    /// Adds `member` to the set at `key`, which never expires
+    pub async fn add_member(&self, key: &str, member: &str) -> Result<(), CacheError> {
+        let mut conn = self.redis_client.get_multiplexed_tokio_connection().await?;
+        conn.sadd::<&str, &str, ()>(key, member).await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_member(&self, key: &str, member: &str) -> Result<(), CacheError> {
+        let mut conn = self.redis_client.get_multiplexed_tokio_connection().await?;
+        conn.srem::<&str, &str, ()>(key, member).await?;
+
+        Ok(())
+    }
+
+    /// The members of the set at `key`, empty if there is none
+    pub async fn members(&self, key: &str) -> Result<Vec<String>, CacheError> {
+        let mut conn = self.redis_client.get_multiplexed_tokio_connection().await?;
+        let members: Vec<String> = conn.smembers(key).await?;
+
+        Ok(members)
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<(), CacheError> {
+        let mut conn = self.redis_client.get_multiplexed_tokio_connection().await?;
+        conn.del::<&str, ()>(key).await?;
+
+        Ok(())
+    }
 }
 
 impl Default for Client {
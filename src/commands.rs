@@ -1,13 +1,27 @@
+mod audit;
+mod book;
 mod cargo;
 mod crates;
 mod docs;
 mod explain;
+mod feature;
 mod krate;
+mod lint;
+mod settings;
+mod tip;
+mod unsafety;
 mod version;
 
+pub use audit::{audit, refresh_advisories};
+pub use book::book;
 pub use cargo::{cargo, run_alias};
 pub use crates::crates;
-pub use docs::docs;
-pub use explain::explain;
+pub use docs::{docs, reload_docs};
+pub use explain::{explain, explain_message, show_compiler_output};
+pub use feature::feature;
 pub use krate::krate;
+pub use lint::lint;
+pub use settings::settings;
+pub use tip::{post_daily_tips, run_tip_example, tip, why};
+pub use unsafety::unsafety;
 pub use version::version;
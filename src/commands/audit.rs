@@ -0,0 +1,232 @@
+use crate::{
+    Context, Error,
+    advisories::{ADVISORIES_PATH, AdvisoryDb, OSV_EXPORT_URL},
+    common::extract_code,
+    error::CommandError,
+};
+use log::info;
+use poise::{CreateReply, serenity_prelude::CreateEmbed};
+use regex::Regex;
+use semver::Version;
+use std::{collections::BTreeSet, sync::LazyLock};
+
+static CRATE_PATH_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"\b([A-Za-z_][A-Za-z0-9_]*)\s*::|\bextern\s+crate\s+([A-Za-z_][A-Za-z0-9_]*)")
+        .unwrap_unchecked()
+});
+
+/// Check a crate or the crates used by your code against the RustSec advisory database
+#[poise::command(slash_command)]
+pub async fn audit(
+    ctx: Context<'_>,
+    #[rename = "crate"]
+    #[description = "Name of the crate to check"]
+    name: Option<String>,
+    #[description = "Version of the crate (defaults to the one used by the playground)"]
+    version: Option<String>,
+    #[description = "Code whose playground crates should be checked"] code: Option<String>,
+) -> Result<(), Error> {
+    info!("executing audit");
+
+    if name.is_none() && code.is_none() {
+        return Err(CommandError::NothingToAudit.into());
+    }
+
+    let crates = match ctx.data().redis_client.get("crates").await {
+        Ok(Some(crates)) => crates,
+        Ok(None) => {
+            let res = ctx.data().playground_client.crates().await?;
+            ctx.data().redis_client.set("crates", &res, 86400).await?;
+            res
+        }
+        Err(e) => return Err(Error::Database(e)),
+    };
+
+    let mut targets = Vec::new();
+
+    if let Some(name) = name {
+        let name = name.trim().to_lowercase();
+        let version = match version {
+            Some(version) => Some(
+                Version::parse(version.trim())
+                    .map_err(|_| CommandError::InvalidVersion(version))?,
+            ),
+            None => crates
+                .crates
+                .iter()
+                .find(|krate| krate.name == name)
+                .and_then(|krate| Version::parse(&krate.version).ok()),
+        };
+        targets.push((name, version));
+    }
+
+    if let Some(code) = code {
+        let code = extract_code(&code).unwrap_or(code);
+        let referenced = referenced_crates(&code);
+
+        for krate in crates.crates.iter() {
+            if referenced.contains(&krate.id) {
+                targets.push((krate.name.clone(), Version::parse(&krate.version).ok()));
+            }
+        }
+    }
+
+    let embed = {
+        let db = ctx
+            .data()
+            .advisories
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // an empty database would report every crate as safe
+        if db.is_empty() {
+            return Err(CommandError::AdvisoriesNotLoaded.into());
+        }
+        build_embed(&db, &targets)
+    };
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+
+    Ok(())
+}
+
+/// Replace the bundled advisory database with the current RustSec export
+#[poise::command(prefix_command, owners_only)]
+pub async fn refresh_advisories(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let db = download_advisories().await?;
+    let count = db.len();
+    *ctx.data()
+        .advisories
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = db;
+
+    ctx.say(format!("Loaded {count} advisories")).await?;
+
+    Ok(())
+}
+
+/// Downloads the RustSec export and saves it as the bundled database
+async fn download_advisories() -> Result<AdvisoryDb, Error> {
+    info!("downloading the advisory database from {OSV_EXPORT_URL}");
+    let bytes = reqwest::get(OSV_EXPORT_URL)
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    let db = tokio::task::spawn_blocking(move || AdvisoryDb::from_osv_zip(&bytes)).await??;
+    db.save(ADVISORIES_PATH)?;
+    Ok(db)
+}
+
+fn build_embed(db: &AdvisoryDb, targets: &[(String, Option<Version>)]) -> CreateEmbed {
+    let mut embed = CreateEmbed::new().title("RustSec audit");
+    let mut found = 0;
+
+    for (name, version) in targets {
+        let advisories = match version {
+            Some(version) => db.check(name, version),
+            None => db.for_package(name).iter().collect(),
+        };
+        let label = match version {
+            Some(version) => format!("{name} {version}"),
+            None => format!("{name} (any version)"),
+        };
+
+        for advisory in advisories {
+            found += 1;
+            // embeds can't hold more than 25 fields
+            if found > 25 {
+                continue;
+            }
+
+            let severity = match (advisory.severity(), &advisory.informational) {
+                (_, Some(informational)) => informational.clone(),
+                (Some(severity), None) => severity.to_string(),
+                (None, None) => "unknown".to_owned(),
+            };
+            let patched = advisory.patched();
+            let patched = if patched.is_empty() {
+                "none".to_owned()
+            } else {
+                patched.join(", ")
+            };
+
+            embed = embed.field(
+                format!("{} — {label}", advisory.id),
+                format!(
+                    "{}\n**Severity:** {severity}\n**Patched:** `{patched}`\n[rustsec.org](<{}>)",
+                    advisory.title,
+                    advisory.url()
+                ),
+                false,
+            );
+        }
+    }
+
+    let checked = targets
+        .iter()
+        .map(|(name, _)| format!("`{name}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let snapshot = match db.updated_at {
+        Some(date) => format!("RustSec export from <t:{}:D>", date.timestamp()),
+        None => "RustSec export of unknown date".to_owned(),
+    };
+
+    let description = match (targets.is_empty(), found) {
+        (true, _) => "None of the playground crates are used in your code.".to_owned(),
+        (false, 0) => format!("No advisories found for {checked} ({snapshot})."),
+        (false, n) if n > 25 => {
+            format!("Found {n} advisories for {checked}, showing the first 25 ({snapshot}).")
+        }
+        (false, n) => format!("Found {n} advisories for {checked} ({snapshot})."),
+    };
+
+    let color = if found == 0 { 0x2ecc71 } else { 0xe74c3c };
+
+    embed.description(description).color(color)
+}
+
+/// Names of the crates a piece of code refers to through paths, `use` or `extern crate`
+fn referenced_crates(code: &str) -> BTreeSet<String> {
+    CRATE_PATH_RE
+        .captures_iter(code)
+        .filter_map(|cap| cap.get(1).or_else(|| cap.get(2)))
+        .map(|m| m.as_str().to_owned())
+        .filter(|name| {
+            !matches!(
+                name.as_str(),
+                "std" | "core" | "alloc" | "crate" | "self" | "super"
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn referenced() {
+        let code = r#"
+use rand::Rng;
+extern crate serde;
+use std::collections::HashMap;
+
+fn main() {
+    let re = regex::Regex::new("a").unwrap();
+    let map: HashMap<u8, u8> = HashMap::new();
+    let x = crate::foo();
+}
+"#;
+        let crates = referenced_crates(code);
+
+        assert!(crates.contains("rand"));
+        assert!(crates.contains("serde"));
+        assert!(crates.contains("regex"));
+        assert!(!crates.contains("std"));
+        assert!(!crates.contains("crate"));
+    }
+}
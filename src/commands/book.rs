@@ -0,0 +1,155 @@
+use crate::{
+    Context, Error,
+    books::{self, Hit},
+    common::autocomplete::{MAX_CHOICES, choice, selected},
+    error::CommandError,
+};
+use poise::{
+    ChoiceParameter, CreateReply,
+    serenity_prelude::{AutocompleteChoice, CommandDataOptionValue, CreateEmbed},
+};
+
+/// At most this many sections are listed
+const MAX_SECTIONS: usize = 5;
+
+/// Discord limits embed field names to 256 characters
+const MAX_FIELD_NAME_LENGTH: usize = 256;
+
+/// Find sections of the Book, the Reference, Rust by Example and the Edition Guide
+#[poise::command(slash_command, prefix_command)]
+pub async fn book(
+    ctx: Context<'_>,
+    #[description = "A topic like trait objects or lifetime elision"]
+    #[autocomplete = "autocomplete_section"]
+    #[rest]
+    query: String,
+    #[description = "Book to search, defaults to all of them"] source: Option<Source>,
+) -> Result<(), Error> {
+    // a section picked from the autocompletion is sent as its id
+    let hits = match books::by_id(&query) {
+        Some(hit) => vec![hit],
+        None => books::search(&query, source.map(Source::book), MAX_SECTIONS),
+    };
+    if hits.is_empty() {
+        return Err(CommandError::NoMatch(query).into());
+    }
+
+    let title = match hits.as_slice() {
+        [hit] => hit.section.title.to_owned(),
+        _ => format!("Sections about “{}”", query.trim()),
+    };
+    let mut embed = CreateEmbed::new().title(title).color(0xDEA584);
+    if let [hit] = hits.as_slice() {
+        embed = embed.url(hit.url());
+    }
+    for hit in &hits {
+        embed = embed.field(breadcrumbs(hit), field(hit), false);
+    }
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum Source {
+    #[name = "The Rust Programming Language"]
+    Book,
+    #[name = "The Rust Reference"]
+    Reference,
+    #[name = "Rust by Example"]
+    RustByExample,
+    #[name = "The Rust Edition Guide"]
+    EditionGuide,
+}
+
+impl Source {
+    /// The name of the book in [`books::BOOKS`]
+    fn book(self) -> &'static str {
+        match self {
+            Self::Book => "book",
+            Self::Reference => "reference",
+            Self::RustByExample => "rust-by-example",
+            Self::EditionGuide => "edition-guide",
+        }
+    }
+}
+
+/// The path to the section, cut off at the front when it's too long for a field name
+fn breadcrumbs(hit: &Hit) -> String {
+    let path = hit.breadcrumbs();
+    if path.chars().count() <= MAX_FIELD_NAME_LENGTH {
+        return path;
+    }
+    let skip = path.chars().count() - (MAX_FIELD_NAME_LENGTH - 1);
+    format!("…{}", path.chars().skip(skip).collect::<String>())
+}
+
+/// A link to the section above its excerpt and keywords
+fn field(hit: &Hit) -> String {
+    let mut field = format!("[{}]({})", hit.section.title, hit.url());
+    if !hit.section.excerpt.is_empty() {
+        field.push('\n');
+        field.push_str(hit.section.excerpt);
+    }
+    if !hit.section.keywords.is_empty() {
+        let keywords = hit
+            .section
+            .keywords
+            .iter()
+            .map(|keyword| format!("`{keyword}`"))
+            .collect::<Vec<_>>();
+        field.push_str(&format!("\n**Keywords:** {}", keywords.join(", ")));
+    }
+    field
+}
+
+async fn autocomplete_section(ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    let source = selected(ctx, "source")
+        .and_then(CommandDataOptionValue::as_i64)
+        .and_then(|index| Source::from_index(usize::try_from(index).ok()?));
+
+    books::search(partial, source.map(Source::book), MAX_CHOICES)
+        .into_iter()
+        .map(|hit| choice(choice_name(&hit), &hit.id()))
+        .collect()
+}
+
+/// Like "What is Ownership? — The Rust Programming Language › Understanding Ownership"
+fn choice_name(hit: &Hit) -> String {
+    let mut path = vec![hit.book.title];
+    path.extend(hit.section.parents);
+    format!("{} — {}", hit.section.title, path.join(" › "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_name_books() {
+        for source in [
+            Source::Book,
+            Source::Reference,
+            Source::RustByExample,
+            Source::EditionGuide,
+        ] {
+            assert_eq!(
+                books::get(source.book()).map(|book| book.title),
+                Some(source.name())
+            );
+        }
+    }
+
+    #[test]
+    fn describes_sections() {
+        let hit = books::search("ownership rules", Some("book"), 1)[0];
+        assert_eq!(
+            choice_name(&hit),
+            "Ownership Rules — The Rust Programming Language › Understanding Ownership › What is Ownership?"
+        );
+        assert!(field(&hit).starts_with(
+            "[Ownership Rules](https://doc.rust-lang.org/book/ch04-01-what-is-ownership.html#ownership-rules)\n"
+        ));
+        assert_eq!(breadcrumbs(&hit), hit.breadcrumbs());
+    }
+}
@@ -2,19 +2,23 @@ mod publish;
 use publish::publish;
 
 mod run;
+pub(crate) use run::result_reply;
 pub use run::run_alias;
 use run::run_code_block;
 
 mod miri;
 use miri::miri;
 
+mod edition;
+use edition::edition;
+
 use crate::{Context, Error};
 use poise::command;
 
 #[command(
     prefix_command,
     slash_command,
-    subcommands("run_code_block", "publish", "miri")
+    subcommands("run_code_block", "publish", "miri", "edition")
 )]
 pub async fn cargo(_ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
@@ -0,0 +1,291 @@
+use crate::{
+    Context, Error,
+    common::{EMBED_FIELD_SIZE, extract_code, split_markdown},
+};
+use log::info;
+use playground_api::endpoints::{
+    Channel, CrateType, Edition, ExecuteRequest, ExecuteResponse, Mode,
+};
+use poise::{CreateReply, serenity_prelude::CreateEmbed};
+use regex::Regex;
+use std::{collections::BTreeSet, sync::LazyLock};
+
+const EDITIONS: [&str; 4] = ["2015", "2018", "2021", "2024"];
+
+/// Enables the migration lints on every edition, kept on the first line so line numbers match
+const COMPATIBILITY_LINTS: &str =
+    "#![warn(rust_2018_compatibility, rust_2021_compatibility, rust_2024_compatibility)] ";
+
+const EDITION_GUIDE: &str = "https://doc.rust-lang.org/edition-guide";
+
+/// Lints which are part of an edition migration and the Edition Guide section explaining them
+const EDITION_LINTS: &[(&str, &str)] = &[
+    ("anonymous_parameters", "rust-2018/trait-fn-parameters.html"),
+    (
+        "absolute_paths_not_starting_with_crate",
+        "rust-2018/path-changes.html",
+    ),
+    ("keyword_idents_2018", "rust-2018/new-keywords.html"),
+    (
+        "bare_trait_objects",
+        "rust-2021/warnings-promoted-to-error.html",
+    ),
+    (
+        "ellipsis_inclusive_range_patterns",
+        "rust-2021/warnings-promoted-to-error.html",
+    ),
+    ("array_into_iter", "rust-2021/IntoIterator-for-arrays.html"),
+    (
+        "rust_2021_incompatible_closure_captures",
+        "rust-2021/disjoint-capture-in-closures.html",
+    ),
+    (
+        "rust_2021_incompatible_or_patterns",
+        "rust-2021/or-patterns-macro-rules.html",
+    ),
+    (
+        "rust_2021_prefixes_incompatible_syntax",
+        "rust-2021/reserved-syntax.html",
+    ),
+    ("rust_2021_prelude_collisions", "rust-2021/prelude.html"),
+    ("non_fmt_panics", "rust-2021/panic-macro-consistency.html"),
+    ("keyword_idents_2024", "rust-2024/gen-keyword.html"),
+    (
+        "boxed_slice_into_iter",
+        "rust-2024/intoiterator-box-slice.html",
+    ),
+    (
+        "dependency_on_unit_never_type_fallback",
+        "rust-2024/never-type-fallback.html",
+    ),
+    (
+        "never_type_fallback_flowing_into_unsafe",
+        "rust-2024/never-type-fallback.html",
+    ),
+    (
+        "deprecated_safe_2024",
+        "rust-2024/newly-unsafe-functions.html",
+    ),
+    (
+        "edition_2024_expr_fragment_specifier",
+        "rust-2024/macro-fragment-specifiers.html",
+    ),
+    ("if_let_rescope", "rust-2024/temporary-if-let-scope.html"),
+    (
+        "impl_trait_overcaptures",
+        "rust-2024/rpit-lifetime-capture.html",
+    ),
+    ("missing_unsafe_on_extern", "rust-2024/unsafe-extern.html"),
+    (
+        "rust_2024_guarded_string_incompatible_syntax",
+        "rust-2024/reserved-syntax.html",
+    ),
+    (
+        "rust_2024_incompatible_pat",
+        "rust-2024/match-ergonomics.html",
+    ),
+    ("rust_2024_prelude_collisions", "rust-2024/prelude.html"),
+    ("static_mut_refs", "rust-2024/static-mut-references.html"),
+    (
+        "tail_expr_drop_order",
+        "rust-2024/temporary-tail-expr-scope.html",
+    ),
+    (
+        "unsafe_attr_outside_unsafe",
+        "rust-2024/unsafe-attributes.html",
+    ),
+    (
+        "unsafe_op_in_unsafe_fn",
+        "rust-2024/unsafe-op-in-unsafe-fn.html",
+    ),
+];
+
+static DIAGNOSTIC_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"(?m)^(error|warning)(?:\[(E\d{4})\])?: (.+)$").unwrap_unchecked()
+});
+
+static LINT_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"#\[(?:warn|deny|forbid)\(([a-z0-9_]+)\)\]").unwrap_unchecked()
+});
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Diagnostics {
+    errors: Vec<String>,
+    warnings: Vec<String>,
+    lints: BTreeSet<String>,
+}
+
+/// Compiles a code block on every edition and reports the differences
+#[poise::command(prefix_command, slash_command)]
+pub async fn edition(ctx: Context<'_>, #[rest] input: Option<String>) -> Result<(), Error> {
+    info!("executing edition report");
+    let input = input.unwrap_or_default();
+    let code = extract_code(&input)?;
+
+    ctx.defer().await?;
+
+    let binary = code.contains("fn main");
+    let request = |edition| {
+        ExecuteRequest::new(
+            Channel::Stable,
+            Mode::Debug,
+            edition,
+            if binary {
+                CrateType::Binary
+            } else {
+                CrateType::Library
+            },
+            false,
+            false,
+            format!("{COMPATIBILITY_LINTS}{code}"),
+        )
+    };
+
+    let client = &ctx.data().playground_client;
+    let (r2015, r2018, r2021, r2024) = tokio::join!(
+        client.execute(&request(Edition::Edition2015)),
+        client.execute(&request(Edition::Edition2018)),
+        client.execute(&request(Edition::Edition2021)),
+        client.execute(&request(Edition::Edition2024)),
+    );
+    let results = [r2015?, r2018?, r2021?, r2024?];
+
+    ctx.send(CreateReply::default().embed(build_embed(&results)))
+        .await?;
+
+    Ok(())
+}
+
+fn build_embed(results: &[ExecuteResponse; 4]) -> CreateEmbed {
+    let diagnostics = results
+        .iter()
+        .map(|res| parse_diagnostics(&res.stderr))
+        .collect::<Vec<_>>();
+
+    let mut matrix = String::from("```\nEdition | Result | Errors | Warnings\n");
+    for ((label, res), diag) in EDITIONS.iter().zip(results).zip(&diagnostics) {
+        matrix += &format!(
+            "{label:<7} | {:<6} | {:<6} | {}\n",
+            if res.success { "ok" } else { "failed" },
+            diag.errors.len(),
+            diag.warnings.len()
+        );
+    }
+    matrix += "```";
+
+    let all_succeeded = results.iter().all(|res| res.success);
+    let mut embed = CreateEmbed::new()
+        .title("Edition report")
+        .color(if all_succeeded { 0x2ecc71 } else { 0xe67e22 })
+        .description(matrix);
+
+    for ((label, res), diag) in EDITIONS.iter().zip(results).zip(&diagnostics) {
+        if res.success && diag.warnings.is_empty() {
+            continue;
+        }
+
+        let lines = diag
+            .errors
+            .iter()
+            .map(|error| format!("❌ {error}"))
+            .chain(diag.warnings.iter().map(|warning| format!("⚠️ {warning}")))
+            .collect::<Vec<_>>()
+            .join("\n");
+        embed = embed.field(format!("Edition {label}"), first_field(&lines), false);
+    }
+
+    let lints = diagnostics
+        .iter()
+        .flat_map(|diag| diag.lints.iter())
+        .collect::<BTreeSet<_>>();
+    let guide = lints
+        .iter()
+        .filter_map(|lint| {
+            EDITION_LINTS
+                .iter()
+                .find(|(name, _)| *name == lint.as_str())
+                .map(|(name, page)| format!("[`{name}`](<{EDITION_GUIDE}/{page}>)"))
+        })
+        .collect::<Vec<_>>();
+
+    if !guide.is_empty() {
+        embed = embed.field("Edition lints", first_field(&guide.join("\n")), false);
+    }
+
+    embed
+}
+
+/// As much of `content` as fits into a single embed field
+fn first_field(content: &str) -> String {
+    split_markdown(content, EMBED_FIELD_SIZE)
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+}
+
+fn parse_diagnostics(stderr: &str) -> Diagnostics {
+    let mut diagnostics = Diagnostics::default();
+
+    for cap in DIAGNOSTIC_RE.captures_iter(stderr) {
+        let message = &cap[3];
+        // summary lines like "aborting due to 2 previous errors" are not diagnostics
+        if message.starts_with("aborting due to")
+            || (message.contains("generated") && message.contains("warning"))
+        {
+            continue;
+        }
+
+        let message = match cap.get(2) {
+            Some(code) => format!("`{}` {message}", code.as_str()),
+            None => message.to_owned(),
+        };
+        match &cap[1] {
+            "error" => diagnostics.errors.push(message),
+            _ => diagnostics.warnings.push(message),
+        }
+    }
+
+    diagnostics.lints = LINT_RE
+        .captures_iter(stderr)
+        .map(|cap| cap[1].to_owned())
+        .collect();
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagnostics() {
+        let stderr = r#"   Compiling playground v0.0.1 (/playground)
+warning: trait objects without an explicit `dyn` are deprecated
+ --> src/main.rs:2:15
+  |
+  = note: `#[warn(bare_trait_objects)]` on by default
+
+error[E0425]: cannot find value `async` in this scope
+ --> src/main.rs:3:5
+
+warning: `playground` (bin "playground") generated 1 warning
+error: aborting due to 1 previous error
+"#;
+        let diagnostics = parse_diagnostics(stderr);
+
+        assert_eq!(
+            diagnostics.errors,
+            ["`E0425` cannot find value `async` in this scope"]
+        );
+        assert_eq!(
+            diagnostics.warnings,
+            ["trait objects without an explicit `dyn` are deprecated"]
+        );
+        assert!(diagnostics.lints.contains("bare_trait_objects"));
+    }
+
+    #[test]
+    fn clean() {
+        assert_eq!(parse_diagnostics(""), Diagnostics::default());
+    }
+}
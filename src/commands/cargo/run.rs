@@ -1,11 +1,26 @@
 use crate::{
     Context, Error,
-    common::{extract_32byte_hex, limit_string},
+    common::{
+        extract_32byte_hex, limit_string,
+        reply::{LongReply, send_long},
+        split_content,
+    },
     error::CommandError,
+    features::{self, Feature},
 };
 use log::{debug, info};
-use playground_api::endpoints::{Channel, CrateType, Edition, ExecuteRequest, Mode};
-use poise::{CreateReply, serenity_prelude::Attachment};
+use playground_api::endpoints::{
+    Channel, CrateType, Edition, ExecuteRequest, ExecuteResponse, Mode,
+};
+use poise::serenity_prelude::Attachment;
+use std::time::{Duration, Instant};
+
+/// Embed fields hold 1024 characters, enough for this many feature gates
+const MAX_FEATURE_LINKS: usize = 5;
+
+/// The output is paginated or moved into a thread, this keeps it to about ten pages
+const MAX_OUTPUT_LINES: usize = 1000;
+const MAX_OUTPUT_LENGTH: usize = 40_000;
 
 /// Runs code from a code block in the Rust playground and returns the output
 #[poise::command(
@@ -35,29 +50,15 @@ async fn run_code_block_logic(ctx: Context<'_>, input: Option<String>) -> Result
 
     let code = crate::common::extract_code(&input)?;
     let req = parse_run_command(parameters, code);
-    let res = ctx.data().playground_client.execute(&req).await?;
 
-    let content = limit_string(
-        if res.success {
-            &res.stdout
-        } else {
-            &res.stderr
-        },
-        50,
-        2000,
-    );
-    let reply = if !content.is_empty() {
-        format!(
-            "Running your code returned the following output <@{}>\n```{content}```",
-            ctx.author().id
-        )
-    } else {
-        format!("Running your code gave no output <@{}>", ctx.author().id)
+    let start = Instant::now();
+    let res = ctx.data().playground_client.execute(&req).await?;
+    let reply = LongReply {
+        content: Some(format!("<@{}>", ctx.author().id)),
+        ..result_reply(&req, &res, start.elapsed(), "your code")
     };
 
-    ctx.send(CreateReply::default().content(reply)).await?;
-
-    Ok(())
+    send_long(ctx, reply).await
 }
 
 /// Runs code from a Github gist
@@ -108,32 +109,11 @@ async fn run_gist(
         code: gist.code,
         ..config
     };
-    let res = ctx.data().playground_client.execute(&req).await?;
-    let content = limit_string(
-        if res.success {
-            &res.stdout
-        } else {
-            &res.stderr
-        },
-        50,
-        2000,
-    );
-
-    let reply = if !content.is_empty() {
-        format!(
-            "Running the code from [#{}](<{}>) gave the following output\n```{content}```",
-            gist.id, gist.url
-        )
-    } else {
-        format!(
-            "Running the code from [#{}](<{}>) gave no output",
-            gist.id, gist.url
-        )
-    };
 
-    ctx.send(CreateReply::default().content(reply)).await?;
-
-    Ok(())
+    let start = Instant::now();
+    let res = ctx.data().playground_client.execute(&req).await?;
+    let source = format!("the code from [#{}](<{}>)", gist.id, gist.url);
+    send_long(ctx, result_reply(&req, &res, start.elapsed(), &source)).await
 }
 
 /// Runs code from a Rust source file upload
@@ -176,32 +156,173 @@ async fn run_file(
     let code = String::from_utf8(file_content).map_err(|_| CommandError::NotValidUTF8)?;
 
     let req = ExecuteRequest { code, ..config };
+
+    let start = Instant::now();
     let res = ctx.data().playground_client.execute(&req).await?;
-    let content = limit_string(
+    let source = format!("the code from [{}](<{}>)", file.filename, file.url);
+    send_long(ctx, result_reply(&req, &res, start.elapsed(), &source)).await
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Compilation,
+    Runtime,
+}
+
+/// Renders the output of an execution together with the configuration it ran with
+pub(crate) fn result_reply(
+    req: &ExecuteRequest,
+    res: &ExecuteResponse,
+    duration: Duration,
+    source: &str,
+) -> LongReply {
+    let stage = stage(&res.stderr);
+    let output = limit_string(
         if res.success {
             &res.stdout
         } else {
             &res.stderr
         },
-        50,
-        2000,
+        MAX_OUTPUT_LINES,
+        MAX_OUTPUT_LENGTH,
     );
 
-    let reply = if !content.is_empty() {
-        format!(
-            "Running the code from [{}](<{}>) gave the following output\n```{content}```",
-            file.filename, file.url
-        )
+    let (title, sentence, color) = match (res.success, stage) {
+        (true, _) => ("Output", format!("Ran {source}"), 0x2ecc71),
+        (false, Stage::Compilation) => (
+            "Compilation failed",
+            format!("Failed to compile {source}"),
+            0xe74c3c,
+        ),
+        (false, Stage::Runtime) => (
+            "Runtime failure",
+            format!("Running {source} failed"),
+            0xe67e22,
+        ),
+    };
+
+    let description = if output.is_empty() {
+        format!("{sentence} without any output")
     } else {
-        format!(
-            "Running the code from [{}](<{}>) gave no output",
-            file.filename, file.url
-        )
+        format!("{sentence}\n```\n{output}\n```")
     };
 
-    ctx.send(CreateReply::default().content(reply)).await?;
+    let mut fields = vec![
+        ("Channel", format!("{:?}", req.channel), true),
+        ("Mode", format!("{:?}", req.mode), true),
+        (
+            "Edition",
+            format!("{:?}", req.edition)
+                .trim_start_matches("Edition")
+                .to_owned(),
+            true,
+        ),
+        ("Crate type", format!("{:?}", req.crate_type), true),
+        (
+            "Tests",
+            if req.tests { "yes" } else { "no" }.to_owned(),
+            true,
+        ),
+        ("Duration", format!("{:.2}s", duration.as_secs_f64()), true),
+    ];
+
+    if !res.success {
+        let stage_name = match stage {
+            Stage::Compilation => "compilation",
+            Stage::Runtime => "runtime",
+        };
+        fields.push(("Failed during", stage_name.to_owned(), true));
+
+        if let Some(explanation) = explain_failure(&res.exit_detail, &res.stderr) {
+            fields.push(("What happened?", explanation.to_owned(), false));
+        }
+
+        let features = features::in_diagnostics(&res.stderr);
+        if !features.is_empty() {
+            fields.push(("Feature gates", feature_links(&features), false));
+        }
+    }
 
-    Ok(())
+    LongReply {
+        title: title.to_owned(),
+        color: color.into(),
+        pages: split_content(description),
+        fields: fields
+            .into_iter()
+            .map(|(name, value, inline)| (name.to_owned(), value, inline))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// A line per feature gate an `E0658` error mentions, linking its documentation
+fn feature_links(features: &[&Feature]) -> String {
+    features
+        .iter()
+        .take(MAX_FEATURE_LINKS)
+        .map(|feature| {
+            let name = match feature.url().or_else(|| feature.issue_url()) {
+                Some(url) => format!("[`{}`]({url})", feature.name),
+                None => format!("`{}`", feature.name),
+            };
+            format!(
+                "{name} ({}), see `/feature {}`",
+                feature.status, feature.name
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The playground only prints `Running` once the build finished successfully, followed by the
+/// binary like ``Running `target/debug/playground` `` or, in test mode, by the test harness like
+/// `Running unittests src/main.rs (..)`
+fn stage(stderr: &str) -> Stage {
+    if stderr
+        .lines()
+        .any(|line| line.trim_start().starts_with("Running "))
+    {
+        Stage::Runtime
+    } else {
+        Stage::Compilation
+    }
+}
+
+/// A human readable explanation for well-known ways a program can die
+fn explain_failure(exit_detail: &str, stderr: &str) -> Option<&'static str> {
+    if stderr.contains("has overflowed its stack") {
+        return Some(
+            "The program overflowed its stack, usually because of unbounded recursion or a very large value on the stack.",
+        );
+    }
+
+    if stderr.contains("memory allocation of") {
+        return Some("The program ran out of memory (OOM) while trying to allocate.");
+    }
+
+    if exit_detail.contains("SIGKILL") || stderr.contains("Killed") {
+        return Some(
+            "The process was killed, most likely because it exceeded the playground's time or memory limit.",
+        );
+    }
+
+    if exit_detail.contains("SIGSEGV") {
+        return Some(
+            "The program crashed with a segmentation fault (SIGSEGV), which means it accessed invalid memory. This is usually caused by faulty `unsafe` code.",
+        );
+    }
+
+    if exit_detail.contains("101") && stderr.contains("test result: FAILED") {
+        return Some("At least one test failed.");
+    }
+
+    if exit_detail.contains("101") && stderr.contains("panicked at") {
+        return Some(
+            "The program panicked (exit code 101). The message above shows where and why.",
+        );
+    }
+
+    None
 }
 
 fn parse_run_command(command: &str, code: String) -> ExecuteRequest {
@@ -228,3 +349,73 @@ fn parse_run_command(command: &str, code: String) -> ExecuteRequest {
     config.code = code.to_owned();
     config
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages() {
+        let compile_error = "   Compiling playground v0.0.1 (/playground)\nerror[E0425]: cannot find value `x` in this scope\n";
+        let runtime_error = "   Compiling playground v0.0.1 (/playground)\n    Finished `dev` profile [unoptimized + debuginfo] target(s) in 0.52s\n     Running `target/debug/playground`\nthread 'main' panicked at src/main.rs:2:5:\n";
+
+        let test_failure = "   Compiling playground v0.0.1 (/playground)\n    Finished `test` profile [unoptimized + debuginfo] target(s) in 0.61s\n     Running unittests src/lib.rs (target/debug/deps/playground-6e9d5ac87a2bbd8c)\nerror: test failed, to rerun pass `--lib`\n";
+
+        assert_eq!(stage(compile_error), Stage::Compilation);
+        assert_eq!(stage(runtime_error), Stage::Runtime);
+        assert_eq!(stage(test_failure), Stage::Runtime);
+    }
+
+    #[test]
+    fn links_feature_gates() {
+        let stderr = "   Compiling playground v0.0.1 (/playground)
+error[E0658]: use of unstable library feature `iter_intersperse`
+ --> src/main.rs:2:40
+  |
+  = note: see issue #79524 <https://github.com/rust-lang/rust/issues/79524> for more information
+";
+        assert_eq!(
+            feature_links(&features::in_diagnostics(stderr)),
+            "[`iter_intersperse`](https://doc.rust-lang.org/nightly/unstable-book/library-features/iter-intersperse.html) (unstable), see `/feature iter_intersperse`"
+        );
+    }
+
+    #[test]
+    fn explanations() {
+        assert!(
+            explain_failure(
+                "Exit status: 101",
+                "thread 'main' panicked at src/main.rs:2:5:"
+            )
+            .unwrap()
+            .contains("panicked")
+        );
+        assert!(
+            explain_failure(
+                "signal: 6 (SIGABRT: process abort signal)",
+                "thread 'main' has overflowed its stack"
+            )
+            .unwrap()
+            .contains("stack")
+        );
+        assert!(
+            explain_failure("signal: 11 (SIGSEGV: invalid memory reference)", "")
+                .unwrap()
+                .contains("SIGSEGV")
+        );
+        assert!(
+            explain_failure("signal: 9 (SIGKILL: kill)", "")
+                .unwrap()
+                .contains("killed")
+        );
+        assert!(
+            explain_failure(
+                "Exit status: 1",
+                "memory allocation of 1099511627776 bytes failed"
+            )
+            .unwrap()
+            .contains("memory")
+        );
+        assert_eq!(explain_failure("Exit status: 1", ""), None);
+    }
+}
@@ -1,45 +1,712 @@
-use crate::{Context, Error, error::CommandError};
-use docsrs::Item;
-use poise::{CreateReply, serenity_prelude::CreateEmbed};
+use crate::{
+    Context, Error,
+    common::{EMBED_FIELD_SIZE, autocomplete::selected, split_markdown},
+    doc_sets::{DOCS_PATH, DocSet, SNAPSHOTS_PATH, Unavailable, channel_name},
+    error::CommandError,
+    rustdoc::{
+        Category, DocIndex, Hit, Member, MemberList, Sections, Signature, resolve, search,
+        search_signature, sections,
+    },
+};
+use log::info;
+use playground_api::endpoints::Channel;
+use poise::{
+    ChoiceParameter, CreateReply,
+    serenity_prelude::{
+        ButtonStyle, CommandDataOptionValue, ComponentInteractionCollector,
+        ComponentInteractionDataKind, CreateActionRow, CreateButton, CreateEmbed,
+        CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
+        CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption,
+    },
+};
+use std::{path::Path, sync::Arc, time::Duration};
+
+/// How many matches are offered in the picker
+const MAX_RESULTS: usize = 10;
+
+/// The components are removed after this long without interaction
+const PICKER_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Discord allows at most 25 options in a select menu
+const MAX_SELECT_OPTIONS: usize = 25;
+
+/// Discord limits select menu labels and autocomplete values to 100 characters
+const MAX_LABEL_LENGTH: usize = 100;
+
+/// Leaves room for the section fields within the total embed size
+const MAX_DESCRIPTION_LENGTH: usize = 2048;
+
+const MAX_SIGNATURE_LENGTH: usize = 1000;
+
+/// Sections shown as fields below the description, in this order
+const SHOWN_SECTIONS: [&str; 3] = ["Safety", "Panics", "Examples"];
 
 #[poise::command(slash_command)]
 pub async fn docs(
     ctx: Context<'_>,
-    #[description = "Choose crate"] source: Krate,
-    #[description = "Search query"] query: String,
+    #[description = "Search query"]
+    #[autocomplete = "autocomplete_docs"]
+    query: Option<String>,
+    #[description = "Function type, like fn(&str) -> usize"] signature: Option<String>,
+    #[description = "Crate to search, defaults to std, core and alloc"] source: Option<Krate>,
+    #[description = "Toolchain channel, defaults to stable"] channel: Option<Channel>,
+    #[description = "Search a crate from docs.rs instead"]
+    #[rename = "crate"]
+    crate_name: Option<String>,
+    #[description = "Version of the docs.rs crate, defaults to the newest"] version: Option<String>,
 ) -> Result<(), Error> {
-    let item = match source {
-        Krate::Std => ctx.data().std.search(&query, 1),
-        Krate::Core => ctx.data().core.search(&query, 1),
-        Krate::Alloc => ctx.data().alloc.search(&query, 1),
+    let (query, signature) = match (query, signature) {
+        (Some(query), None) => (query, None),
+        (None, Some(query)) => {
+            let signature = Signature::parse(&query)
+                .ok_or_else(|| CommandError::InvalidSignature(query.clone()))?;
+            (query, Some(signature))
+        }
+        _ => return Err(CommandError::QueryOrSignature.into()),
+    };
+
+    let remote = match crate_name {
+        Some(name) => {
+            let name = crate_name_of(&name)?;
+            ctx.defer().await?;
+            let version = match version {
+                Some(version) => semver::Version::parse(version.trim())
+                    .map_err(|_| CommandError::InvalidVersion(version))?
+                    .to_string(),
+                None => latest_version(ctx, &name).await?,
+            };
+
+            let Some(index) = ctx.data().docs_rs.index(&name, &version).await? else {
+                ctx.send(
+                    CreateReply::default().embed(fallback_embed(ctx, &name, &version, &query)),
+                )
+                .await?;
+                return Ok(());
+            };
+            Some(index)
+        }
+        None => None,
+    };
+
+    // a docs.rs crate doesn't need the toolchain docs, they only help to open its std members
+    let set = match remote {
+        Some(_) => doc_set(ctx, channel.as_ref()).ok(),
+        None => Some(doc_set(ctx, channel.as_ref())?),
+    };
+    let searched = match (&remote, &set) {
+        (Some(index), _) => vec![&**index],
+        (None, Some(set)) => indices(set, source),
+        (None, None) => Vec::new(),
+    };
+    let results = match &signature {
+        Some(signature) => search_signature(&searched, signature, MAX_RESULTS),
+        None => search(&searched, &query, MAX_RESULTS),
     };
 
-    let Some(item) = item else {
+    if results.is_empty() {
         return Err(CommandError::NoMatch(query).into());
+    }
+
+    let mut browser = Browser::new(results);
+
+    if browser.results.len() == 1 && browser.lists.is_empty() {
+        ctx.send(CreateReply::default().embed(browser.embed()))
+            .await?;
+        return Ok(());
+    }
+
+    // members may live in any of the crates, like the trait implementations of a std type
+    let everywhere = remote
+        .iter()
+        .map(|index| &**index)
+        .chain(set.iter().flat_map(|set| indices(set, None)))
+        .collect::<Vec<_>>();
+    let ids = Ids::new(ctx.id());
+    let handle = ctx
+        .send(
+            CreateReply::default()
+                .embed(browser.embed())
+                .components(browser.components(&ids)),
+        )
+        .await?;
+    let message_id = handle.message().await?.id;
+
+    while let Some(press) = ComponentInteractionCollector::new(ctx)
+        .message_id(message_id)
+        .timeout(PICKER_TIMEOUT)
+        .await
+    {
+        if press.user.id != ctx.author().id {
+            press
+                .create_response(
+                    ctx,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .ephemeral(true)
+                            .content("Only the person who used the command can browse the docs."),
+                    ),
+                )
+                .await?;
+            continue;
+        }
+
+        let choice = match &press.data.kind {
+            ComponentInteractionDataKind::StringSelect { values } => {
+                values.first().and_then(|value| value.parse::<usize>().ok())
+            }
+            _ => None,
+        };
+        let id = &press.data.custom_id;
+        if *id == ids.result {
+            browser.pick_result(choice);
+        } else if *id == ids.list {
+            browser.open_list(choice);
+        } else if *id == ids.member {
+            browser.open_member(choice, &everywhere);
+        } else if *id == ids.prev {
+            browser.turn_page(false);
+        } else if *id == ids.next {
+            browser.turn_page(true);
+        } else if *id == ids.back {
+            browser.back();
+        } else {
+            continue;
+        }
+
+        press
+            .create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .embed(browser.embed())
+                        .components(browser.components(&ids)),
+                ),
+            )
+            .await?;
+    }
+
+    handle
+        .edit(
+            ctx,
+            CreateReply::default()
+                .embed(browser.embed())
+                .components(vec![]),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// The state of a `/docs` reply, which can switch between the results and browse the members
+/// of the shown item
+struct Browser<'a> {
+    results: Vec<Hit<'a>>,
+    /// The result shown, `None` after opening a member
+    selected: Option<usize>,
+    current: Hit<'a>,
+    lists: Vec<MemberList>,
+    view: View,
+    /// The items left by opening a member, for the back button
+    history: Vec<(Option<usize>, Hit<'a>)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum View {
+    Item,
+    Members { list: usize, page: usize },
+}
+
+impl<'a> Browser<'a> {
+    fn new(results: Vec<Hit<'a>>) -> Self {
+        let current = results[0];
+        Self {
+            lists: members(&current),
+            results,
+            selected: Some(0),
+            current,
+            view: View::Item,
+            history: Vec::new(),
+        }
+    }
+
+    fn show(&mut self, selected: Option<usize>, hit: Hit<'a>) {
+        self.selected = selected;
+        self.current = hit;
+        self.lists = members(&hit);
+        self.view = View::Item;
+    }
+
+    fn pick_result(&mut self, choice: Option<usize>) {
+        if let Some(choice) = choice
+            && let Some(&hit) = self.results.get(choice)
+        {
+            self.history.clear();
+            self.show(Some(choice), hit);
+        }
+    }
+
+    fn open_list(&mut self, choice: Option<usize>) {
+        if let Some(list) = choice.filter(|&list| list < self.lists.len()) {
+            self.view = View::Members { list, page: 0 };
+        }
+    }
+
+    fn open_member(&mut self, choice: Option<usize>, indices: &[&'a DocIndex]) {
+        let View::Members { list, .. } = self.view else {
+            return;
+        };
+        let hit = choice
+            .and_then(|choice| self.lists[list].members.get(choice))
+            .and_then(|member| member.id.as_ref())
+            .and_then(|id| resolve(indices, self.current.index, id));
+
+        if let Some(hit) = hit {
+            self.history.push((self.selected, self.current));
+            self.show(None, hit);
+        }
+    }
+
+    fn turn_page(&mut self, forward: bool) {
+        if let View::Members { list, page } = self.view {
+            let page = if forward {
+                (page + 1).min(self.pages(list) - 1)
+            } else {
+                page.saturating_sub(1)
+            };
+            self.view = View::Members { list, page };
+        }
+    }
+
+    /// Leaves a member list, or returns to the item a member was opened from
+    fn back(&mut self) {
+        match self.view {
+            View::Members { .. } => self.view = View::Item,
+            View::Item => {
+                if let Some((selected, hit)) = self.history.pop() {
+                    self.show(selected, hit);
+                }
+            }
+        }
+    }
+
+    fn pages(&self, list: usize) -> usize {
+        self.lists[list].members.len().div_ceil(MAX_SELECT_OPTIONS)
+    }
+
+    /// The members on `page` of a list with their position in the list
+    fn page(&self, list: usize, page: usize) -> impl Iterator<Item = (usize, &Member)> {
+        self.lists[list]
+            .members
+            .iter()
+            .enumerate()
+            .skip(page * MAX_SELECT_OPTIONS)
+            .take(MAX_SELECT_OPTIONS)
+    }
+
+    fn embed(&self) -> CreateEmbed {
+        match self.view {
+            View::Item => item_embed(&self.current),
+            View::Members { list, page } => {
+                let category = self.lists[list].category;
+                let description = self
+                    .page(list, page)
+                    .map(|(_, member)| match category {
+                        Category::Items => {
+                            format!("`{}` *{}*", truncate(&member.label), member.kind)
+                        }
+                        _ => format!("`{}`", truncate(&member.label)),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+
+                let embed = CreateEmbed::new()
+                    .title(format!("{} · {category}", self.current.path))
+                    .url(self.current.url())
+                    .color(crate_color(self.current.origin_crate()))
+                    .description(description);
+                match self.pages(list) {
+                    1 => embed,
+                    pages => {
+                        embed.footer(CreateEmbedFooter::new(format!("Page {}/{pages}", page + 1)))
+                    }
+                }
+            }
+        }
+    }
+
+    fn components(&self, ids: &Ids) -> Vec<CreateActionRow> {
+        let mut rows = Vec::new();
+        if self.results.len() > 1 {
+            rows.push(picker(&ids.result, &self.results, self.selected));
+        }
+
+        match self.view {
+            View::Item => {
+                if !self.lists.is_empty() {
+                    let options = self
+                        .lists
+                        .iter()
+                        .enumerate()
+                        .map(|(i, list)| {
+                            CreateSelectMenuOption::new(
+                                format!("{} ({})", list.category, list.members.len()),
+                                i.to_string(),
+                            )
+                        })
+                        .collect();
+                    rows.push(select(&ids.list, options, "Browse members"));
+                }
+                if !self.history.is_empty() {
+                    rows.push(CreateActionRow::Buttons(vec![back_button(&ids.back)]));
+                }
+            }
+            View::Members { list, page } => {
+                // members without a page of their own, like primitive implementors, are only listed
+                let options = self
+                    .page(list, page)
+                    .filter(|(_, member)| member.id.is_some())
+                    .map(|(i, member)| {
+                        CreateSelectMenuOption::new(truncate(&member.label), i.to_string())
+                            .description(member.kind.to_string())
+                    })
+                    .collect::<Vec<_>>();
+                if !options.is_empty() {
+                    rows.push(select(&ids.member, options, "Open a member"));
+                }
+
+                let pages = self.pages(list);
+                let mut buttons = Vec::new();
+                if pages > 1 {
+                    buttons.push(
+                        CreateButton::new(&ids.prev)
+                            .emoji('◀')
+                            .style(ButtonStyle::Secondary)
+                            .disabled(page == 0),
+                    );
+                    buttons.push(
+                        CreateButton::new(&ids.next)
+                            .emoji('▶')
+                            .style(ButtonStyle::Secondary)
+                            .disabled(page + 1 == pages),
+                    );
+                }
+                buttons.push(back_button(&ids.back));
+                rows.push(CreateActionRow::Buttons(buttons));
+            }
+        }
+
+        rows
+    }
+}
+
+fn members(hit: &Hit) -> Vec<MemberList> {
+    hit.item()
+        .map(|item| hit.index.members(item))
+        .unwrap_or_default()
+}
+
+struct Ids {
+    result: String,
+    list: String,
+    member: String,
+    prev: String,
+    next: String,
+    back: String,
+}
+
+impl Ids {
+    fn new(ctx_id: u64) -> Self {
+        Self {
+            result: format!("{ctx_id}docs"),
+            list: format!("{ctx_id}list"),
+            member: format!("{ctx_id}member"),
+            prev: format!("{ctx_id}prev"),
+            next: format!("{ctx_id}next"),
+            back: format!("{ctx_id}back"),
+        }
+    }
+}
+
+/// Completes from the chosen docs.rs crate only if it is already loaded, autocomplete has to be
+/// too quick for a download
+async fn autocomplete_docs(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    let remote = match selected(ctx, "crate")
+        .and_then(CommandDataOptionValue::as_str)
+        .and_then(|name| crate_name_of(name).ok())
+    {
+        Some(name) => {
+            let version = match selected(ctx, "version").and_then(CommandDataOptionValue::as_str) {
+                Some(version) => Some(version.trim().to_owned()),
+                None => ctx
+                    .data()
+                    .redis_client
+                    .get::<String>(&version_key(&name))
+                    .await
+                    .ok()
+                    .flatten(),
+            };
+            match version.and_then(|version| ctx.data().docs_rs.cached(&name, &version)) {
+                Some(index) => Some(index),
+                None => return Vec::new(),
+            }
+        }
+        None => None,
+    };
+
+    let set;
+    let searched = match &remote {
+        Some(index) => vec![&**index],
+        None => {
+            let channel = selected(ctx, "channel").and_then(channel_of);
+            let Ok(loaded) = doc_set(ctx, channel.as_ref()) else {
+                return Vec::new();
+            };
+            set = loaded;
+            indices(
+                &set,
+                selected(ctx, "source")
+                    .and_then(CommandDataOptionValue::as_i64)
+                    .and_then(|index| Krate::from_index(index as usize)),
+            )
+        }
     };
 
-    let item = item[0].clone();
-    let Item { name, docs, .. } = item;
+    search(&searched, partial, 25)
+        .into_iter()
+        .map(|hit| hit.path.to_owned())
+        .filter(|path| path.len() <= MAX_LABEL_LENGTH)
+        .collect()
+}
+
+/// Crate names are ASCII letters, digits, `-` and `_`, anything else can't be on docs.rs
+fn crate_name_of(name: &str) -> Result<String, CommandError> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty()
+        || name.len() > 64
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(CommandError::CrateNotFound(name));
+    }
+    Ok(name)
+}
+
+fn version_key(name: &str) -> String {
+    format!("docs_version::{name}")
+}
+
+/// The newest version of a crate, cached for an hour
+async fn latest_version(ctx: Context<'_>, name: &str) -> Result<String, Error> {
+    let key = version_key(name);
+    if let Some(version) = ctx.data().redis_client.get(&key).await? {
+        return Ok(version);
+    }
 
-    let mut embed = CreateEmbed::new()
-        .title(name)
-        .description(docs.unwrap_or_default());
+    let version = ctx
+        .data()
+        .crates_io_client
+        .get_crate(name)
+        .await
+        .map_err(|e| match e {
+            crates_io_api::Error::NotFound(_) => {
+                CommandError::CrateNotFound(name.to_owned()).into()
+            }
+            other => Error::CratesIO(other),
+        })?
+        .crate_data
+        .max_version;
+    ctx.data().redis_client.set(&key, &version, 3600).await?;
 
-    let color = match source {
-        Krate::Std => 0x1E88E5,
-        Krate::Alloc => 0x8E24AA,
-        Krate::Core => 0xF4511E,
+    Ok(version)
+}
+
+/// Points to the search on docs.rs for crates built without rustdoc JSON
+fn fallback_embed(ctx: Context<'_>, name: &str, version: &str, query: &str) -> CreateEmbed {
+    let url = ctx.data().docs_rs.search_url(name, version, query);
+    CreateEmbed::new()
+        .title(format!("{name} {version}"))
+        .url(&url)
+        .description(format!(
+            "docs.rs has no rustdoc JSON for this version, so it can't be searched here.\n\n[Search for `{query}` on docs.rs]({url})"
+        ))
+        .color(crate_color(name))
+}
+
+/// The docs of `channel`, stable if none was chosen
+fn doc_set(ctx: Context<'_>, channel: Option<&Channel>) -> Result<Arc<DocSet>, CommandError> {
+    let channel = channel.unwrap_or(&Channel::Stable);
+    ctx.data().docs.get(channel).map_err(|e| match e {
+        Unavailable::Loading => CommandError::DocsLoading,
+        Unavailable::Missing => CommandError::DocsNotLoaded(channel_name(channel)),
+    })
+}
+
+/// The channel chosen while autocompleting, which arrives as its choice index or its name
+fn channel_of(value: &CommandDataOptionValue) -> Option<Channel> {
+    let name = match (value.as_i64(), value.as_str()) {
+        (Some(index), _) => ["stable", "beta", "nightly"].get(index as usize).copied()?,
+        (None, Some(name)) => name,
+        (None, None) => return None,
     };
-    embed = embed.color(color);
 
-    ctx.send(CreateReply::default().embed(embed)).await?;
+    match name.to_lowercase().as_str() {
+        "stable" => Some(Channel::Stable),
+        "beta" => Some(Channel::Beta),
+        "nightly" => Some(Channel::Nightly),
+        _ => None,
+    }
+}
+
+/// The indices searched for `source`, all of them if no crate was chosen
+fn indices(set: &DocSet, source: Option<Krate>) -> Vec<&DocIndex> {
+    match source {
+        Some(Krate::Std) => vec![&set.std],
+        Some(Krate::Core) => vec![&set.core],
+        Some(Krate::Alloc) => vec![&set.alloc],
+        None => vec![&set.std, &set.core, &set.alloc],
+    }
+}
+
+/// Load the docs of a channel from disk again, after its JSON files were replaced
+#[poise::command(prefix_command, owners_only)]
+pub async fn reload_docs(ctx: Context<'_>, channel: Channel) -> Result<(), Error> {
+    let name = channel_name(&channel);
+    info!("reloading the {name} docs from {DOCS_PATH}");
+    ctx.defer().await?;
+
+    let set = DocSet::load(Path::new(DOCS_PATH), Path::new(SNAPSHOTS_PATH), &channel).await?;
+    let version = set.version().map(str::to_owned);
+    ctx.data().docs.replace(set);
+
+    match version {
+        Some(version) => {
+            ctx.say(format!("Loaded the {name} docs for {version}"))
+                .await?
+        }
+        None => ctx.say(format!("Loaded the {name} docs")).await?,
+    };
 
     Ok(())
 }
 
+fn item_embed(hit: &Hit) -> CreateEmbed {
+    let mut description = String::new();
+    if let Some(signature) = hit.signature() {
+        description += &format!(
+            "```rust\n{}\n```\n",
+            first_piece(&signature, MAX_SIGNATURE_LENGTH)
+        );
+    }
+    description += &format!("*{}*", hit.entry.kind);
+    if hit.entry.origin != hit.path {
+        description += &format!(" · defined in `{}`", hit.entry.origin);
+    }
+
+    let embed = CreateEmbed::new()
+        .title(hit.path)
+        .url(hit.url())
+        .color(crate_color(hit.origin_crate()));
+
+    let Some(docs) = hit.docs() else {
+        return embed.description(description);
+    };
+    let Sections { intro, sections } = sections(&docs);
+
+    let room = MAX_DESCRIPTION_LENGTH.saturating_sub(description.len() + 2);
+    if !intro.is_empty() {
+        description += "\n\n";
+        description += &first_piece(&intro, room);
+    }
+    let mut embed = embed.description(description);
+
+    for name in SHOWN_SECTIONS {
+        if let Some((title, body)) = sections.iter().find(|(title, _)| title == name)
+            && !body.is_empty()
+        {
+            embed = embed.field(title, first_piece(body, EMBED_FIELD_SIZE), false);
+        }
+    }
+
+    let others = sections
+        .iter()
+        .map(|(title, _)| title.as_str())
+        .filter(|title| !SHOWN_SECTIONS.contains(title))
+        .collect::<Vec<_>>();
+    if !others.is_empty() {
+        embed = embed.footer(CreateEmbedFooter::new(format!(
+            "More on the page: {}",
+            others.join(", ")
+        )));
+    }
+
+    embed
+}
+
+/// As much of `content` as fits into `limit` bytes
+fn first_piece(content: &str, limit: usize) -> String {
+    split_markdown(content, limit)
+        .into_iter()
+        .next()
+        .unwrap_or_default()
+}
+
+fn picker(custom_id: &str, results: &[Hit], selected: Option<usize>) -> CreateActionRow {
+    let options = results
+        .iter()
+        .enumerate()
+        .map(|(i, hit)| {
+            CreateSelectMenuOption::new(truncate(hit.path), i.to_string())
+                .description(format!("{} from {}", hit.entry.kind, hit.origin_crate()))
+                .default_selection(selected == Some(i))
+        })
+        .collect();
+
+    select(custom_id, options, "Show another match")
+}
+
+fn select(
+    custom_id: &str,
+    options: Vec<CreateSelectMenuOption>,
+    placeholder: &str,
+) -> CreateActionRow {
+    CreateActionRow::SelectMenu(
+        CreateSelectMenu::new(custom_id, CreateSelectMenuKind::String { options })
+            .placeholder(placeholder),
+    )
+}
+
+fn back_button(custom_id: &str) -> CreateButton {
+    CreateButton::new(custom_id)
+        .label("Back")
+        .style(ButtonStyle::Secondary)
+}
+
+/// Keeps the end of long paths since the item name is the interesting part
+fn truncate(path: &str) -> String {
+    if path.len() <= MAX_LABEL_LENGTH {
+        return path.to_owned();
+    }
+
+    let mut start = path.len() - (MAX_LABEL_LENGTH - 1);
+    while !path.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("…{}", &path[start..])
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
 pub enum Krate {
     Std,
     Core,
     Alloc,
 }
+
+/// Colors the embed after the crate which defines the item
+fn crate_color(name: &str) -> u32 {
+    match name {
+        "alloc" => 0x8E24AA,
+        "core" => 0xF4511E,
+        _ => 0x1E88E5,
+    }
+}
@@ -1,564 +1,384 @@
-use crate::{Context, Error, common::split_content, error::CommandError};
-use poise::{self, CreateReply, serenity_prelude::CreateEmbed};
-use std::{fs, str::FromStr};
-use strum::IntoEnumIterator;
+mod diagnostics;
+use diagnostics::{Mention, mentions};
 
-/// Get an explanation for a specified rust compiler error
+mod example;
+
+use crate::{
+    Context, Data, Error,
+    common::{
+        EMBED_FIELD_SIZE,
+        autocomplete::{MAX_CHOICES, choice},
+        limit_string,
+        reply::{LongReply, send_long},
+        split_content,
+    },
+    doc_sets::channel_name,
+    error::CommandError,
+    error_codes::{self, ErrorCode},
+};
+use playground_api::endpoints::{Channel, CrateType, Edition, ExecuteRequest, Mode};
+use poise::{
+    CreateReply,
+    serenity_prelude::{
+        self as serenity, AutocompleteChoice, ButtonStyle, ComponentInteraction,
+        ComponentInteractionCollector, CreateActionRow, CreateButton, CreateEmbed,
+        CreateInteractionResponse, CreateInteractionResponseMessage, EditInteractionResponse,
+        Message,
+    },
+};
+use std::time::Duration;
+
+/// At most this many codes of a text are summarized
+const MAX_CODES: usize = 10;
+
+/// Discord allows at most 5 buttons in a row
+const BUTTONS_PER_ROW: usize = 5;
+
+const MAX_SUMMARY_LENGTH: usize = 600;
+
+/// The buttons are removed after this long without interaction
+const BUTTON_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The custom id of a "Show real compiler output" button is this followed by the error code and
+/// the channel, so the button keeps working after the command is done
+const COMPILER_OUTPUT_PREFIX: &str = "compiler_output:";
+
+/// Explain a rustc error code, every error code in pasted compiler output, or search the
+/// explanations
 #[poise::command(slash_command, prefix_command)]
 pub async fn explain(
     ctx: Context<'_>,
-    #[autocomplete = "autocomplete_rustc_error"] error_code: String,
+    #[description = "An error code like E0382, or compiler output"]
+    #[autocomplete = "autocomplete_rustc_error"]
+    #[rest]
+    error_code: Option<String>,
+    #[description = "Words to search the explanations for"] search: Option<String>,
+    #[description = "Toolchain channel for the compiler output, defaults to stable"]
+    channel: Option<Channel>,
 ) -> Result<(), Error> {
-    let Ok(error_code) = ErrorCodes::from_str(&error_code) else {
-        return Err(Error::Command(CommandError::InvalidErrorCode(error_code)));
-    };
+    match (error_code, search) {
+        (Some(text), None) => match error_codes::get(&text) {
+            Some(error_code) => {
+                let channel = channel.unwrap_or(Channel::Stable);
+                let reply = LongReply {
+                    components: output_button(error_code, &channel),
+                    ..explanation(error_code)
+                };
+                send_long(ctx, reply).await
+            }
+            None => explain_text(ctx, &text).await,
+        },
+        (None, Some(query)) => search_explanations(ctx, &query).await,
+        _ => Err(CommandError::CodeOrSearch.into()),
+    }
+}
+
+/// Explain the error codes in the compiler output of a message
+#[poise::command(context_menu_command = "Explain errors")]
+pub async fn explain_message(ctx: Context<'_>, message: Message) -> Result<(), Error> {
+    explain_text(ctx, &message.content).await
+}
+
+/// Summarizes every known error code in `text`, with a button per code for its full explanation
+async fn explain_text(ctx: Context<'_>, text: &str) -> Result<(), Error> {
+    let codes = mentions(text)
+        .into_iter()
+        .filter_map(|mention| Some((error_codes::get(&mention.code)?, mention)))
+        .take(MAX_CODES)
+        .collect::<Vec<_>>();
+    if codes.is_empty() {
+        return Err(CommandError::NoErrorCodes.into());
+    }
+
+    explain_codes(ctx, "Compiler errors", codes).await
+}
 
-    // Changed the remaining error_codes.md to custom ones currently at E0094
-    let content = fs::read_to_string(format!("assets/error_codes/{}.md", error_code.as_ref()))?;
-    let content = split_content(content);
+/// The explanations most relevant to `query`, ranked like [`ErrorIndex::search`]
+///
+/// [`ErrorIndex::search`]: crate::error_index::ErrorIndex::search
+async fn search_explanations(ctx: Context<'_>, query: &str) -> Result<(), Error> {
+    let codes = ctx
+        .data()
+        .error_index
+        .search(query, MAX_CODES)
+        .into_iter()
+        .map(|error_code| {
+            let mention = Mention {
+                code: error_code.code.to_owned(),
+                ..Default::default()
+            };
+            (error_code, mention)
+        })
+        .collect::<Vec<_>>();
+    if codes.is_empty() {
+        return Err(CommandError::NoMatch(query.to_owned()).into());
+    }
+
+    explain_codes(ctx, &format!("Explanations for “{query}”"), codes).await
+}
+
+/// Summarizes `codes` in one embed, with a button per code for its full explanation
+async fn explain_codes(
+    ctx: Context<'_>,
+    title: &str,
+    codes: Vec<(&'static ErrorCode, Mention)>,
+) -> Result<(), Error> {
+    let mut embed = CreateEmbed::new().title(title).color((255, 0, 0));
+    for (error_code, mention) in &codes {
+        embed = embed.field(error_code.code, field(error_code, mention), false);
+    }
 
-    for (i, msg) in content.iter().enumerate() {
-        let mut embed = CreateEmbed::new().color((255, 0, 0)).description(msg);
+    let prefix = format!("{}explain", ctx.id());
+    let mut opened = Vec::new();
+    let handle = ctx
+        .send(
+            CreateReply::default()
+                .embed(embed.clone())
+                .components(buttons(&prefix, &codes, &opened)),
+        )
+        .await?;
+    let message_id = handle.message().await?.id;
 
-        if i == 0 {
-            embed = embed.title(error_code.as_ref()).url(format!(
-                "https://doc.rust-lang.org/error_codes/{}.html",
-                error_code.as_ref()
-            ));
+    while let Some(press) = ComponentInteractionCollector::new(ctx)
+        .message_id(message_id)
+        .timeout(BUTTON_TIMEOUT)
+        .await
+    {
+        if press.user.id != ctx.author().id {
+            press
+                .create_response(
+                    ctx,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .ephemeral(true)
+                            .content(
+                                "Only the person who used the command can open the explanations.",
+                            ),
+                    ),
+                )
+                .await?;
+            continue;
         }
 
-        let reply = CreateReply::default().embed(embed);
-        ctx.send(reply).await?;
+        let Some(error_code) = press
+            .data
+            .custom_id
+            .strip_prefix(&prefix)
+            .and_then(error_codes::get)
+        else {
+            continue;
+        };
+
+        // every explanation is posted once
+        opened.push(error_code);
+        press
+            .create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .components(buttons(&prefix, &codes, &opened)),
+                ),
+            )
+            .await?;
+        send_long(ctx, explanation(error_code)).await?;
     }
 
+    handle
+        .edit(ctx, CreateReply::default().embed(embed).components(vec![]))
+        .await?;
+
     Ok(())
 }
 
-async fn autocomplete_rustc_error(_ctx: Context<'_>, focused: &str) -> Vec<String> {
-    ErrorCodes::iter()
-        .map(|variant| variant.as_ref().to_string())
-        .filter(|name| name.to_lowercase().starts_with(&focused.to_lowercase()))
+/// A button which compiles the first erroneous example of `error_code` on `channel`, if it has
+/// one
+fn output_button(error_code: &ErrorCode, channel: &Channel) -> Vec<CreateActionRow> {
+    if error_code.examples.is_empty() {
+        return Vec::new();
+    }
+
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(output_id(error_code, channel))
+            .label(format!(
+                "Show real compiler output ({})",
+                channel_name(channel)
+            ))
+            .style(ButtonStyle::Primary),
+    ])]
+}
+
+fn output_id(error_code: &ErrorCode, channel: &Channel) -> String {
+    format!(
+        "{COMPILER_OUTPUT_PREFIX}{}:{}",
+        error_code.code,
+        channel_name(channel)
+    )
+}
+
+/// The error code and channel of a compiler output button
+fn output_target(custom_id: &str) -> Option<(&'static ErrorCode, Channel)> {
+    let (code, channel) = custom_id
+        .strip_prefix(COMPILER_OUTPUT_PREFIX)?
+        .split_once(':')?;
+    let channel = match channel {
+        "stable" => Channel::Stable,
+        "beta" => Channel::Beta,
+        "nightly" => Channel::Nightly,
+        _ => return None,
+    };
+    Some((error_codes::get(code)?, channel))
+}
+
+/// Compiles the example of an explanation whose button was pressed and shows what rustc reports
+/// for it only to whoever pressed it, ignoring the buttons of other commands
+pub async fn show_compiler_output(
+    ctx: &serenity::Context,
+    press: &ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let Some((error_code, channel)) = output_target(&press.data.custom_id) else {
+        return Ok(());
+    };
+    let Some(example) = error_code.examples.first() else {
+        return Ok(());
+    };
+    press.defer_ephemeral(ctx).await?;
+
+    let channel_name = channel_name(&channel);
+    let request = ExecuteRequest::new(
+        channel,
+        Mode::Debug,
+        Edition::Edition2024,
+        CrateType::Binary,
+        false,
+        false,
+        example::source(example),
+    );
+    let response = data.playground_client.execute(&request).await?;
+    let diagnostics = example::diagnostics(&response.stderr);
+
+    let mut embed = CreateEmbed::new()
+        .title(format!("Compiler output for {}", error_code.code))
+        .url(error_code.url())
+        .field("Channel", channel_name, true);
+    embed = if diagnostics.is_empty() {
+        embed.description("The example compiled without any diagnostics.")
+    } else {
+        embed.description(format!("```\n{}```", limit_string(&diagnostics, 50, 3900)))
+    };
+    embed = if example::reports(&diagnostics, error_code.code) {
+        embed.color((255, 0, 0))
+    } else {
+        embed.color(0xe67e22).field(
+            "Outdated explanation?",
+            format!(
+                "rustc did not report {} for this example, the explanation may not match the \
+                 current compiler.",
+                error_code.code
+            ),
+            false,
+        )
+    };
+
+    press
+        .edit_response(ctx, EditInteractionResponse::new().embed(embed))
+        .await?;
+    Ok(())
+}
+
+/// The full explanation of `error_code` with a link to its page
+fn explanation(error_code: &ErrorCode) -> LongReply {
+    LongReply {
+        title: error_code.code.to_owned(),
+        url: Some(error_code.url()),
+        color: (255, 0, 0).into(),
+        pages: split_content(error_code.explanation.to_owned()),
+        ..Default::default()
+    }
+}
+
+/// The summary of a code followed by where the pasted diagnostic reported it
+fn field(error_code: &ErrorCode, mention: &Mention) -> String {
+    let mut field = limit_string(error_code.summary, 1, MAX_SUMMARY_LENGTH);
+    if let Some(headline) = &mention.headline {
+        field.push_str(&format!("\n**error:** {headline}"));
+    }
+    match (&mention.location, &mention.label) {
+        (Some(location), Some(label)) => field.push_str(&format!("\n`{location}`: {label}")),
+        (Some(location), None) => field.push_str(&format!("\n`{location}`")),
+        _ => {}
+    }
+    limit_string(&field, 3, EMBED_FIELD_SIZE)
+}
+
+/// A button per code which opens its full explanation, disabled once it was opened
+fn buttons(
+    prefix: &str,
+    codes: &[(&ErrorCode, Mention)],
+    opened: &[&ErrorCode],
+) -> Vec<CreateActionRow> {
+    codes
+        .chunks(BUTTONS_PER_ROW)
+        .map(|row| {
+            CreateActionRow::Buttons(
+                row.iter()
+                    .map(|(error_code, _)| {
+                        CreateButton::new(format!("{prefix}{}", error_code.code))
+                            .label(error_code.code)
+                            .style(ButtonStyle::Secondary)
+                            .disabled(opened.contains(error_code))
+                    })
+                    .collect(),
+            )
+        })
         .collect()
 }
 
-#[derive(
-    Debug, Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::AsRefStr, strum::EnumString,
-)]
-enum ErrorCodes {
-    E0001,
-    E0002,
-    E0004,
-    E0005,
-    E0007,
-    E0009,
-    E0010,
-    E0013,
-    E0014,
-    E0015,
-    E0023,
-    E0025,
-    E0026,
-    E0027,
-    E0029,
-    E0030,
-    E0033,
-    E0034,
-    E0038,
-    E0040,
-    E0044,
-    E0045,
-    E0046,
-    E0049,
-    E0050,
-    E0053,
-    E0054,
-    E0055,
-    E0057,
-    E0059,
-    E0060,
-    E0061,
-    E0062,
-    E0063,
-    E0067,
-    E0069,
-    E0070,
-    E0071,
-    E0072,
-    E0073,
-    E0074,
-    E0075,
-    E0076,
-    E0077,
-    E0080,
-    E0081,
-    E0084,
-    E0087,
-    E0088,
-    E0089,
-    E0090,
-    E0091,
-    E0092,
-    E0093,
-    E0094,
-    E0106,
-    E0107,
-    E0109,
-    E0110,
-    E0116,
-    E0117,
-    E0118,
-    E0119,
-    E0120,
-    E0121,
-    E0124,
-    E0128,
-    E0130,
-    E0131,
-    E0132,
-    E0133,
-    E0136,
-    E0137,
-    E0138,
-    E0139,
-    E0152,
-    E0154,
-    E0158,
-    E0161,
-    E0162,
-    E0164,
-    E0165,
-    E0170,
-    E0178,
-    E0183,
-    E0184,
-    E0185,
-    E0186,
-    E0191,
-    E0192,
-    E0193,
-    E0195,
-    E0197,
-    E0198,
-    E0199,
-    E0200,
-    E0201,
-    E0203,
-    E0204,
-    E0205,
-    E0206,
-    E0207,
-    E0208,
-    E0210,
-    E0211,
-    E0212,
-    E0214,
-    E0220,
-    E0221,
-    E0222,
-    E0223,
-    E0224,
-    E0225,
-    E0226,
-    E0227,
-    E0228,
-    E0229,
-    E0230,
-    E0231,
-    E0232,
-    E0243,
-    E0244,
-    E0251,
-    E0252,
-    E0253,
-    E0254,
-    E0255,
-    E0256,
-    E0259,
-    E0260,
-    E0261,
-    E0262,
-    E0263,
-    E0264,
-    E0267,
-    E0268,
-    E0271,
-    E0275,
-    E0276,
-    E0277,
-    E0281,
-    E0282,
-    E0283,
-    E0284,
-    E0297,
-    E0301,
-    E0302,
-    E0303,
-    E0307,
-    E0308,
-    E0309,
-    E0310,
-    E0311,
-    E0312,
-    E0316,
-    E0317,
-    E0320,
-    E0321,
-    E0322,
-    E0323,
-    E0324,
-    E0325,
-    E0326,
-    E0328,
-    E0329,
-    E0364,
-    E0365,
-    E0366,
-    E0367,
-    E0368,
-    E0369,
-    E0370,
-    E0371,
-    E0373,
-    E0374,
-    E0375,
-    E0376,
-    E0377,
-    E0378,
-    E0379,
-    E0380,
-    E0381,
-    E0382,
-    E0383,
-    E0384,
-    E0386,
-    E0387,
-    E0388,
-    E0389,
-    E0390,
-    E0391,
-    E0392,
-    E0393,
-    E0398,
-    E0399,
-    E0401,
-    E0403,
-    E0404,
-    E0405,
-    E0407,
-    E0408,
-    E0409,
-    E0411,
-    E0412,
-    E0415,
-    E0416,
-    E0422,
-    E0423,
-    E0424,
-    E0425,
-    E0426,
-    E0428,
-    E0429,
-    E0430,
-    E0431,
-    E0432,
-    E0433,
-    E0434,
-    E0435,
-    E0436,
-    E0437,
-    E0438,
-    E0439,
-    E0445,
-    E0446,
-    E0447,
-    E0448,
-    E0449,
-    E0451,
-    E0452,
-    E0453,
-    E0454,
-    E0455,
-    E0457,
-    E0458,
-    E0459,
-    E0460,
-    E0461,
-    E0462,
-    E0463,
-    E0464,
-    E0466,
-    E0468,
-    E0469,
-    E0472,
-    E0476,
-    E0477,
-    E0478,
-    E0482,
-    E0491,
-    E0492,
-    E0493,
-    E0495,
-    E0496,
-    E0497,
-    E0498,
-    E0499,
-    E0500,
-    E0501,
-    E0502,
-    E0503,
-    E0504,
-    E0505,
-    E0506,
-    E0507,
-    E0508,
-    E0509,
-    E0510,
-    E0511,
-    E0512,
-    E0514,
-    E0515,
-    E0516,
-    E0517,
-    E0518,
-    E0519,
-    E0520,
-    E0521,
-    E0522,
-    E0523,
-    E0524,
-    E0525,
-    E0527,
-    E0528,
-    E0529,
-    E0530,
-    E0531,
-    E0532,
-    E0533,
-    E0534,
-    E0535,
-    E0536,
-    E0537,
-    E0538,
-    E0539,
-    E0541,
-    E0542,
-    E0543,
-    E0544,
-    E0545,
-    E0546,
-    E0547,
-    E0549,
-    E0550,
-    E0551,
-    E0552,
-    E0554,
-    E0556,
-    E0557,
-    E0559,
-    E0560,
-    E0561,
-    E0562,
-    E0565,
-    E0566,
-    E0567,
-    E0568,
-    E0569,
-    E0570,
-    E0571,
-    E0572,
-    E0573,
-    E0574,
-    E0575,
-    E0576,
-    E0577,
-    E0578,
-    E0579,
-    E0580,
-    E0581,
-    E0582,
-    E0583,
-    E0584,
-    E0585,
-    E0586,
-    E0587,
-    E0588,
-    E0589,
-    E0590,
-    E0591,
-    E0592,
-    E0593,
-    E0594,
-    E0595,
-    E0596,
-    E0597,
-    E0599,
-    E0600,
-    E0601,
-    E0602,
-    E0603,
-    E0604,
-    E0605,
-    E0606,
-    E0607,
-    E0608,
-    E0609,
-    E0610,
-    E0614,
-    E0615,
-    E0616,
-    E0617,
-    E0618,
-    E0619,
-    E0620,
-    E0621,
-    E0622, // REMOVED: rustc-intrinsic ABI was removed
-    E0623,
-    E0624,
-    E0625,
-    E0626,
-    E0627,
-    E0628,
-    E0631,
-    E0632,
-    E0633,
-    E0634,
-    E0635,
-    E0636,
-    E0637,
-    E0638,
-    E0639,
-    E0640,
-    E0641,
-    E0642,
-    E0643,
-    E0644,
-    E0646,
-    E0647,
-    E0648,
-    E0657,
-    E0658,
-    E0659,
-    E0660,
-    E0661,
-    E0662,
-    E0663,
-    E0664,
-    E0665,
-    E0666,
-    E0667,
-    E0668,
-    E0669,
-    E0670,
-    E0671,
-    E0687,
-    E0688,
-    E0689,
-    E0690,
-    E0691,
-    E0692,
-    E0693,
-    E0695,
-    E0696,
-    E0697,
-    E0698,
-    E0699, // REMOVED: merged into generic inference var error
-    E0700,
-    E0701,
-    E0703,
-    E0704,
-    E0705,
-    E0706,
-    E0708,
-    E0710,
-    E0712,
-    E0713,
-    E0714,
-    E0715,
-    E0716,
-    E0711,
-    E0717,
-    E0718,
-    E0719,
-    E0720,
-    E0722,
-    E0724,
-    E0725,
-    E0726,
-    E0727,
-    E0728,
-    E0729,
-    E0730,
-    E0731,
-    E0732,
-    E0733,
-    E0734,
-    E0735,
-    E0736,
-    E0737,
-    E0739,
-    E0740,
-    E0741,
-    E0742,
-    E0743,
-    E0744,
-    E0745,
-    E0746,
-    E0747,
-    E0748,
-    E0749,
-    E0750,
-    E0751,
-    E0752,
-    E0753,
-    E0754,
-    E0755,
-    E0756,
-    E0757,
-    E0758,
-    E0759,
-    E0760,
-    E0761,
-    E0762,
-    E0763,
-    E0764,
-    E0765,
-    E0766,
-    E0767,
-    E0768,
-    E0769,
-    E0770,
-    E0771,
-    E0772,
-    E0773,
-    E0774,
-    E0775,
-    E0776,
-    E0777,
-    E0778,
-    E0779,
-    E0780,
-    E0781,
-    E0782,
-    E0783,
-    E0784,
-    E0785,
-    E0786,
-    E0787,
-    E0788,
-    E0789,
-    E0790,
-    E0791,
-    E0792,
-    E0793,
-    E0794,
-    E0795,
-    E0796,
-    E0797,
-    E0798,
-    E0799,
-    E0800,
-    E0801,
-    E0802,
-    E0803,
-    E0804,
+/// Codes starting with the input, or the explanations matching its words best
+async fn autocomplete_rustc_error(ctx: Context<'_>, focused: &str) -> Vec<AutocompleteChoice> {
+    let index = &ctx.data().error_index;
+    let focused = focused.trim();
+    let is_code = focused
+        .strip_prefix(['E', 'e'])
+        .is_some_and(|digits| digits.bytes().all(|b| b.is_ascii_digit()));
+
+    let error_codes = if focused.is_empty() || is_code {
+        let prefix = focused.to_uppercase();
+        index
+            .iter()
+            .filter(|error_code| error_code.code.starts_with(&prefix))
+            .take(MAX_CHOICES)
+            .collect::<Vec<_>>()
+    } else {
+        index.search(focused, MAX_CHOICES)
+    };
+
+    error_codes
+        .into_iter()
+        .map(|error_code| choice(choice_name(error_code), error_code.code))
+        .collect()
+}
+
+/// Like "E0382 — A variable was used after its contents have been moved elsewhere", marked when
+/// the compiler no longer emits the code
+fn choice_name(error_code: &ErrorCode) -> String {
+    let removed = if error_code.removed { " (removed)" } else { "" };
+    format!("{}{removed} — {}", error_code.code, error_code.title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_buttons_name_their_code() {
+        let error_code = error_codes::get("E0382").unwrap();
+        let id = output_id(error_code, &Channel::Nightly);
+        assert!(id.len() <= 100);
+
+        let (found, channel) = output_target(&id).unwrap();
+        assert_eq!(found.code, "E0382");
+        assert_eq!(channel_name(&channel), "nightly");
+        assert!(output_target("tip_example:lifetimes").is_none());
+    }
 }
@@ -0,0 +1,163 @@
+use regex::Regex;
+use std::{borrow::Cow, sync::LazyLock};
+
+static HEADER_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"^\s*(?:error|warning)\[([Ee]\d{4})\]: (.+)$").unwrap_unchecked()
+});
+
+static SPAN_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"^\s*--> (\S+)").unwrap_unchecked() });
+
+/// Source lines start with their number, so a `^` in code is never taken for the marker
+static LABEL_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"^\s*\|[^^]*\^+\s*(.*)$").unwrap_unchecked() });
+
+static BREAK_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"\s+(-->|(?:error|warning)\[)").unwrap_unchecked() });
+
+static CODE_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"\b[Ee]\d{4}\b").unwrap_unchecked() });
+
+/// An error code found in a text, with the first diagnostic which reported it if the text is
+/// compiler output
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Mention {
+    pub code: String,
+    /// The message of the diagnostic, like ``borrow of moved value: `v` ``
+    pub headline: Option<String>,
+    /// Where the primary span points, like `src/main.rs:4:20`
+    pub location: Option<String>,
+    /// The label under the primary span
+    pub label: Option<String>,
+}
+
+/// Every distinct error code in `text` in the order they first appear
+pub fn mentions(text: &str) -> Vec<Mention> {
+    // slash command options lose their line breaks, the diagnostics are split up again
+    let text = if text.contains('\n') {
+        Cow::Borrowed(text)
+    } else {
+        BREAK_RE.replace_all(text, "\n$1")
+    };
+
+    let mut mentions: Vec<Mention> = Vec::new();
+    // the mention whose diagnostic is being read, until its span is complete
+    let mut current = None;
+
+    for line in text.lines() {
+        if let Some(cap) = HEADER_RE.captures(line) {
+            let i = position(&mut mentions, &cap[1]);
+            let mention = &mut mentions[i];
+            current = mention.headline.is_none().then_some(i);
+            mention
+                .headline
+                .get_or_insert_with(|| cap[2].trim().to_owned());
+        } else if let Some(i) = current {
+            let mention = &mut mentions[i];
+            if let Some(cap) = SPAN_RE.captures(line) {
+                mention.location.get_or_insert_with(|| cap[1].to_owned());
+            } else if let Some(cap) = LABEL_RE.captures(line) {
+                let label = cap[1].trim();
+                mention.label = (!label.is_empty()).then(|| label.to_owned());
+                current = None;
+            }
+        }
+
+        for code in CODE_RE.find_iter(line) {
+            position(&mut mentions, code.as_str());
+        }
+    }
+
+    mentions
+}
+
+/// The index of the mention of `code`, added if it is new
+fn position(mentions: &mut Vec<Mention>, code: &str) -> usize {
+    let code = code.to_uppercase();
+    match mentions.iter().position(|mention| mention.code == code) {
+        Some(i) => i,
+        None => {
+            mentions.push(Mention {
+                code,
+                ..Default::default()
+            });
+            mentions.len() - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = r#"
+error[E0382]: borrow of moved value: `v`
+ --> src/main.rs:4:20
+  |
+2 |     let v = vec![1];
+  |         - move occurs because `v` has type `Vec<i32>`, which does not implement the `Copy` trait
+3 |     let w = v;
+  |             - value moved here
+4 |     println!("{v:?}");
+  |                ^^^^^ value borrowed here after move
+
+error[E0499]: cannot borrow `x` as mutable more than once at a time
+  --> src/lib.rs:10:5
+   |
+10 |     a(&mut x, &mut x);
+   |     - ------  ^^^^^^ second mutable borrow occurs here
+   |     | |
+   |     | first mutable borrow occurs here
+
+error[E0382]: use of moved value: `s`
+ --> src/main.rs:9:5
+
+Some errors have detailed explanations: E0382, E0499.
+For more information about an error, try `rustc --explain E0382`.
+"#;
+
+    #[test]
+    fn reads_compiler_output() {
+        assert_eq!(
+            mentions(OUTPUT),
+            [
+                Mention {
+                    code: "E0382".to_owned(),
+                    headline: Some("borrow of moved value: `v`".to_owned()),
+                    location: Some("src/main.rs:4:20".to_owned()),
+                    label: Some("value borrowed here after move".to_owned()),
+                },
+                Mention {
+                    code: "E0499".to_owned(),
+                    headline: Some(
+                        "cannot borrow `x` as mutable more than once at a time".to_owned()
+                    ),
+                    location: Some("src/lib.rs:10:5".to_owned()),
+                    label: Some("second mutable borrow occurs here".to_owned()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_flattened_output() {
+        let flattened = OUTPUT.replace('\n', " ");
+        let mentions = mentions(&flattened);
+
+        assert_eq!(mentions.len(), 2);
+        assert_eq!(
+            mentions[0].headline.as_deref(),
+            Some("borrow of moved value: `v`")
+        );
+        assert_eq!(mentions[1].location.as_deref(), Some("src/lib.rs:10:5"));
+    }
+
+    #[test]
+    fn finds_codes_in_text() {
+        let codes = mentions("what do e0308 and E0277 mean? E0308 again, not E03081")
+            .into_iter()
+            .map(|mention| mention.code)
+            .collect::<Vec<_>>();
+        assert_eq!(codes, ["E0308", "E0277"]);
+    }
+}
@@ -0,0 +1,86 @@
+/// Lines cargo prints around the diagnostics of the playground crate
+const CARGO_STATUS: &[&str] = &[
+    "Compiling ",
+    "Finished ",
+    "Running ",
+    "error: could not compile",
+];
+
+/// The example as rustdoc compiles it, wrapped in `fn main` unless it has one
+pub fn source(example: &str) -> String {
+    if example.contains("fn main") {
+        return example.to_owned();
+    }
+
+    // crate attributes have to stay at the top
+    let (attributes, body): (Vec<_>, Vec<_>) = example
+        .lines()
+        .partition(|line| line.trim_start().starts_with("#!["));
+    let mut source = attributes.join("\n");
+    if !source.is_empty() {
+        source.push('\n');
+    }
+    source.push_str("fn main() {\n");
+    for line in body {
+        source.push_str(line);
+        source.push('\n');
+    }
+    source.push('}');
+    source
+}
+
+/// The diagnostics in the playground's stderr, without cargo's status lines
+pub fn diagnostics(stderr: &str) -> String {
+    stderr
+        .lines()
+        .filter(|line| {
+            let line = line.trim_start();
+            !CARGO_STATUS.iter().any(|status| line.starts_with(status))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_owned()
+}
+
+/// Whether rustc reported `code` in `diagnostics`
+pub fn reports(diagnostics: &str, code: &str) -> bool {
+    diagnostics.contains(&format!("error[{code}]"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_like_rustdoc() {
+        assert_eq!(
+            source("#![feature(never_type)]\nlet x: ! = panic!();"),
+            "#![feature(never_type)]\nfn main() {\nlet x: ! = panic!();\n}"
+        );
+        assert_eq!(source("let y = x;"), "fn main() {\nlet y = x;\n}");
+
+        let with_main = "struct S;\n\nfn main() {\n    let s = S;\n}";
+        assert_eq!(source(with_main), with_main);
+    }
+
+    #[test]
+    fn keeps_only_diagnostics() {
+        let stderr = "   Compiling playground v0.0.1 (/playground)
+error[E0382]: borrow of moved value: `x`
+ --> src/main.rs:4:5
+  |
+4 |     x.s = 6;
+  |     ^^^^^^^ value borrowed here after move
+
+For more information about this error, try `rustc --explain E0382`.
+error: could not compile `playground` (bin \"playground\") due to 1 previous error
+";
+        let diagnostics = diagnostics(stderr);
+
+        assert!(diagnostics.starts_with("error[E0382]: borrow of moved value"));
+        assert!(diagnostics.ends_with("try `rustc --explain E0382`."));
+        assert!(reports(&diagnostics, "E0382"));
+        assert!(!reports(&diagnostics, "E0499"));
+    }
+}
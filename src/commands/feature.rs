@@ -0,0 +1,84 @@
+use crate::{
+    Context, Error,
+    common::{
+        autocomplete::{MAX_CHOICES, choice},
+        reply::{LongReply, send_long},
+        split_content,
+    },
+    error::CommandError,
+    features::{self, Feature, Status},
+};
+use poise::serenity_prelude::AutocompleteChoice;
+
+/// Explain a feature gate and whether it has been stabilized
+#[poise::command(slash_command, prefix_command)]
+pub async fn feature(
+    ctx: Context<'_>,
+    #[description = "A feature like let_chains or iter_intersperse"]
+    #[autocomplete = "autocomplete_feature"]
+    #[rest]
+    name: String,
+) -> Result<(), Error> {
+    let feature = features::get(&name).ok_or(CommandError::UnknownFeature(name))?;
+    send_long(ctx, explanation(feature)).await
+}
+
+/// The documentation of `feature` below a line with its metadata and how to use it
+fn explanation(feature: &Feature) -> LongReply {
+    let mut metadata = vec![
+        format!("**Kind:** {}", feature.kind),
+        format!("**Status:** {}", feature.status),
+    ];
+    if let (Some(issue), Some(url)) = (feature.issue, feature.issue_url()) {
+        metadata.push(format!("**Tracking issue:** [#{issue}]({url})"));
+    }
+    let usage = match feature.status {
+        Status::Unstable => format!(
+            "Enable it on nightly with `#![feature({})]` at the top of the crate root.",
+            feature.name
+        ),
+        Status::Stable(since) => format!(
+            "Stabilized in Rust {since}, `#![feature({})]` is no longer needed.",
+            feature.name
+        ),
+        Status::Removed => "The compiler removed it without stabilizing it.".to_owned(),
+    };
+
+    let mut content = format!(
+        "{}\n*{}*\n\n{usage}",
+        metadata.join(" · "),
+        feature.description
+    );
+    if !feature.explanation.is_empty() {
+        content.push_str("\n\n");
+        content.push_str(feature.explanation);
+    }
+
+    LongReply {
+        title: feature.name.to_owned(),
+        url: feature.url().or_else(|| feature.issue_url()),
+        color: match feature.status {
+            Status::Unstable => (255, 176, 0).into(),
+            Status::Stable(_) => (46, 204, 113).into(),
+            Status::Removed => (149, 165, 166).into(),
+        },
+        pages: split_content(content),
+        ..Default::default()
+    }
+}
+
+async fn autocomplete_feature(_ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    features::search(partial, MAX_CHOICES)
+        .into_iter()
+        .map(|feature| choice(choice_name(feature), feature.name))
+        .collect()
+}
+
+/// Like "let_chains (stable since 1.88.0) — Allows `&&` chains of `let` expressions in `if` and
+/// `while` in the 2024 edition."
+fn choice_name(feature: &Feature) -> String {
+    format!(
+        "{} ({}) — {}",
+        feature.name, feature.status, feature.description
+    )
+}
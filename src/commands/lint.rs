@@ -0,0 +1,65 @@
+use crate::{
+    Context, Error,
+    common::{
+        autocomplete::{MAX_CHOICES, choice},
+        reply::{LongReply, send_long},
+        split_content,
+    },
+    error::CommandError,
+    lints::{self, Lint},
+};
+use poise::serenity_prelude::AutocompleteChoice;
+
+/// Explain a rustc or clippy lint
+#[poise::command(slash_command, prefix_command)]
+pub async fn lint(
+    ctx: Context<'_>,
+    #[description = "A lint like unused_must_use or clippy::needless_borrow"]
+    #[autocomplete = "autocomplete_lint"]
+    name: String,
+) -> Result<(), Error> {
+    let lint = lints::get(&name).ok_or(CommandError::UnknownLint(name))?;
+    send_long(ctx, explanation(lint)).await
+}
+
+/// The documentation of `lint` below a line with its metadata
+fn explanation(lint: &Lint) -> LongReply {
+    let mut metadata = vec![format!("**Default level:** `{}`", lint.level)];
+    if let Some(group) = lint.group {
+        metadata.push(format!("**Group:** `{group}`"));
+    }
+    if let Some(since) = lint.since {
+        metadata.push(format!("**Since:** {since}"));
+    }
+    let content = format!(
+        "{}\n*{}*\n\n{}",
+        metadata.join(" · "),
+        lint.description,
+        lint.explanation
+    );
+
+    LongReply {
+        title: lint.name.to_owned(),
+        url: Some(lint.url()),
+        color: if lint.is_clippy() {
+            (255, 176, 0).into()
+        } else {
+            (255, 0, 0).into()
+        },
+        pages: split_content(content),
+        ..Default::default()
+    }
+}
+
+async fn autocomplete_lint(_ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    lints::search(partial, MAX_CHOICES)
+        .into_iter()
+        .map(|lint| choice(choice_name(lint), lint.name))
+        .collect()
+}
+
+/// Like "clippy::needless_borrow — taking a reference that is going to be automatically
+/// dereferenced"
+fn choice_name(lint: &Lint) -> String {
+    format!("{} — {}", lint.name, lint.description)
+}
@@ -0,0 +1,167 @@
+use crate::{
+    Context, Error,
+    error::CommandError,
+    settings::{DEFAULT_TIP_HOUR, DailyTip, GuildSettings},
+    tips,
+};
+use poise::{
+    CreateReply,
+    serenity_prelude::{CreateEmbed, GuildChannel},
+};
+
+/// Configure the bot for this server
+#[poise::command(
+    slash_command,
+    guild_only,
+    required_permissions = "MANAGE_GUILD",
+    subcommands("threads", "doc_links", "daily_tip")
+)]
+pub async fn settings(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Configure when long replies are moved into a thread
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
+async fn threads(
+    ctx: Context<'_>,
+    #[description = "Move long replies into a thread"] enabled: bool,
+    #[description = "Replies longer than this many characters are moved (default 4096)"]
+    #[min = 500]
+    threshold: Option<usize>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    let client = &ctx.data().redis_client;
+    let mut settings = GuildSettings::load(client, guild_id).await?;
+    settings.thread_threshold = match (enabled, threshold) {
+        (false, _) => None,
+        (true, Some(threshold)) => Some(threshold),
+        (true, None) => Some(
+            settings
+                .thread_threshold
+                .unwrap_or(crate::settings::DEFAULT_THREAD_THRESHOLD),
+        ),
+    };
+    settings.save(client, guild_id).await?;
+
+    let description = match settings.thread_threshold {
+        Some(threshold) => {
+            format!("Replies longer than **{threshold}** characters are now posted in a thread.")
+        }
+        None => "Long replies are no longer posted in a thread.".to_owned(),
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .ephemeral(true)
+            .embed(CreateEmbed::new().color(0xCC5500).description(description)),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Link the docs of items mentioned like [[Vec::retain]] in messages
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
+async fn doc_links(
+    ctx: Context<'_>,
+    #[description = "Reply to [[path]] references with links"] enabled: bool,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    let client = &ctx.data().redis_client;
+    let mut settings = GuildSettings::load(client, guild_id).await?;
+    settings.doc_links = enabled;
+    settings.save(client, guild_id).await?;
+
+    let description = if enabled {
+        "References like `[[Vec::retain]]` in messages are now answered with links to the docs."
+    } else {
+        "References in messages are no longer linked."
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .ephemeral(true)
+            .embed(CreateEmbed::new().color(0xCC5500).description(description)),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Post a tip to a channel every day
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
+async fn daily_tip(
+    ctx: Context<'_>,
+    #[description = "Post a tip every day"] enabled: bool,
+    #[description = "Channel to post the tips in"]
+    #[channel_types("Text", "News")]
+    channel: Option<GuildChannel>,
+    #[description = "Hour of the day in UTC to post at (default 9)"]
+    #[max = 23]
+    hour: Option<u8>,
+    #[description = "Only post tips with this tag"]
+    #[autocomplete = "super::tip::autocomplete_tag"]
+    tag: Option<String>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    let client = &ctx.data().redis_client;
+    let mut settings = GuildSettings::load(client, guild_id).await?;
+    settings.daily_tip = if enabled {
+        let previous = settings.daily_tip.take();
+        let channel = channel
+            .map(|channel| channel.id)
+            .or(previous.as_ref().map(|daily_tip| daily_tip.channel))
+            .ok_or(CommandError::NoTipChannel)?;
+        let tag = match tag {
+            Some(tag) if tips::with_tag(Some(&tag)).next().is_none() => {
+                return Err(CommandError::UnknownTipTag(tag).into());
+            }
+            Some(tag) => Some(tips::normalize_tag(&tag)),
+            None => previous
+                .as_ref()
+                .and_then(|daily_tip| daily_tip.tag.clone()),
+        };
+        Some(DailyTip {
+            channel,
+            hour: hour
+                .or(previous.map(|daily_tip| daily_tip.hour))
+                .unwrap_or(DEFAULT_TIP_HOUR),
+            tag,
+        })
+    } else {
+        None
+    };
+    settings.save(client, guild_id).await?;
+
+    let description = match &settings.daily_tip {
+        Some(DailyTip {
+            channel,
+            hour,
+            tag: Some(tag),
+        }) => format!(
+            "A tip tagged `{tag}` is now posted in <#{channel}> every day at **{hour:02}:00 UTC**."
+        ),
+        Some(DailyTip { channel, hour, .. }) => {
+            format!("A tip is now posted in <#{channel}> every day at **{hour:02}:00 UTC**.")
+        }
+        None => "Daily tips are no longer posted.".to_owned(),
+    };
+
+    ctx.send(
+        CreateReply::default()
+            .ephemeral(true)
+            .embed(CreateEmbed::new().color(0xCC5500).description(description)),
+    )
+    .await?;
+
+    Ok(())
+}
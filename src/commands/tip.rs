@@ -0,0 +1,218 @@
+mod daily;
+pub use daily::post_daily_tips;
+
+use crate::{
+    Context, Data, Error,
+    commands::cargo::result_reply,
+    common::autocomplete::{MAX_CHOICES, choice},
+    error::CommandError,
+    tips::{self, Difficulty, Tip},
+};
+use playground_api::endpoints::{Channel, CrateType, Edition, ExecuteRequest, Mode};
+use poise::{
+    CreateReply,
+    serenity_prelude::{
+        self as serenity, AutocompleteChoice, ButtonStyle, ComponentInteraction, CreateActionRow,
+        CreateButton, CreateEmbed, CreateEmbedFooter, EditInteractionResponse,
+    },
+};
+use std::time::Instant;
+
+/// The custom id of a "Run example" button is this followed by the name of the tip, so the
+/// buttons of daily tips keep working after restarts
+const RUN_EXAMPLE_PREFIX: &str = "tip_example:";
+
+/// At most this many other tips are suggested below the answer of `/why`
+const MAX_SEE_ALSO: usize = 3;
+
+/// Get a random tip, optionally about a topic like lifetimes or error-handling
+#[poise::command(slash_command, prefix_command)]
+pub async fn tip(
+    ctx: Context<'_>,
+    #[description = "Only tips with this tag"]
+    #[autocomplete = "autocomplete_tag"]
+    tag: Option<String>,
+) -> Result<(), Error> {
+    let tip = tips::random(tag.as_deref(), &[])
+        .ok_or_else(|| CommandError::UnknownTipTag(tag.unwrap_or_default()))?;
+
+    ctx.send(
+        CreateReply::default()
+            .embed(embed(tip))
+            .components(run_button(tip)),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Find out why Rust works the way it does, like why there is no null
+#[poise::command(slash_command, prefix_command)]
+pub async fn why(
+    ctx: Context<'_>,
+    #[description = "A concept like lifetimes or interior mutability"]
+    #[autocomplete = "autocomplete_concept"]
+    #[rest]
+    concept: String,
+) -> Result<(), Error> {
+    // a tip picked from the autocompletion is sent as its name
+    let hits = match tips::get(&concept) {
+        Some(tip) => vec![tip],
+        None => tips::lookup(&concept, MAX_SEE_ALSO + 1),
+    };
+    let Some((tip, others)) = hits.split_first() else {
+        return Err(CommandError::NoTip(concept).into());
+    };
+
+    let mut embed = embed(tip);
+    if !others.is_empty() {
+        let see_also = others
+            .iter()
+            .map(|other| format!("{} (`/why {}`)", other.topic, other.name))
+            .collect::<Vec<_>>();
+        embed = embed.field("See also", see_also.join("\n"), false);
+    }
+
+    ctx.send(
+        CreateReply::default()
+            .embed(embed)
+            .components(run_button(tip)),
+    )
+    .await?;
+    Ok(())
+}
+
+/// The explanation of `tip` above its example
+pub(crate) fn embed(tip: &Tip) -> CreateEmbed {
+    let tags = tip
+        .tags
+        .iter()
+        .map(|tag| format!("`{tag}`"))
+        .collect::<Vec<_>>();
+
+    CreateEmbed::new()
+        .title(tip.topic)
+        .description(description(tip))
+        .color(match tip.difficulty {
+            Difficulty::Beginner => 0x2ecc71,
+            Difficulty::Intermediate => 0xf1c40f,
+            Difficulty::Advanced => 0xe74c3c,
+        })
+        .field("Difficulty", tip.difficulty.to_string(), true)
+        .field("Tags", tags.join(", "), true)
+        .footer(CreateEmbedFooter::new(
+            "/tip for another tip · /why <concept> to look one up",
+        ))
+}
+
+fn description(tip: &Tip) -> String {
+    format!("{}\n```rust\n{}\n```", tip.explanation, tip.example)
+}
+
+/// A button which runs the example of `tip` on the playground
+pub(crate) fn run_button(tip: &Tip) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(format!("{RUN_EXAMPLE_PREFIX}{}", tip.name))
+            .label("Run example")
+            .style(ButtonStyle::Primary),
+    ])]
+}
+
+/// Runs the example of a tip whose button was pressed and shows the output only to whoever
+/// pressed it, ignoring the buttons of other commands
+pub async fn run_tip_example(
+    ctx: &serenity::Context,
+    press: &ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let Some(tip) = press
+        .data
+        .custom_id
+        .strip_prefix(RUN_EXAMPLE_PREFIX)
+        .and_then(tips::get)
+    else {
+        return Ok(());
+    };
+    press.defer_ephemeral(ctx).await?;
+
+    let request = ExecuteRequest::new(
+        Channel::Stable,
+        Mode::Debug,
+        Edition::Edition2024,
+        CrateType::Binary,
+        false,
+        false,
+        tip.example.to_owned(),
+    );
+    let start = Instant::now();
+    let response = data.playground_client.execute(&request).await?;
+    let source = format!("the example of “{}”", tip.topic);
+    let embed = result_reply(&request, &response, start.elapsed(), &source).first_embed();
+
+    press
+        .edit_response(ctx, EditInteractionResponse::new().embed(embed))
+        .await?;
+    Ok(())
+}
+
+pub(super) async fn autocomplete_tag(_ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    let partial = tips::normalize_tag(partial);
+    tips::tags()
+        .into_iter()
+        .filter(|tag| tag.contains(&partial))
+        .take(MAX_CHOICES)
+        .map(|tag| {
+            let count = tips::with_tag(Some(tag)).count();
+            choice(format!("{tag} ({count})"), tag)
+        })
+        .collect()
+}
+
+async fn autocomplete_concept(_ctx: Context<'_>, partial: &str) -> Vec<AutocompleteChoice> {
+    let hits = if partial.trim().is_empty() {
+        tips::with_tag(None).take(MAX_CHOICES).collect()
+    } else {
+        tips::lookup(partial, MAX_CHOICES)
+    };
+    hits.into_iter()
+        .map(|tip| choice(choice_name(tip), tip.name))
+        .collect()
+}
+
+/// Like "Lifetimes (intermediate) — lifetimes, borrowing, references"
+fn choice_name(tip: &Tip) -> String {
+    format!(
+        "{} ({}) — {}",
+        tip.topic,
+        tip.difficulty,
+        tip.tags.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::EMBED_DESCRIPTION_SIZE;
+
+    #[test]
+    fn buttons_name_their_tip() {
+        let tip = tips::get("lifetimes").unwrap();
+        let id = format!("{RUN_EXAMPLE_PREFIX}{}", tip.name);
+        assert!(id.len() <= 100);
+        assert_eq!(
+            id.strip_prefix(RUN_EXAMPLE_PREFIX).and_then(tips::get),
+            Some(tip)
+        );
+        assert!(choice_name(tip).starts_with("Lifetimes (intermediate) — lifetimes, borrowing"));
+    }
+
+    #[test]
+    fn tips_fit_into_an_embed() {
+        for tip in tips::TIPS {
+            assert!(
+                description(tip).chars().count() <= EMBED_DESCRIPTION_SIZE,
+                "{}",
+                tip.name
+            );
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use super::{embed, run_button};
+use crate::{
+    Error,
+    cache::Client,
+    settings::{DailyTip, GuildSettings},
+    tips,
+};
+use chrono::{NaiveDate, Timelike, Utc};
+use log::{info, warn};
+use poise::serenity_prelude::{CreateMessage, GuildId, Http};
+use std::{sync::Arc, time::Duration};
+
+/// How often the guilds are checked for a tip that is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Posts a tip every day to the channels configured with `/settings daily_tip`, without repeating
+/// a tip in a guild until all of them were posted there
+pub async fn post_daily_tips(http: Arc<Http>) {
+    let client = Client::default();
+    let mut interval = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        let guilds = match GuildSettings::with_daily_tip(&client).await {
+            Ok(guilds) => guilds,
+            Err(e) => {
+                warn!("failed to load the guilds with a daily tip: {e}");
+                continue;
+            }
+        };
+        for guild_id in guilds {
+            if let Err(e) = post_if_due(&http, &client, guild_id).await {
+                warn!("failed to post the daily tip in {guild_id}: {e}");
+            }
+        }
+    }
+}
+
+async fn post_if_due(http: &Http, client: &Client, guild_id: GuildId) -> Result<(), Error> {
+    let Some(daily_tip) = GuildSettings::load(client, guild_id).await?.daily_tip else {
+        return Ok(());
+    };
+    let now = Utc::now();
+    let last_key = format!("daily_tip::last::{guild_id}");
+    let last = client.get::<NaiveDate>(&last_key).await?;
+    if !is_due(&daily_tip, now.date_naive(), now.hour(), last) {
+        return Ok(());
+    }
+    // a failing channel is retried tomorrow rather than every few minutes
+    client.set_persistent(&last_key, now.date_naive()).await?;
+
+    let posted_key = format!("daily_tip::posted::{guild_id}");
+    let tag = daily_tip.tag.as_deref();
+    let mut posted = client.members(&posted_key).await?;
+    if tips::random(tag, &posted).is_none() {
+        // every tip was posted, so start over
+        client.delete(&posted_key).await?;
+        posted.clear();
+    }
+    let Some(tip) = tips::random(tag, &posted) else {
+        return Ok(());
+    };
+
+    daily_tip
+        .channel
+        .send_message(
+            http,
+            CreateMessage::new()
+                .content("**Tip of the day**")
+                .embed(embed(tip))
+                .components(run_button(tip)),
+        )
+        .await?;
+    client.add_member(&posted_key, tip.name).await?;
+    info!("posted the daily tip {} in {guild_id}", tip.name);
+    Ok(())
+}
+
+/// Whether the configured hour has come today and nothing was posted yet
+fn is_due(daily_tip: &DailyTip, today: NaiveDate, hour: u32, last: Option<NaiveDate>) -> bool {
+    u32::from(daily_tip.hour) <= hour && last.is_none_or(|last| last < today)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poise::serenity_prelude::ChannelId;
+
+    #[test]
+    fn posts_once_a_day() {
+        let daily_tip = DailyTip {
+            channel: ChannelId::new(1),
+            hour: 9,
+            tag: None,
+        };
+        let today = NaiveDate::from_ymd_opt(2025, 6, 2).unwrap();
+        let yesterday = today.pred_opt();
+
+        assert!(!is_due(&daily_tip, today, 8, yesterday));
+        assert!(is_due(&daily_tip, today, 9, yesterday));
+        assert!(is_due(&daily_tip, today, 23, None));
+        assert!(!is_due(&daily_tip, today, 12, Some(today)));
+    }
+}
@@ -0,0 +1,191 @@
+mod analysis;
+
+use crate::{
+    Context, Error,
+    common::{extract_32byte_hex, extract_code, limit_string},
+    error::CommandError,
+};
+use analysis::{Report, SiteKind};
+use log::info;
+use playground_api::endpoints::{Edition, MiriRequest};
+use poise::{
+    CreateReply,
+    serenity_prelude::{
+        Attachment, ButtonStyle, ComponentInteractionCollector, CreateActionRow, CreateButton,
+        CreateEmbed, CreateInteractionResponse, CreateInteractionResponseFollowup,
+    },
+};
+use std::time::Duration;
+
+const MIRI_OFFER_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Lists every `unsafe` block, fn, impl, trait, `static mut` and `extern` block in a code block
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "unsafe",
+    subcommands("unsafety_gist", "unsafety_file")
+)]
+pub async fn unsafety(ctx: Context<'_>, #[rest] input: Option<String>) -> Result<(), Error> {
+    info!("executing unsafe analysis");
+    let input = input.unwrap_or_default();
+    let code = extract_code(&input)?;
+
+    report(ctx, code, "your code".to_owned()).await
+}
+
+/// Lists every `unsafe` usage in the code from a Github gist
+#[poise::command(slash_command, rename = "gist")]
+async fn unsafety_gist(
+    ctx: Context<'_>,
+    #[description = "Id of the gist which code you want to analyze."] id: String,
+) -> Result<(), Error> {
+    let Some(id) = extract_32byte_hex(&id) else {
+        return Err(CommandError::InvalidId(id).into());
+    };
+
+    ctx.defer().await?;
+
+    let db_id = format!("gist::{id}");
+    let gist = match ctx.data().redis_client.get(&db_id).await {
+        Ok(Some(gist)) => gist,
+        Ok(None) => {
+            let gist = ctx.data().playground_client.gist_get(id).await?;
+            ctx.data().redis_client.set(&db_id, &gist, 86400).await?;
+            gist
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    report(ctx, gist.code, format!("[#{}](<{}>)", gist.id, gist.url)).await
+}
+
+/// Lists every `unsafe` usage in a Rust source file upload
+#[poise::command(slash_command, rename = "file")]
+async fn unsafety_file(
+    ctx: Context<'_>,
+    #[description = "Rust source file to analyze."] file: Attachment,
+) -> Result<(), Error> {
+    if !file.filename.ends_with(".rs") {
+        return Err(CommandError::NotValidFile(file.filename).into());
+    }
+
+    if file.size > ctx.data().max_code_size {
+        return Err(CommandError::CodeTooLong(file.size, ctx.data().max_code_size).into());
+    }
+
+    ctx.defer().await?;
+
+    let file_content = file.download().await?;
+    let code = String::from_utf8(file_content).map_err(|_| CommandError::NotValidUTF8)?;
+
+    report(ctx, code, format!("[{}](<{}>)", file.filename, file.url)).await
+}
+
+/// Sends the analysis of `code` and offers to run it under Miri afterwards
+async fn report(ctx: Context<'_>, code: String, source: String) -> Result<(), Error> {
+    let report = analysis::analyze(&code).map_err(|e| CommandError::ParseFailed(e.to_string()))?;
+
+    let embed = CreateEmbed::new()
+        .title("Unsafe analysis")
+        .color(0xCC5500)
+        .description(limit_string(&render(&report, &source), 100, 4000))
+        .field("Summary", summary(&report), false);
+
+    if report.sites.is_empty() {
+        ctx.send(CreateReply::default().embed(embed)).await?;
+        return Ok(());
+    }
+
+    let miri_button_id = format!("{}miri", ctx.id());
+    let button = CreateButton::new(&miri_button_id)
+        .label("Run with Miri")
+        .style(ButtonStyle::Primary);
+    let handle = ctx
+        .send(
+            CreateReply::default()
+                .embed(embed.clone())
+                .components(vec![CreateActionRow::Buttons(vec![button])]),
+        )
+        .await?;
+
+    let press = ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .filter(move |press| press.data.custom_id == miri_button_id)
+        .timeout(MIRI_OFFER_TIMEOUT)
+        .await;
+
+    handle
+        .edit(ctx, CreateReply::default().embed(embed).components(vec![]))
+        .await?;
+
+    let Some(press) = press else {
+        return Ok(());
+    };
+
+    press
+        .create_response(ctx, CreateInteractionResponse::Acknowledge)
+        .await?;
+
+    let req = MiriRequest::new(code, Edition::Edition2024, false, None);
+    let res = ctx.data().playground_client.miri(&req).await?;
+
+    let content = if res.success { res.stdout } else { res.stderr };
+    let content = limit_string(&content, 50, 1900);
+    let content = if !content.is_empty() {
+        format!("Running {source} with miri returned the following output\n```{content}```")
+    } else {
+        format!("Running {source} with miri gave no output")
+    };
+
+    press
+        .create_followup(
+            ctx,
+            CreateInteractionResponseFollowup::new().content(content),
+        )
+        .await?;
+
+    Ok(())
+}
+
+fn render(report: &Report, source: &str) -> String {
+    if report.sites.is_empty() {
+        return format!("No `unsafe` found in {source}.");
+    }
+
+    let mut out = format!("Found the following `unsafe` in {source}:\n");
+    for site in &report.sites {
+        match &site.name {
+            Some(name) => out += &format!("**{}** `{name}` (line {})\n", site.kind, site.line),
+            None => out += &format!("**{}** (line {})\n", site.kind, site.line),
+        }
+
+        for op in &site.operations {
+            out += &format!("-# ↳ line {}: {} `{}`\n", op.line, op.kind, op.detail);
+        }
+    }
+
+    out
+}
+
+fn summary(report: &Report) -> String {
+    let kinds = [
+        SiteKind::Block,
+        SiteKind::Fn,
+        SiteKind::Impl,
+        SiteKind::Trait,
+        SiteKind::StaticMut,
+        SiteKind::Extern,
+    ];
+
+    let mut parts = kinds
+        .iter()
+        .filter_map(|&kind| match report.count(kind) {
+            0 => None,
+            n => Some(format!("{n}× {kind}")),
+        })
+        .collect::<Vec<_>>();
+    parts.push(format!("{} unsafe operation(s)", report.operations()));
+
+    parts.join(", ")
+}
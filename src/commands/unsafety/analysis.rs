@@ -0,0 +1,701 @@
+use std::{collections::HashSet, fmt};
+use syn::{
+    Expr, ExprCall, ExprField, ExprMethodCall, ExprPath, ExprUnary, ExprUnsafe, FnArg, ForeignItem,
+    ImplItemFn, ItemFn, ItemForeignMod, ItemImpl, ItemStatic, ItemTrait, ItemUnion, Local, Macro,
+    Member, Pat, StaticMutability, Token, TraitItemFn, Type, UnOp,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    visit::{self, Visit},
+};
+
+/// Unsafe functions from `std`/`core`/`alloc` by the type or module they belong to and their name
+const KNOWN_UNSAFE_FNS: &[(&str, &str)] = &[
+    ("Arc", "from_raw"),
+    ("Box", "from_raw"),
+    ("CString", "from_raw"),
+    ("NonNull", "new_unchecked"),
+    ("NonZero", "new_unchecked"),
+    ("Pin", "new_unchecked"),
+    ("Rc", "from_raw"),
+    ("String", "from_raw_parts"),
+    ("String", "from_utf8_unchecked"),
+    ("Vec", "from_raw_parts"),
+    ("alloc", "alloc"),
+    ("alloc", "alloc_zeroed"),
+    ("alloc", "dealloc"),
+    ("alloc", "realloc"),
+    ("char", "from_u32_unchecked"),
+    ("hint", "unreachable_unchecked"),
+    ("mem", "transmute"),
+    ("mem", "transmute_copy"),
+    ("mem", "zeroed"),
+    ("ptr", "copy"),
+    ("ptr", "copy_nonoverlapping"),
+    ("ptr", "drop_in_place"),
+    ("ptr", "read_unaligned"),
+    ("ptr", "read_volatile"),
+    ("ptr", "swap_nonoverlapping"),
+    ("ptr", "write_bytes"),
+    ("ptr", "write_unaligned"),
+    ("ptr", "write_volatile"),
+    ("slice", "from_raw_parts"),
+    ("slice", "from_raw_parts_mut"),
+    ("str", "from_utf8_unchecked"),
+    ("str", "from_utf8_unchecked_mut"),
+];
+
+/// Unsafe methods of `std` types, which can't be told apart from methods of the same name on other
+/// types without type information
+const KNOWN_UNSAFE_METHODS: &[&str] = &[
+    "as_bytes_mut",
+    "assume_init",
+    "assume_init_drop",
+    "assume_init_mut",
+    "assume_init_read",
+    "assume_init_ref",
+    "get_unchecked",
+    "get_unchecked_mut",
+    "set_len",
+    "unwrap_unchecked",
+];
+
+/// Methods which are only unsafe when called on a raw pointer
+const RAW_POINTER_METHODS: &[&str] = &[
+    "add", "as_mut", "as_ref", "offset", "read", "replace", "sub", "swap", "write",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SiteKind {
+    Block,
+    Fn,
+    Impl,
+    Trait,
+    StaticMut,
+    Extern,
+}
+
+impl fmt::Display for SiteKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            SiteKind::Block => "unsafe block",
+            SiteKind::Fn => "unsafe fn",
+            SiteKind::Impl => "unsafe impl",
+            SiteKind::Trait => "unsafe trait",
+            SiteKind::StaticMut => "static mut",
+            SiteKind::Extern => "extern block",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    RawPointerDeref,
+    UnsafeCall,
+    /// A call which is unsafe if it goes to the `std` function or method of that name
+    PossiblyUnsafeCall,
+    UnionFieldAccess,
+    StaticMutAccess,
+}
+
+impl fmt::Display for OperationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            OperationKind::RawPointerDeref => "raw pointer deref",
+            OperationKind::UnsafeCall => "call to unsafe fn",
+            OperationKind::PossiblyUnsafeCall => "possibly a call to unsafe fn",
+            OperationKind::UnionFieldAccess => "union field access",
+            OperationKind::StaticMutAccess => "access to static mut",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    pub kind: OperationKind,
+    pub line: usize,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsafeSite {
+    pub kind: SiteKind,
+    pub line: usize,
+    pub name: Option<String>,
+    pub operations: Vec<Operation>,
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub sites: Vec<UnsafeSite>,
+}
+
+impl Report {
+    pub fn count(&self, kind: SiteKind) -> usize {
+        self.sites.iter().filter(|site| site.kind == kind).count()
+    }
+
+    pub fn operations(&self) -> usize {
+        self.sites.iter().map(|site| site.operations.len()).sum()
+    }
+}
+
+/// Parses `code` and reports every place which introduces `unsafe` together with the unsafe
+/// operations performed inside of it.
+pub fn analyze(code: &str) -> Result<Report, syn::Error> {
+    let file = syn::parse_file(code)?;
+
+    let mut declarations = Declarations::default();
+    declarations.visit_file(&file);
+
+    let mut analyzer = Analyzer {
+        declarations,
+        report: Report::default(),
+        stack: Vec::new(),
+    };
+    analyzer.visit_file(&file);
+
+    Ok(analyzer.report)
+}
+
+fn line(span: proc_macro2::Span) -> usize {
+    span.start().line
+}
+
+fn is_raw_pointer(ty: &Type) -> bool {
+    match ty {
+        Type::Ptr(_) => true,
+        Type::Paren(paren) => is_raw_pointer(&paren.elem),
+        Type::Group(group) => is_raw_pointer(&group.elem),
+        _ => false,
+    }
+}
+
+fn pat_ident(pat: &Pat) -> Option<String> {
+    match pat {
+        Pat::Ident(ident) => Some(ident.ident.to_string()),
+        Pat::Type(typed) => pat_ident(&typed.pat),
+        _ => None,
+    }
+}
+
+fn path_ident(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Path(ExprPath { path, .. }) => path.get_ident().map(|ident| ident.to_string()),
+        Expr::Paren(paren) => path_ident(&paren.expr),
+        _ => None,
+    }
+}
+
+/// Expressions which evaluate to a raw pointer without needing type information
+fn produces_raw_pointer(expr: &Expr) -> bool {
+    match expr {
+        Expr::Cast(cast) => is_raw_pointer(&cast.ty),
+        Expr::RawAddr(_) => true,
+        Expr::Paren(paren) => produces_raw_pointer(&paren.expr),
+        Expr::MethodCall(call) => matches!(
+            call.method.to_string().as_str(),
+            "as_ptr" | "as_mut_ptr" | "cast" | "cast_mut" | "cast_const"
+        ),
+        Expr::Call(call) => match &*call.func {
+            Expr::Path(path) => path.path.segments.last().is_some_and(|segment| {
+                matches!(
+                    segment.ident.to_string().as_str(),
+                    "null" | "null_mut" | "dangling" | "dangling_mut" | "into_raw"
+                )
+            }),
+            _ => false,
+        },
+        Expr::Macro(mac) => mac
+            .mac
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "addr_of" || segment.ident == "addr_of_mut"),
+        _ => false,
+    }
+}
+
+/// First pass: collects names that make an operation unsafe
+#[derive(Default)]
+struct Declarations {
+    unsafe_fns: HashSet<String>,
+    /// Safe functions and methods, which shadow the `std` ones of the same name
+    safe_fns: HashSet<String>,
+    union_fields: HashSet<String>,
+    raw_pointers: HashSet<String>,
+    static_muts: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for Declarations {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        self.insert_fn(i.sig.unsafety.is_some(), i.sig.ident.to_string());
+        visit::visit_item_fn(self, i);
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        self.insert_fn(i.sig.unsafety.is_some(), i.sig.ident.to_string());
+        visit::visit_impl_item_fn(self, i);
+    }
+
+    fn visit_trait_item_fn(&mut self, i: &'ast TraitItemFn) {
+        self.insert_fn(i.sig.unsafety.is_some(), i.sig.ident.to_string());
+        visit::visit_trait_item_fn(self, i);
+    }
+
+    fn visit_item_foreign_mod(&mut self, i: &'ast ItemForeignMod) {
+        for item in &i.items {
+            match item {
+                ForeignItem::Fn(f) => {
+                    self.unsafe_fns.insert(f.sig.ident.to_string());
+                }
+                ForeignItem::Static(s) => {
+                    self.static_muts.insert(s.ident.to_string());
+                }
+                _ => {}
+            }
+        }
+        visit::visit_item_foreign_mod(self, i);
+    }
+
+    fn visit_item_union(&mut self, i: &'ast ItemUnion) {
+        for field in &i.fields.named {
+            if let Some(ident) = &field.ident {
+                self.union_fields.insert(ident.to_string());
+            }
+        }
+        visit::visit_item_union(self, i);
+    }
+
+    fn visit_item_static(&mut self, i: &'ast ItemStatic) {
+        if matches!(i.mutability, StaticMutability::Mut(_)) {
+            self.static_muts.insert(i.ident.to_string());
+        }
+        visit::visit_item_static(self, i);
+    }
+
+    fn visit_fn_arg(&mut self, i: &'ast FnArg) {
+        if let FnArg::Typed(typed) = i
+            && is_raw_pointer(&typed.ty)
+            && let Some(name) = pat_ident(&typed.pat)
+        {
+            self.raw_pointers.insert(name);
+        }
+        visit::visit_fn_arg(self, i);
+    }
+
+    fn visit_local(&mut self, i: &'ast Local) {
+        let annotated = matches!(&i.pat, Pat::Type(typed) if is_raw_pointer(&typed.ty));
+        let initialized = i
+            .init
+            .as_ref()
+            .is_some_and(|init| produces_raw_pointer(&init.expr));
+
+        if (annotated || initialized)
+            && let Some(name) = pat_ident(&i.pat)
+        {
+            self.raw_pointers.insert(name);
+        }
+        visit::visit_local(self, i);
+    }
+}
+
+impl Declarations {
+    fn insert_fn(&mut self, unsafety: bool, name: String) {
+        if unsafety {
+            self.unsafe_fns.insert(name);
+        } else {
+            self.safe_fns.insert(name);
+        }
+    }
+}
+
+/// Second pass: records the unsafe sites and attributes operations to the innermost one
+struct Analyzer {
+    declarations: Declarations,
+    report: Report,
+    /// Indices into `report.sites` of the enclosing sites which allow unsafe operations
+    stack: Vec<usize>,
+}
+
+impl Analyzer {
+    fn push_site(&mut self, kind: SiteKind, line: usize, name: Option<String>) -> usize {
+        self.report.sites.push(UnsafeSite {
+            kind,
+            line,
+            name,
+            operations: Vec::new(),
+        });
+        self.report.sites.len() - 1
+    }
+
+    fn record(&mut self, kind: OperationKind, line: usize, detail: String) {
+        let Some(&index) = self.stack.last() else {
+            return;
+        };
+        self.report.sites[index]
+            .operations
+            .push(Operation { kind, line, detail });
+    }
+
+    /// Runs `f` with a fresh unsafe context, e.g. for nested items which do not inherit it
+    fn isolated(&mut self, f: impl FnOnce(&mut Self)) {
+        let stack = std::mem::take(&mut self.stack);
+        f(self);
+        self.stack = stack;
+    }
+
+    fn unsafe_fn(&mut self, name: String, line: usize, f: impl FnOnce(&mut Self)) {
+        let index = self.push_site(SiteKind::Fn, line, Some(name));
+        self.isolated(|this| {
+            this.stack.push(index);
+            f(this);
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for Analyzer {
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        if i.sig.unsafety.is_some() {
+            let line = line(i.sig.fn_token.span);
+            self.unsafe_fn(i.sig.ident.to_string(), line, |this| {
+                visit::visit_item_fn(this, i)
+            });
+        } else {
+            self.isolated(|this| visit::visit_item_fn(this, i));
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast ImplItemFn) {
+        if i.sig.unsafety.is_some() {
+            let line = line(i.sig.fn_token.span);
+            self.unsafe_fn(i.sig.ident.to_string(), line, |this| {
+                visit::visit_impl_item_fn(this, i)
+            });
+        } else {
+            self.isolated(|this| visit::visit_impl_item_fn(this, i));
+        }
+    }
+
+    fn visit_trait_item_fn(&mut self, i: &'ast TraitItemFn) {
+        if i.sig.unsafety.is_some() {
+            let line = line(i.sig.fn_token.span);
+            self.unsafe_fn(i.sig.ident.to_string(), line, |this| {
+                visit::visit_trait_item_fn(this, i)
+            });
+        } else {
+            self.isolated(|this| visit::visit_trait_item_fn(this, i));
+        }
+    }
+
+    fn visit_item_impl(&mut self, i: &'ast ItemImpl) {
+        if let Some(token) = i.unsafety {
+            let name = i.trait_.as_ref().map(|(_, path, _)| {
+                let trait_name = path
+                    .segments
+                    .last()
+                    .map(|segment| segment.ident.to_string())
+                    .unwrap_or_default();
+                let self_ty = &i.self_ty;
+                format!("{trait_name} for {}", quote_type(self_ty))
+            });
+            self.push_site(SiteKind::Impl, line(token.span), name);
+        }
+        visit::visit_item_impl(self, i);
+    }
+
+    fn visit_item_trait(&mut self, i: &'ast ItemTrait) {
+        if let Some(token) = i.unsafety {
+            self.push_site(SiteKind::Trait, line(token.span), Some(i.ident.to_string()));
+        }
+        visit::visit_item_trait(self, i);
+    }
+
+    fn visit_item_static(&mut self, i: &'ast ItemStatic) {
+        if matches!(i.mutability, StaticMutability::Mut(_)) {
+            self.push_site(
+                SiteKind::StaticMut,
+                line(i.static_token.span),
+                Some(i.ident.to_string()),
+            );
+        }
+        visit::visit_item_static(self, i);
+    }
+
+    fn visit_item_foreign_mod(&mut self, i: &'ast ItemForeignMod) {
+        let name = i
+            .abi
+            .name
+            .as_ref()
+            .map(|abi| format!("\"{}\"", abi.value()));
+        self.push_site(SiteKind::Extern, line(i.abi.extern_token.span), name);
+        visit::visit_item_foreign_mod(self, i);
+    }
+
+    fn visit_expr_unsafe(&mut self, i: &'ast ExprUnsafe) {
+        let index = self.push_site(SiteKind::Block, line(i.unsafe_token.span), None);
+        self.stack.push(index);
+        visit::visit_expr_unsafe(self, i);
+        self.stack.pop();
+    }
+
+    fn visit_expr_unary(&mut self, i: &'ast ExprUnary) {
+        if let UnOp::Deref(star) = i.op {
+            let target = path_ident(&i.expr);
+            let raw = target
+                .as_ref()
+                .is_some_and(|name| self.declarations.raw_pointers.contains(name))
+                || produces_raw_pointer(&i.expr);
+
+            if raw {
+                let detail = target.unwrap_or_else(|| "<expression>".to_owned());
+                self.record(OperationKind::RawPointerDeref, line(star.span), detail);
+            }
+        }
+        visit::visit_expr_unary(self, i);
+    }
+
+    fn visit_expr_call(&mut self, i: &'ast ExprCall) {
+        if let Expr::Path(ExprPath { path, .. }) = &*i.func
+            && let Some(last) = path.segments.last()
+        {
+            let name = last.ident.to_string();
+            let owner = path
+                .segments
+                .len()
+                .checked_sub(2)
+                .map(|i| path.segments[i].ident.to_string());
+            let known = match &owner {
+                Some(owner) => {
+                    KNOWN_UNSAFE_FNS.contains(&(owner.as_str(), name.as_str()))
+                        || owner == "ptr" && RAW_POINTER_METHODS.contains(&name.as_str())
+                }
+                None => false,
+            };
+            // `transmute(x)` after a `use std::mem::transmute`, unless the code has its own
+            let imported = owner.is_none()
+                && KNOWN_UNSAFE_FNS.iter().any(|&(_, known)| known == name)
+                && !self.declarations.safe_fns.contains(&name);
+
+            let kind = if known || self.declarations.unsafe_fns.contains(&name) {
+                Some(OperationKind::UnsafeCall)
+            } else if imported {
+                Some(OperationKind::PossiblyUnsafeCall)
+            } else {
+                None
+            };
+            if let Some(kind) = kind {
+                let detail = path
+                    .segments
+                    .iter()
+                    .map(|segment| segment.ident.to_string())
+                    .collect::<Vec<_>>()
+                    .join("::");
+                self.record(kind, line(path.span()), detail);
+            }
+        }
+        visit::visit_expr_call(self, i);
+    }
+
+    fn visit_expr_method_call(&mut self, i: &'ast ExprMethodCall) {
+        let name = i.method.to_string();
+        let on_raw_pointer = path_ident(&i.receiver)
+            .is_some_and(|receiver| self.declarations.raw_pointers.contains(&receiver))
+            && RAW_POINTER_METHODS.contains(&name.as_str());
+
+        let kind = if self.declarations.unsafe_fns.contains(&name) || on_raw_pointer {
+            Some(OperationKind::UnsafeCall)
+        } else if KNOWN_UNSAFE_METHODS.contains(&name.as_str())
+            && !self.declarations.safe_fns.contains(&name)
+        {
+            Some(OperationKind::PossiblyUnsafeCall)
+        } else {
+            None
+        };
+        if let Some(kind) = kind {
+            self.record(kind, line(i.method.span()), format!(".{name}()"));
+        }
+        visit::visit_expr_method_call(self, i);
+    }
+
+    fn visit_expr_field(&mut self, i: &'ast ExprField) {
+        if let Member::Named(ident) = &i.member
+            && self.declarations.union_fields.contains(&ident.to_string())
+        {
+            self.record(
+                OperationKind::UnionFieldAccess,
+                line(ident.span()),
+                format!(".{ident}"),
+            );
+        }
+        visit::visit_expr_field(self, i);
+    }
+
+    fn visit_macro(&mut self, i: &'ast Macro) {
+        // Most macros used in snippets (`println!`, `assert_eq!`, `vec!`, ...) take expressions
+        if let Ok(args) = i.parse_body_with(Punctuated::<Expr, Token![,]>::parse_terminated) {
+            for arg in &args {
+                self.visit_expr(arg);
+            }
+        }
+        visit::visit_macro(self, i);
+    }
+
+    fn visit_expr_path(&mut self, i: &'ast ExprPath) {
+        if let Some(ident) = i.path.get_ident() {
+            let name = ident.to_string();
+            if self.declarations.static_muts.contains(&name) {
+                self.record(OperationKind::StaticMutAccess, line(ident.span()), name);
+            }
+        }
+        visit::visit_expr_path(self, i);
+    }
+}
+
+fn quote_type(ty: &Type) -> String {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<_>>()
+            .join("::"),
+        Type::Ptr(ptr) => {
+            let qualifier = if ptr.mutability.is_some() {
+                "mut"
+            } else {
+                "const"
+            };
+            format!("*{qualifier} {}", quote_type(&ptr.elem))
+        }
+        Type::Reference(reference) => format!("&{}", quote_type(&reference.elem)),
+        _ => "_".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = r#"static mut COUNTER: u32 = 0;
+
+union IntOrFloat {
+    i: u32,
+    f: f32,
+}
+
+unsafe trait Zeroable {}
+unsafe impl Zeroable for u32 {}
+
+extern "C" {
+    fn abs(input: i32) -> i32;
+}
+
+unsafe fn dangerous() {}
+
+fn main() {
+    let x = 5;
+    let ptr = &x as *const i32;
+    let u = IntOrFloat { i: 1 };
+    unsafe {
+        println!("{}", *ptr);
+        dangerous();
+        abs(-3);
+        let _ = u.f;
+        COUNTER += 1;
+    }
+}
+"#;
+
+    #[test]
+    fn sites() {
+        let report = analyze(CODE).unwrap();
+
+        assert_eq!(report.count(SiteKind::StaticMut), 1);
+        assert_eq!(report.count(SiteKind::Trait), 1);
+        assert_eq!(report.count(SiteKind::Impl), 1);
+        assert_eq!(report.count(SiteKind::Extern), 1);
+        assert_eq!(report.count(SiteKind::Fn), 1);
+        assert_eq!(report.count(SiteKind::Block), 1);
+
+        let static_mut = &report.sites[0];
+        assert_eq!(static_mut.line, 1);
+        assert_eq!(static_mut.name.as_deref(), Some("COUNTER"));
+    }
+
+    #[test]
+    fn operations() {
+        let report = analyze(CODE).unwrap();
+        let block = report
+            .sites
+            .iter()
+            .find(|site| site.kind == SiteKind::Block)
+            .unwrap();
+
+        assert_eq!(block.line, 21);
+        let kinds = block
+            .operations
+            .iter()
+            .map(|op| op.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                OperationKind::RawPointerDeref,
+                OperationKind::UnsafeCall,
+                OperationKind::UnsafeCall,
+                OperationKind::UnionFieldAccess,
+                OperationKind::StaticMutAccess,
+            ]
+        );
+        assert_eq!(block.operations[0].line, 22);
+    }
+
+    #[test]
+    fn nested_fn_does_not_inherit() {
+        let code =
+            "fn main() { let p = 0 as *const u8; unsafe { fn inner(p: *const u8) -> u8 { *p } } }";
+        let report = analyze(code).unwrap();
+
+        assert_eq!(report.operations(), 0);
+    }
+
+    #[test]
+    fn calls_by_name_alone_are_possible() {
+        let code = "struct Table;
+impl Table {
+    fn set_len(&mut self, len: usize) {}
+}
+fn main() {
+    let v = vec![1];
+    unsafe {
+        std::slice::from_raw_parts(v.as_ptr(), 1);
+        transmute::<u32, f32>(1);
+        v.get_unchecked(0);
+        Table.set_len(0);
+    }
+}";
+        let report = analyze(code).unwrap();
+        let details = report.sites[0]
+            .operations
+            .iter()
+            .map(|op| (op.kind, op.detail.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            details,
+            [
+                (OperationKind::UnsafeCall, "std::slice::from_raw_parts"),
+                (OperationKind::PossiblyUnsafeCall, "transmute"),
+                (OperationKind::PossiblyUnsafeCall, ".get_unchecked()"),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_code() {
+        assert!(analyze("fn main( {").is_err());
+    }
+}
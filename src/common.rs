@@ -1,9 +1,17 @@
+pub(crate) mod autocomplete;
 mod extract_code;
+mod fuzzy;
 mod hex;
 mod limit_content;
+mod paginate;
+pub(crate) mod reply;
 mod split_content;
 
 pub(crate) use extract_code::extract_code;
+pub(crate) use fuzzy::fuzzy_score;
 pub(crate) use hex::extract_32byte_hex;
 pub(crate) use limit_content::limit_string;
-pub(crate) use split_content::split_content;
+pub(crate) use paginate::{Extras, paginate};
+pub(crate) use split_content::{
+    EMBED_DESCRIPTION_SIZE, EMBED_FIELD_SIZE, split_content, split_markdown,
+};
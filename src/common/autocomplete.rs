@@ -0,0 +1,50 @@
+use crate::Context;
+use poise::serenity_prelude::{AutocompleteChoice, CommandDataOptionValue};
+
+/// Discord shows at most 25 autocomplete choices
+pub const MAX_CHOICES: usize = 25;
+
+/// Discord limits autocomplete names to 100 characters
+const MAX_CHOICE_LENGTH: usize = 100;
+
+/// A choice which shows `name`, cut off to fit, and fills in `value`
+pub fn choice(name: String, value: &str) -> AutocompleteChoice {
+    AutocompleteChoice::new(choice_name(name), value.to_owned())
+}
+
+fn choice_name(name: String) -> String {
+    if name.chars().count() <= MAX_CHOICE_LENGTH {
+        return name;
+    }
+    let mut name = name.chars().take(MAX_CHOICE_LENGTH - 1).collect::<String>();
+    name.push('…');
+    name
+}
+
+/// An option the user already filled in while another one is being autocompleted
+pub fn selected<'a>(ctx: Context<'a>, name: &str) -> Option<&'a CommandDataOptionValue> {
+    let Context::Application(ctx) = ctx else {
+        return None;
+    };
+
+    ctx.interaction
+        .data
+        .options
+        .iter()
+        .find(|option| option.name == name)
+        .map(|option| &option.value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cuts_long_names() {
+        assert_eq!(choice_name("E0382 — short".to_owned()), "E0382 — short");
+
+        let name = choice_name("é".repeat(150));
+        assert_eq!(name.chars().count(), MAX_CHOICE_LENGTH);
+        assert!(name.ends_with("é…"));
+    }
+}
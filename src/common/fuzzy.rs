@@ -0,0 +1,44 @@
+/// How well `name` matches `query`, exact names first, then prefixes, then names containing the
+/// query, then names containing its characters in order with as few gaps as possible
+pub fn fuzzy_score(name: &str, query: &str) -> Option<i64> {
+    if name == query {
+        return Some(1000);
+    }
+    if name.starts_with(query) {
+        return Some(800);
+    }
+    if name.contains(query) {
+        return Some(600);
+    }
+
+    // positions are counted in characters, so the span can't be shorter than the query
+    let mut start = None;
+    let mut end = 0;
+    let mut chars = name.chars().enumerate();
+    for c in query.chars() {
+        let (i, _) = chars.find(|&(_, n)| n == c)?;
+        start.get_or_insert(i);
+        end = i;
+    }
+    let span = end - start.unwrap_or_default() + 1;
+    Some(400 - span.saturating_sub(query.chars().count()) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores() {
+        assert_eq!(
+            fuzzy_score("needless_borrow", "needless_borrow"),
+            Some(1000)
+        );
+        assert_eq!(fuzzy_score("needless_borrow", "needless"), Some(800));
+        assert_eq!(fuzzy_score("needless_borrow", "borrow"), Some(600));
+        assert_eq!(fuzzy_score("needless_borrow", "nb"), Some(400 - 8));
+        assert_eq!(fuzzy_score("needless_borrow", "bn"), None);
+        assert_eq!(fuzzy_score("ab😀", "a😀"), Some(400 - 1));
+        assert_eq!(fuzzy_score("é_clippy", "éc"), Some(400 - 1));
+    }
+}
@@ -0,0 +1,265 @@
+use crate::{Context, Error};
+use log::warn;
+use poise::{
+    CreateReply,
+    serenity_prelude::{
+        self as serenity, ButtonStyle, ComponentInteractionCollector, ComponentInteractionDataKind,
+        CreateActionRow, CreateButton, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+        CreateInteractionResponseMessage, CreateSelectMenu, CreateSelectMenuKind,
+        CreateSelectMenuOption, EditMessage, Message, UserId,
+    },
+};
+use std::{ops::Range, time::Duration};
+
+/// Navigation is locked to the invoker and released after this long without interaction
+const LOCK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Discord allows at most 25 options in a select menu
+const MAX_SELECT_OPTIONS: usize = 25;
+
+/// What a paginated message shows besides its current page
+#[derive(Default)]
+pub struct Extras {
+    /// Text above the embed, like a mention of the invoker
+    pub content: Option<String>,
+    /// Rows below the page controls, which stay after the controls are removed
+    pub components: Vec<CreateActionRow>,
+}
+
+impl Extras {
+    fn reply(&self) -> CreateReply {
+        let reply = CreateReply::default().components(self.components.clone());
+        match &self.content {
+            Some(content) => reply.content(content),
+            None => reply,
+        }
+    }
+
+    fn with_controls(&self, controls: Vec<CreateActionRow>) -> Vec<CreateActionRow> {
+        controls
+            .into_iter()
+            .chain(self.components.iter().cloned())
+            .collect()
+    }
+}
+
+/// Sends `pages` as a single message which the invoker can navigate with buttons and a jump
+/// menu. The message is edited in place and the controls are removed once the lock times out.
+///
+/// Only the first page is awaited, the buttons are handled in the background so the caller can
+/// carry on right away.
+pub async fn paginate(
+    ctx: Context<'_>,
+    pages: Vec<CreateEmbed>,
+    start: usize,
+    extras: Extras,
+) -> Result<(), Error> {
+    if pages.is_empty() {
+        return Ok(());
+    }
+
+    let total = pages.len();
+    let current = start.min(total - 1);
+
+    if total == 1 {
+        ctx.send(extras.reply().embed(page(&pages, current)))
+            .await?;
+        return Ok(());
+    }
+
+    let ids = Ids::new(ctx.id());
+    let handle = ctx
+        .send(
+            extras
+                .reply()
+                .embed(page(&pages, current))
+                .components(extras.with_controls(components(&ids, current, total))),
+        )
+        .await?;
+    let message = handle.message().await?.into_owned();
+
+    let serenity_ctx = ctx.serenity_context().clone();
+    let author = ctx.author().id;
+    tokio::spawn(async move {
+        if let Err(e) =
+            turn_pages(&serenity_ctx, author, message, ids, pages, current, extras).await
+        {
+            warn!("failed to turn the pages: {e}");
+        }
+    });
+
+    Ok(())
+}
+
+/// Handles the buttons of the paginated `message` until the lock times out
+async fn turn_pages(
+    ctx: &serenity::Context,
+    author: UserId,
+    mut message: Message,
+    ids: Ids,
+    pages: Vec<CreateEmbed>,
+    mut current: usize,
+    extras: Extras,
+) -> Result<(), Error> {
+    let total = pages.len();
+    // the extra components are handled elsewhere and may be used by anyone
+    while let Some(press) = ComponentInteractionCollector::new(ctx)
+        .message_id(message.id)
+        .custom_ids(ids.all())
+        .timeout(LOCK_TIMEOUT)
+        .await
+    {
+        if press.user.id != author {
+            press
+                .create_response(
+                    ctx,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .ephemeral(true)
+                            .content("Only the person who used the command can turn the pages."),
+                    ),
+                )
+                .await?;
+            continue;
+        }
+
+        let id = &press.data.custom_id;
+        current = if *id == ids.first {
+            0
+        } else if *id == ids.prev {
+            current.saturating_sub(1)
+        } else if *id == ids.next {
+            (current + 1).min(total - 1)
+        } else if *id == ids.last {
+            total - 1
+        } else if *id == ids.jump {
+            match &press.data.kind {
+                ComponentInteractionDataKind::StringSelect { values } => values
+                    .first()
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .filter(|&page| page < total)
+                    .unwrap_or(current),
+                _ => current,
+            }
+        } else {
+            continue;
+        };
+
+        press
+            .create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .embed(page(&pages, current))
+                        .components(extras.with_controls(components(&ids, current, total))),
+                ),
+            )
+            .await?;
+    }
+
+    message
+        .edit(
+            ctx,
+            EditMessage::new()
+                .embed(page(&pages, current))
+                .components(extras.components),
+        )
+        .await?;
+
+    Ok(())
+}
+
+struct Ids {
+    first: String,
+    prev: String,
+    next: String,
+    last: String,
+    jump: String,
+}
+
+impl Ids {
+    fn new(ctx_id: u64) -> Self {
+        Self {
+            first: format!("{ctx_id}first"),
+            prev: format!("{ctx_id}prev"),
+            next: format!("{ctx_id}next"),
+            last: format!("{ctx_id}last"),
+            jump: format!("{ctx_id}jump"),
+        }
+    }
+
+    fn all(&self) -> Vec<String> {
+        [&self.first, &self.prev, &self.next, &self.last, &self.jump]
+            .map(String::clone)
+            .into()
+    }
+}
+
+fn page(pages: &[CreateEmbed], current: usize) -> CreateEmbed {
+    pages[current]
+        .clone()
+        .footer(CreateEmbedFooter::new(format!(
+            "Page {}/{}",
+            current + 1,
+            pages.len()
+        )))
+}
+
+fn components(ids: &Ids, current: usize, total: usize) -> Vec<CreateActionRow> {
+    let buttons = CreateActionRow::Buttons(vec![
+        CreateButton::new(&ids.first)
+            .emoji('⏮')
+            .style(ButtonStyle::Secondary)
+            .disabled(current == 0),
+        CreateButton::new(&ids.prev)
+            .emoji('◀')
+            .style(ButtonStyle::Secondary)
+            .disabled(current == 0),
+        CreateButton::new(&ids.next)
+            .emoji('▶')
+            .style(ButtonStyle::Secondary)
+            .disabled(current + 1 == total),
+        CreateButton::new(&ids.last)
+            .emoji('⏭')
+            .style(ButtonStyle::Secondary)
+            .disabled(current + 1 == total),
+    ]);
+
+    let options = jump_window(current, total)
+        .map(|page| {
+            CreateSelectMenuOption::new(format!("Page {}", page + 1), page.to_string())
+                .default_selection(page == current)
+        })
+        .collect();
+    let jump = CreateActionRow::SelectMenu(
+        CreateSelectMenu::new(&ids.jump, CreateSelectMenuKind::String { options })
+            .placeholder("Jump to page"),
+    );
+
+    vec![buttons, jump]
+}
+
+/// The pages offered in the jump menu, centered around `current` if there are too many
+fn jump_window(current: usize, total: usize) -> Range<usize> {
+    if total <= MAX_SELECT_OPTIONS {
+        return 0..total;
+    }
+
+    let start = current
+        .saturating_sub(MAX_SELECT_OPTIONS / 2)
+        .min(total - MAX_SELECT_OPTIONS);
+    start..start + MAX_SELECT_OPTIONS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window() {
+        assert_eq!(jump_window(0, 3), 0..3);
+        assert_eq!(jump_window(0, 100), 0..25);
+        assert_eq!(jump_window(50, 100), 38..63);
+        assert_eq!(jump_window(99, 100), 75..100);
+    }
+}
@@ -0,0 +1,189 @@
+use super::{Extras, paginate};
+use crate::{Context, Error, settings::GuildSettings};
+use log::warn;
+use poise::{
+    CreateReply,
+    serenity_prelude::{
+        AutoArchiveDuration, ChannelType, Colour, CreateActionRow, CreateEmbed, CreateMessage,
+        CreateThread,
+    },
+};
+
+/// How much of the content is shown in the channel when the rest is moved into a thread
+const SUMMARY_LENGTH: usize = 300;
+
+/// A reply which may be too long for a single embed
+#[derive(Default)]
+pub struct LongReply {
+    pub title: String,
+    pub url: Option<String>,
+    pub color: Colour,
+    pub pages: Vec<String>,
+    /// Fields below the first page as name, value and whether they're inline
+    pub fields: Vec<(String, String, bool)>,
+    /// Text above the embed, like a mention of the invoker
+    pub content: Option<String>,
+    /// Rows of buttons below the reply, handled outside of the command
+    pub components: Vec<CreateActionRow>,
+}
+
+impl LongReply {
+    fn embed(&self, i: usize, page: &str) -> CreateEmbed {
+        let mut embed = CreateEmbed::new().color(self.color).description(page);
+
+        if i == 0 {
+            embed = embed.title(&self.title).fields(self.fields.clone());
+            if let Some(url) = &self.url {
+                embed = embed.url(url);
+            }
+        }
+
+        embed
+    }
+
+    /// The first page on its own, for replies which can't be paginated
+    pub fn first_embed(&self) -> CreateEmbed {
+        self.embed(
+            0,
+            self.pages.first().map(String::as_str).unwrap_or_default(),
+        )
+    }
+
+    /// The number of characters, as the thread threshold is configured in characters
+    fn len(&self) -> usize {
+        self.pages.iter().map(|page| page.chars().count()).sum()
+    }
+}
+
+/// Sends `reply` as a paginated message, or moves it into a thread if the guild's threshold is
+/// exceeded and only leaves a short summary in the channel
+pub async fn send_long(ctx: Context<'_>, reply: LongReply) -> Result<(), Error> {
+    let threshold = match ctx.guild_id() {
+        // the reply matters more than the guild's settings, so an unreachable cache only costs
+        // the thread
+        Some(guild_id) => match GuildSettings::load(&ctx.data().redis_client, guild_id).await {
+            Ok(settings) => settings.thread_threshold,
+            Err(e) => {
+                warn!("failed to load the settings of {guild_id}, using the defaults: {e}");
+                GuildSettings::default().thread_threshold
+            }
+        },
+        None => None,
+    };
+
+    match threshold {
+        Some(threshold) if reply.len() > threshold => send_in_thread(ctx, reply).await,
+        _ => {
+            let pages = reply
+                .pages
+                .iter()
+                .enumerate()
+                .map(|(i, page)| reply.embed(i, page))
+                .collect();
+            let extras = Extras {
+                content: reply.content,
+                components: reply.components,
+            };
+            paginate(ctx, pages, 0, extras).await
+        }
+    }
+}
+
+async fn send_in_thread(ctx: Context<'_>, reply: LongReply) -> Result<(), Error> {
+    let summary = summarize(reply.pages.first().map(String::as_str).unwrap_or_default());
+    let summary_embed = |footer: &str| {
+        let mut embed = CreateEmbed::new()
+            .title(&reply.title)
+            .color(reply.color)
+            .description(format!("{summary}\n\n{footer}"))
+            .fields(reply.fields.clone());
+        if let Some(url) = &reply.url {
+            embed = embed.url(url);
+        }
+        embed
+    };
+
+    let mut summary_reply = CreateReply::default()
+        .embed(summary_embed("The full reply is posted in a thread…"))
+        .components(reply.components.clone());
+    if let Some(content) = &reply.content {
+        summary_reply = summary_reply.content(content);
+    }
+    let handle = ctx.send(summary_reply).await?;
+
+    // prefix commands have an invoking message, slash commands only have our reply
+    let message_id = match ctx {
+        poise::Context::Prefix(prefix) => prefix.msg.id,
+        poise::Context::Application(_) => handle.message().await?.id,
+    };
+
+    let name = reply.title.chars().take(100).collect::<String>();
+    let thread = ctx
+        .channel_id()
+        .create_thread_from_message(
+            ctx,
+            message_id,
+            CreateThread::new(name)
+                .kind(ChannelType::PublicThread)
+                .auto_archive_duration(AutoArchiveDuration::OneDay),
+        )
+        .await?;
+
+    for (i, page) in reply.pages.iter().enumerate() {
+        thread
+            .id
+            .send_message(ctx, CreateMessage::new().embed(reply.embed(i, page)))
+            .await?;
+    }
+
+    handle
+        .edit(
+            ctx,
+            CreateReply::default().embed(summary_embed(&format!(
+                "The full reply is posted in <#{}>.",
+                thread.id
+            ))),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// The first paragraph of `content`, cut to [`SUMMARY_LENGTH`] without splitting a character,
+/// with a code block it cuts into closed again
+fn summarize(content: &str) -> String {
+    let paragraph = content.split("\n\n").next().unwrap_or_default().trim();
+    let mut summary = if paragraph.len() <= SUMMARY_LENGTH {
+        paragraph.to_owned()
+    } else {
+        let mut end = SUMMARY_LENGTH;
+        while !paragraph.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}…", &paragraph[..end])
+    };
+
+    if summary.matches("```").count() % 2 == 1 {
+        summary.push_str("\n```");
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary() {
+        assert_eq!(summarize("first\n\nsecond"), "first");
+        assert_eq!(summarize(""), "");
+
+        let long = "ä".repeat(SUMMARY_LENGTH);
+        let summary = summarize(&long);
+        assert!(summary.len() <= SUMMARY_LENGTH + '…'.len_utf8());
+        assert!(summary.ends_with('…'));
+
+        let output = format!("Ran your code\n```\n{}\n```", "x".repeat(SUMMARY_LENGTH));
+        assert!(summarize(&output).ends_with("…\n```"));
+    }
+}
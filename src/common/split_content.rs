@@ -1,25 +1,356 @@
-const EMBED_DESCRIPTION_SIZE: usize = 4096;
+use regex::Regex;
+use std::sync::LazyLock;
 
+pub const EMBED_DESCRIPTION_SIZE: usize = 4096;
+pub const EMBED_FIELD_SIZE: usize = 1024;
+const EMBED_TITLE_SIZE: usize = 256;
+const EMBED_TOTAL_SIZE: usize = 6000;
+
+// A full description still leaves room for a title and one field within the total embed size
+const _: () =
+    assert!(EMBED_DESCRIPTION_SIZE + EMBED_TITLE_SIZE + EMBED_FIELD_SIZE <= EMBED_TOTAL_SIZE);
+
+const FENCE: &str = "```";
+
+/// `[text](url)`, `<url>` and bare urls, which stop working when they're cut
+static LINK_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(
+        r"\[[^\[\]]*\]\([^()\s]*\)|<[a-z][a-z0-9+.-]*://[^<>\s]*>|\b[a-z][a-z0-9+.-]*://[^\s<>]+",
+    )
+    .unwrap_unchecked()
+});
+
+/// Splits markdown into embed descriptions, see [`split_markdown`]
 pub fn split_content(content: String) -> Vec<String> {
-    let mut result = Vec::new();
-    let mut start = 0;
+    split_markdown(&content, EMBED_DESCRIPTION_SIZE)
+}
+
+/// Splits markdown into pieces of at most `limit` bytes.
+///
+/// Paragraphs are kept together where possible, then lines, and only lines which are longer than
+/// `limit` on their own are cut, preferably at whitespace and never inside a link unless the link
+/// alone is longer than `limit`. A code block which has to be split is closed at the end of one
+/// piece and reopened with the same language tag in the next one.
+pub fn split_markdown(content: &str, limit: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+
+    for block in blocks(content) {
+        let separator = if current.is_empty() { 0 } else { 2 };
+        if current.len() + separator + block.len() <= limit {
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(&block);
+            continue;
+        }
+
+        if !current.is_empty() {
+            pieces.push(std::mem::take(&mut current));
+        }
+
+        if block.len() <= limit {
+            current = block;
+        } else {
+            let mut split = split_lines(&block, limit);
+            current = split.pop().unwrap_or_default();
+            pieces.extend(split);
+        }
+    }
+
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+
+    pieces
+}
+
+/// Groups lines into paragraphs separated by blank lines, code blocks are never separated
+fn blocks(content: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+
+    for line in content.lines() {
+        if is_fence(line) {
+            in_fence = !in_fence;
+        }
+
+        if !in_fence && line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(current.join("\n"));
+                current.clear();
+            }
+            continue;
+        }
+
+        current.push(line);
+    }
+
+    if !current.is_empty() {
+        blocks.push(current.join("\n"));
+    }
+
+    blocks
+}
+
+fn is_fence(line: &str) -> bool {
+    line.trim_start().starts_with(FENCE)
+}
+
+/// The fence line used to reopen a code block, keeping only the language tag
+fn reopen_fence(opening: &str) -> String {
+    let info = opening.trim_start().trim_start_matches('`');
+    let lang = info
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .next()
+        .unwrap_or_default();
+    format!("{FENCE}{lang}")
+}
+
+/// Splits a single paragraph at line boundaries, closing and reopening code blocks as needed
+fn split_lines(block: &str, limit: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut fence: Option<String> = None;
+    // bytes needed to close an open code block: "\n```"
+    let closing = FENCE.len() + 1;
+
+    let flush = |current: &mut String, pieces: &mut Vec<String>, fence: &Option<String>| {
+        if let Some(opening) = fence {
+            current.push('\n');
+            current.push_str(FENCE);
+            pieces.push(std::mem::replace(current, opening.clone()));
+        } else {
+            pieces.push(std::mem::take(current));
+        }
+    };
+
+    for line in block.lines() {
+        let fence_after = match (&fence, is_fence(line)) {
+            (None, true) => Some(reopen_fence(line)),
+            (Some(_), true) => None,
+            (open, false) => open.clone(),
+        };
+        let reserve = if fence_after.is_some() { closing } else { 0 };
+
+        let mut rest = line;
+        loop {
+            let separator = usize::from(!current.is_empty());
+            if current.len() + separator + rest.len() + reserve <= limit {
+                if separator == 1 {
+                    current.push('\n');
+                }
+                current.push_str(rest);
+                break;
+            }
+
+            let is_reopened = fence.as_ref().is_some_and(|opening| current == *opening);
+            if !current.is_empty() && !is_reopened {
+                flush(&mut current, &mut pieces, &fence);
+                continue;
+            }
+
+            // the line doesn't fit into an empty piece and has to be cut
+            let separator = usize::from(!current.is_empty());
+            let available = limit
+                .saturating_sub(current.len() + separator + closing)
+                .max(1);
+            let cut = cut_point(rest, available);
+            if separator == 1 {
+                current.push('\n');
+            }
+            current.push_str(&rest[..cut]);
+            rest = &rest[cut..];
+            flush(&mut current, &mut pieces, &fence);
+        }
+
+        fence = fence_after;
+    }
+
+    if !current.is_empty() {
+        pieces.push(current);
+    }
 
-    while start < content.len() {
-        let mut end = (start + EMBED_DESCRIPTION_SIZE).min(content.len());
+    pieces
+}
+
+/// The largest char boundary not after `max` and outside of links, moved back to whitespace if
+/// that doesn't lose more than half of the space
+fn cut_point(line: &str, max: usize) -> usize {
+    let mut end = max.min(line.len());
+    while !line.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    let links = LINK_RE
+        .find_iter(line)
+        .map(|link| link.range())
+        .collect::<Vec<_>>();
+    let link_at = |position: usize| {
+        links
+            .iter()
+            .find(|link| link.start < position && position < link.end)
+    };
+
+    if let Some(link) = link_at(end)
+        && link.start > 0
+    {
+        end = link.start;
+    }
+
+    if end == 0 {
+        // `max` is smaller than the first character, take it anyway to make progress
+        return line.chars().next().map_or(0, char::len_utf8);
+    }
+
+    let space = line[..end]
+        .char_indices()
+        .rev()
+        .find(|&(i, c)| c.is_whitespace() && link_at(i).is_none())
+        .map(|(i, _)| i);
+    match space {
+        Some(space) if space >= end / 2 && space > 0 => space,
+        _ => end,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn fences_balanced(piece: &str) -> bool {
+        piece.lines().filter(|line| is_fence(line)).count() % 2 == 0
+    }
+
+    /// Content without whitespace and without the fences added by the splitter
+    fn normalized(content: &str) -> String {
+        content.chars().filter(|c| !c.is_whitespace()).collect()
+    }
+
+    #[test]
+    fn short() {
+        assert_eq!(split_content("hello".to_owned()), ["hello"]);
+        assert!(split_content(String::new()).is_empty());
+    }
 
-        while !content.is_char_boundary(end) {
-            end -= 1;
+    #[test]
+    fn paragraphs() {
+        let content = format!("{}\n\n{}", "a".repeat(30), "b".repeat(30));
+        let pieces = split_markdown(&content, 40);
+
+        assert_eq!(pieces, ["a".repeat(30), "b".repeat(30)]);
+    }
+
+    #[test]
+    fn reopens_fence() {
+        let code = (0..20)
+            .map(|i| format!("let x{i} = {i};"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let content = format!("Example:\n\n```rust,compile_fail,E0038\n{code}\n```\n\nDone.");
+        let pieces = split_markdown(&content, 120);
+
+        assert!(pieces.len() > 2);
+        for piece in &pieces {
+            assert!(piece.len() <= 120, "{piece:?}");
+            assert!(fences_balanced(piece), "{piece:?}");
         }
+        assert!(pieces[2].starts_with("```rust\n"));
+    }
 
-        if end <= start {
-            end = (start + EMBED_DESCRIPTION_SIZE).min(content.len());
+    #[test]
+    fn long_line() {
+        let content = "word ".repeat(100);
+        let pieces = split_markdown(&content, 64);
+
+        assert!(pieces.iter().all(|piece| piece.len() <= 64));
+        assert_eq!(normalized(&pieces.concat()), normalized(&content));
+    }
+
+    #[test]
+    fn keeps_links_whole() {
+        let content = (0..40)
+            .map(|i| match i % 3 {
+                0 => format!("see [the item number {i}](https://doc.rust-lang.org/std/{i}.html)"),
+                1 => format!("or <https://docs.rs/crate{i}/latest/>"),
+                _ => format!("and https://github.com/rust-lang/rust/issues/{i}"),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let links = LINK_RE
+            .find_iter(&content)
+            .map(|link| link.as_str())
+            .collect::<Vec<_>>();
+
+        for limit in [64, 100, 237] {
+            let pieces = split_markdown(&content, limit);
+            assert!(pieces.len() > 1);
+            let found = pieces
+                .iter()
+                .flat_map(|piece| LINK_RE.find_iter(piece).map(|link| link.as_str()))
+                .collect::<Vec<_>>();
+            assert_eq!(found, links, "{pieces:#?}");
         }
+    }
 
-        println!("{start}, {end}");
+    #[test]
+    fn bundled_explanations() {
+        for entry in std::fs::read_dir("assets/error_codes").unwrap() {
+            let content = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            for piece in split_content(content) {
+                assert!(piece.len() <= EMBED_DESCRIPTION_SIZE);
+                assert!(fences_balanced(&piece));
+            }
+        }
+    }
 
-        result.push(content[start..end].to_string());
-        start = end;
+    fn markdown() -> impl Strategy<Value = String> {
+        let line = prop_oneof![
+            "[a-zé ]{0,80}",
+            Just(String::new()),
+            Just("```rust".to_owned()),
+            Just("```".to_owned()),
+            "[a-z]{100,300}",
+        ];
+        prop::collection::vec(line, 0..60).prop_map(|lines| lines.join("\n"))
     }
 
-    result
+    proptest! {
+        #[test]
+        fn within_limit(content in markdown(), limit in 64usize..512) {
+            for piece in split_markdown(&content, limit) {
+                prop_assert!(piece.len() <= limit, "{} > {limit}: {piece:?}", piece.len());
+            }
+        }
+
+        #[test]
+        fn balanced_fences(content in markdown(), limit in 64usize..512) {
+            // only meaningful if the input itself is balanced
+            prop_assume!(fences_balanced(&content));
+            for piece in split_markdown(&content, limit) {
+                prop_assert!(fences_balanced(&piece), "{piece:?}");
+            }
+        }
+
+        #[test]
+        fn keeps_content(content in markdown(), limit in 64usize..512) {
+            prop_assume!(fences_balanced(&content));
+            let pieces = split_markdown(&content, limit);
+            let joined = pieces.join("\n");
+
+            let original_fences = content.lines().filter(|line| is_fence(line)).count();
+            let joined_fences = joined.lines().filter(|line| is_fence(line)).count();
+            let without_fences = |text: &str| {
+                text.lines()
+                    .filter(|line| !is_fence(line))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+
+            prop_assert!(joined_fences >= original_fences);
+            prop_assert_eq!(normalized(&without_fences(&joined)), normalized(&without_fences(&content)));
+        }
+    }
 }
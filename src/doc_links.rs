@@ -0,0 +1,163 @@
+use crate::{
+    Data, Error,
+    common::limit_string,
+    rustdoc::{DocIndex, search},
+    settings::GuildSettings,
+};
+use playground_api::endpoints::Channel;
+use poise::serenity_prelude::{self as serenity, CreateAllowedMentions, CreateMessage, Message};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// At most this many references of a message are linked
+const MAX_LINKS: usize = 5;
+
+const MAX_SUMMARY_LENGTH: usize = 120;
+
+/// How many results are checked for one that ends with the reference
+const CANDIDATES: usize = 5;
+
+static REFERENCE_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"\[\[([A-Za-z_][A-Za-z0-9_]*(?:::[A-Za-z_][A-Za-z0-9_]*)*)\]\]").unwrap_unchecked()
+});
+
+/// Replies to messages mentioning items like `[[Vec::retain]]` with links to their docs, in
+/// guilds which enabled it
+pub async fn on_message(
+    ctx: &serenity::Context,
+    message: &Message,
+    data: &Data,
+) -> Result<(), Error> {
+    if message.author.bot {
+        return Ok(());
+    }
+    let Some(guild_id) = message.guild_id else {
+        return Ok(());
+    };
+    let references = references(&message.content);
+    if references.is_empty()
+        || !GuildSettings::load(&data.redis_client, guild_id)
+            .await?
+            .doc_links
+    {
+        return Ok(());
+    }
+
+    // nobody asked for an answer, so there is none while the docs are loading
+    let Ok(set) = data.docs.get(&Channel::Stable) else {
+        return Ok(());
+    };
+    let lines = links(&[&set.std, &set.core, &set.alloc], &references);
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    message
+        .channel_id
+        .send_message(
+            ctx,
+            CreateMessage::new()
+                .content(lines.join("\n"))
+                .reference_message(message)
+                .allowed_mentions(CreateAllowedMentions::new()),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// The distinct `[[path]]` references in `content`, leaving out those in code
+fn references(content: &str) -> Vec<&str> {
+    let prose = content
+        .split("```")
+        .step_by(2)
+        .flat_map(|text| text.split('`').step_by(2));
+
+    let mut references = Vec::new();
+    for text in prose {
+        for capture in REFERENCE_RE.captures_iter(text) {
+            let (_, [reference]) = capture.extract();
+            if !references.contains(&reference) {
+                references.push(reference);
+            }
+            if references.len() == MAX_LINKS {
+                return references;
+            }
+        }
+    }
+    references
+}
+
+/// A line linking the docs of each reference that names an item, others are left out
+fn links(indices: &[&DocIndex], references: &[&str]) -> Vec<String> {
+    references
+        .iter()
+        .filter_map(|reference| {
+            let suffix = format!("::{reference}");
+            let hit = search(indices, reference, CANDIDATES)
+                .into_iter()
+                .find(|hit| hit.path == *reference || hit.path.ends_with(&suffix))?;
+
+            let link = format!("[`{}`](<{}>)", hit.path, hit.url());
+            Some(match hit.summary().filter(|summary| !summary.is_empty()) {
+                Some(summary) => {
+                    format!("{link} — {}", limit_string(&summary, 1, MAX_SUMMARY_LENGTH))
+                }
+                None => link,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rustdoc::Crate;
+
+    const STD: &str = r#"{
+        "root": 0,
+        "paths": {
+            "2": { "crate_id": 0, "path": ["std", "vec", "Vec"], "kind": "struct" }
+        },
+        "index": {
+            "0": { "id": 0, "crate_id": 0, "name": "std", "docs": null, "inner": { "module": { "is_crate": true, "items": [1] } } },
+            "1": { "id": 1, "crate_id": 0, "name": "vec", "docs": null, "inner": { "module": { "is_crate": false, "items": [2] } } },
+            "2": { "id": 2, "crate_id": 0, "name": "Vec", "docs": "A contiguous growable array type.\n\nMore.", "inner": { "struct": { "impls": [3] } } },
+            "3": { "id": 3, "crate_id": 0, "name": null, "docs": null, "inner": { "impl": { "trait": null, "for": { "resolved_path": { "path": "Vec", "id": 2 } }, "items": [4, 5] } } },
+            "4": { "id": 4, "crate_id": 0, "name": "retain", "docs": "Retains only the elements specified by the predicate.", "inner": { "function": {} } },
+            "5": { "id": 5, "crate_id": 0, "name": "retain_mut", "docs": null, "inner": { "function": {} } }
+        }
+    }"#;
+
+    #[test]
+    fn finds_references() {
+        assert_eq!(
+            references("use [[Vec::retain]] or [[ Vec ]], not [[Vec::retain]]"),
+            ["Vec::retain"]
+        );
+        assert_eq!(
+            references("```rust\nlet x = [[1]];\n[[Vec]]\n```\n`[[Option]]` but [[Result]]"),
+            ["Result"]
+        );
+
+        let many = (0..10).map(|i| format!("[[Item{i}]]")).collect::<String>();
+        assert_eq!(references(&many).len(), MAX_LINKS);
+    }
+
+    #[test]
+    fn links_items() {
+        let index = DocIndex::new(Crate::from_slice(STD.as_bytes()).unwrap());
+
+        assert_eq!(
+            links(
+                &[&index],
+                &["Vec::retain", "Vec", "vec::Vec::retain_mut", "Vec::ret"]
+            ),
+            [
+                "[`std::vec::Vec::retain`](<https://doc.rust-lang.org/std/vec/struct.Vec.html#method.retain>) — Retains only the elements specified by the predicate.",
+                "[`std::vec::Vec`](<https://doc.rust-lang.org/std/vec/struct.Vec.html>) — A contiguous growable array type.",
+                "[`std::vec::Vec::retain_mut`](<https://doc.rust-lang.org/std/vec/struct.Vec.html#method.retain_mut>)",
+            ]
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use crate::rustdoc::{Crate, DocIndex, RustdocError};
+use log::{info, warn};
+use playground_api::endpoints::Channel;
+use std::{
+    path::Path,
+    sync::{Arc, RwLock, RwLockWriteGuard},
+};
+
+/// The rustdoc JSON of a channel is read from `{DOCS_PATH}/{channel}/{crate}.json`
+pub const DOCS_PATH: &str = "./assets/docs";
+
+/// Built indices are kept in `{SNAPSHOTS_PATH}/{channel}/{crate}.index` for quicker starts
+pub const SNAPSHOTS_PATH: &str = "./cache/index";
+
+const CHANNELS: [&str; 3] = ["stable", "beta", "nightly"];
+
+/// The toolchain crates as documented for one release channel
+pub struct DocSet {
+    pub channel: &'static str,
+    pub std: DocIndex,
+    pub core: DocIndex,
+    pub alloc: DocIndex,
+}
+
+impl DocSet {
+    /// Loads the three crates concurrently. Stable falls back to the files directly in `root`,
+    /// where they were before there were channels.
+    pub async fn load(
+        root: &Path,
+        snapshots: &Path,
+        channel: &Channel,
+    ) -> Result<Self, RustdocError> {
+        let name = channel_name(channel);
+        let mut dir = root.join(name);
+        if name == "stable" && !dir.exists() {
+            dir = root.to_path_buf();
+        }
+        let snapshots = snapshots.join(name);
+
+        let load = |krate: &'static str| {
+            let json = dir.join(format!("{krate}.json"));
+            let snapshot = snapshots.join(format!("{krate}.index"));
+            tokio::task::spawn_blocking(move || {
+                info!("loading the {name} index of {krate}");
+                DocIndex::load(&json, &snapshot).map(|index| index.with_channel(name))
+            })
+        };
+        let (std, core, alloc) = tokio::join!(load("std"), load("core"), load("alloc"));
+        let mut std = std.expect("loading an index panicked")?;
+        let core = core.expect("loading an index panicked")?;
+        let mut alloc = alloc.expect("loading an index panicked")?;
+
+        // std re-exports most of core and alloc, whose members only those crates know
+        let set = tokio::task::spawn_blocking(move || {
+            alloc.link(&core);
+            std.link(&core);
+            std.link(&alloc);
+            Self {
+                channel: name,
+                std,
+                core,
+                alloc,
+            }
+        });
+        Ok(set.await.expect("linking the indices panicked"))
+    }
+
+    /// Version of the toolchain the docs were built with, if rustdoc recorded it
+    pub fn version(&self) -> Option<&str> {
+        self.std.version()
+    }
+
+    /// Reads the items of indices restored from snapshots, which can take a few seconds
+    pub fn load_items(&self) {
+        for index in [&self.std, &self.core, &self.alloc] {
+            index.items();
+        }
+    }
+}
+
+/// Why the docs of a channel can't be searched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unavailable {
+    Loading,
+    /// The channel failed to load, the bot runs without it
+    Missing,
+}
+
+/// The doc set of every channel, they load in the background and can be swapped while the bot
+/// runs
+pub struct DocSets {
+    slots: RwLock<[Result<Arc<DocSet>, Unavailable>; 3]>,
+}
+
+impl DocSets {
+    /// Loads every channel in the background, channels which fail to load are logged and left
+    /// out
+    pub fn load_in_background(self: &Arc<Self>, root: &Path, snapshots: &Path) {
+        for channel in [Channel::Stable, Channel::Beta, Channel::Nightly] {
+            let sets = Arc::clone(self);
+            let root = root.to_path_buf();
+            let snapshots = snapshots.to_path_buf();
+
+            tokio::spawn(async move {
+                let name = channel_name(&channel);
+                match DocSet::load(&root, &snapshots, &channel).await {
+                    Ok(set) => {
+                        info!("the {name} docs are ready");
+                        sets.replace(set);
+                        // searches work right away, the items are read before a command needs them
+                        if let Ok(set) = sets.get(&channel) {
+                            tokio::task::spawn_blocking(move || set.load_items());
+                        }
+                    }
+                    Err(e) => {
+                        warn!("no {name} docs loaded: {e}");
+                        sets.slots_mut()[slot(name)] = Err(Unavailable::Missing);
+                    }
+                }
+            });
+        }
+    }
+
+    pub fn get(&self, channel: &Channel) -> Result<Arc<DocSet>, Unavailable> {
+        let slot = slot(channel_name(channel));
+        self.slots
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())[slot]
+            .clone()
+    }
+
+    /// Replaces the set of the same channel, commands still using the old one keep it until
+    /// they are done
+    pub fn replace(&self, set: DocSet) {
+        let slot = slot(set.channel);
+        self.slots_mut()[slot] = Ok(Arc::new(set));
+    }
+
+    fn slots_mut(&self) -> RwLockWriteGuard<'_, [Result<Arc<DocSet>, Unavailable>; 3]> {
+        self.slots
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for DocSets {
+    fn default() -> Self {
+        Self {
+            slots: RwLock::new([const { Err(Unavailable::Loading) }; 3]),
+        }
+    }
+}
+
+pub fn channel_name(channel: &Channel) -> &'static str {
+    match channel {
+        Channel::Stable => "stable",
+        Channel::Beta => "beta",
+        Channel::Nightly => "nightly",
+    }
+}
+
+fn slot(name: &str) -> usize {
+    CHANNELS
+        .iter()
+        .position(|channel| *channel == name)
+        .expect("doc sets only exist for known channels")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write_set(dir: &Path, version: &str) {
+        fs::create_dir_all(dir).unwrap();
+        for krate in ["std", "core", "alloc"] {
+            let json = format!(
+                r#"{{ "root": 0, "crate_version": "{version}", "paths": {{}}, "index": {{
+                    "0": {{ "id": 0, "name": "{krate}", "docs": null, "inner": {{ "module": {{ "items": [] }} }} }}
+                }} }}"#
+            );
+            fs::write(dir.join(format!("{krate}.json")), json).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn loads_and_swaps() {
+        let root = std::env::temp_dir().join(format!("corro-doc-sets-{}", std::process::id()));
+        let snapshots = root.join("snapshots");
+        write_set(&root, "1.87.0");
+        write_set(&root.join("nightly"), "1.90.0-nightly");
+
+        // stable uses the files in the root without a `stable` directory
+        let stable = DocSet::load(&root, &snapshots, &Channel::Stable)
+            .await
+            .unwrap();
+        assert_eq!(stable.version(), Some("1.87.0"));
+        assert_eq!(stable.std.base_url(), "https://doc.rust-lang.org");
+        assert!(snapshots.join("stable").join("core.index").exists());
+        assert!(
+            DocSet::load(&root, &snapshots, &Channel::Beta)
+                .await
+                .is_err()
+        );
+
+        let sets = DocSets::default();
+        assert_eq!(sets.get(&Channel::Stable).err(), Some(Unavailable::Loading));
+        sets.replace(stable);
+        sets.replace(
+            DocSet::load(&root, &snapshots, &Channel::Nightly)
+                .await
+                .unwrap(),
+        );
+        let nightly = sets.get(&Channel::Nightly).unwrap();
+        assert_eq!(nightly.version(), Some("1.90.0-nightly"));
+        assert_eq!(nightly.core.base_url(), "https://doc.rust-lang.org/nightly");
+
+        write_set(&root.join("nightly"), "1.91.0-nightly");
+        sets.replace(
+            DocSet::load(&root, &snapshots, &Channel::Nightly)
+                .await
+                .unwrap(),
+        );
+        assert_eq!(
+            sets.get(&Channel::Nightly).unwrap().version(),
+            Some("1.91.0-nightly")
+        );
+        // the old set stays usable for whoever still holds it
+        assert_eq!(nightly.version(), Some("1.90.0-nightly"));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[tokio::test]
+    async fn degrades_without_files() {
+        let root = std::env::temp_dir().join(format!("corro-no-docs-{}", std::process::id()));
+        let sets = Arc::new(DocSets::default());
+        sets.load_in_background(&root, &root.join("snapshots"));
+
+        while sets.get(&Channel::Stable).err() == Some(Unavailable::Loading) {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(sets.get(&Channel::Stable).err(), Some(Unavailable::Missing));
+    }
+}
@@ -0,0 +1,379 @@
+use crate::rustdoc::{Crate, DocIndex, RustdocError};
+use flate2::read::GzDecoder;
+use reqwest::{StatusCode, Url};
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+pub const DOCS_RS_URL: &str = "https://docs.rs";
+
+/// Downloaded rustdoc JSON is kept here, compressed as docs.rs serves it
+pub const DOCS_CACHE_PATH: &str = "./cache/docs";
+
+/// How many indices of third-party crates are kept in memory
+const MAX_INDICES: usize = 8;
+
+/// The largest compressed rustdoc JSON which is downloaded, far above what big crates need
+const MAX_DOWNLOAD_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The largest rustdoc JSON which is decompressed, so a small download can't fill the memory
+const MAX_JSON_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// The disk cache is cut down to this size by removing the oldest downloads first
+const MAX_CACHE_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// Fetches the rustdoc JSON docs.rs builds for published crates
+pub struct DocsRs {
+    base_url: String,
+    cache_dir: PathBuf,
+    http: reqwest::Client,
+    indices: Mutex<Lru<Arc<DocIndex>>>,
+}
+
+impl DocsRs {
+    pub fn new(base_url: impl Into<String>, cache_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            cache_dir: cache_dir.into(),
+            http: reqwest::Client::builder()
+                .user_agent(concat!("corro-discord-bot/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .expect("failed to create the docs.rs client"),
+            indices: Mutex::new(Lru::new(MAX_INDICES)),
+        }
+    }
+
+    /// The index of `name` at `version`, built from memory, the disk cache or a download in that
+    /// order. `None` if docs.rs has no rustdoc JSON for the crate.
+    pub async fn index(
+        &self,
+        name: &str,
+        version: &str,
+    ) -> Result<Option<Arc<DocIndex>>, DocsRsError> {
+        if let Some(index) = self.cached(name, version) {
+            return Ok(Some(index));
+        }
+
+        let path = self.cache_dir.join(format!("{name}-{version}.json.gz"));
+        let compressed = match tokio::fs::read(&path).await {
+            Ok(compressed) => compressed,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                let Some(compressed) = self.download(name, version).await? else {
+                    return Ok(None);
+                };
+                tokio::fs::create_dir_all(&self.cache_dir).await?;
+                tokio::fs::write(&path, &compressed).await?;
+                let dir = self.cache_dir.clone();
+                tokio::task::spawn_blocking(move || evict(&dir, MAX_CACHE_SIZE)).await??;
+                compressed
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let index = match tokio::task::spawn_blocking(move || parse(&compressed)).await? {
+            Ok(index) => Arc::new(index),
+            Err(e) => {
+                // a broken file would fail every time, so it is downloaded again next time
+                let _ = tokio::fs::remove_file(&path).await;
+                return Err(e);
+            }
+        };
+
+        self.lock().insert(key(name, version), index.clone());
+        Ok(Some(index))
+    }
+
+    /// The index of `name` at `version` if it is in memory, never downloads
+    pub fn cached(&self, name: &str, version: &str) -> Option<Arc<DocIndex>> {
+        self.lock().get(&key(name, version)).cloned()
+    }
+
+    /// Search page on docs.rs, for crates which can't be searched here
+    pub fn search_url(&self, name: &str, version: &str, query: &str) -> String {
+        let page = format!(
+            "{}/{name}/{version}/{}/",
+            self.base_url,
+            name.replace('-', "_")
+        );
+        Url::parse_with_params(&page, [("search", query)])
+            .map(String::from)
+            .unwrap_or(page)
+    }
+
+    async fn download(&self, name: &str, version: &str) -> Result<Option<Vec<u8>>, DocsRsError> {
+        let response = self
+            .http
+            .get(format!("{}/crate/{name}/{version}/json.gz", self.base_url))
+            .send()
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let mut response = response.error_for_status()?;
+        if response
+            .content_length()
+            .is_some_and(|length| length > MAX_DOWNLOAD_SIZE)
+        {
+            return Err(DocsRsError::TooLarge);
+        }
+
+        // the length is only a hint, so the body is checked while it arrives
+        let mut compressed = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if (compressed.len() + chunk.len()) as u64 > MAX_DOWNLOAD_SIZE {
+                return Err(DocsRsError::TooLarge);
+            }
+            compressed.extend_from_slice(&chunk);
+        }
+        Ok(Some(compressed))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru<Arc<DocIndex>>> {
+        self.indices
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for DocsRs {
+    fn default() -> Self {
+        Self::new(DOCS_RS_URL, DOCS_CACHE_PATH)
+    }
+}
+
+fn key(name: &str, version: &str) -> String {
+    format!("{name}@{version}")
+}
+
+fn parse(compressed: &[u8]) -> Result<DocIndex, DocsRsError> {
+    let mut json = Vec::new();
+    GzDecoder::new(compressed)
+        .take(MAX_JSON_SIZE + 1)
+        .read_to_end(&mut json)?;
+    if json.len() as u64 > MAX_JSON_SIZE {
+        return Err(DocsRsError::TooLarge);
+    }
+    Ok(DocIndex::new(Crate::from_slice(&json)?))
+}
+
+/// Removes the least recently downloaded files of `dir` until it takes at most `max_size` bytes
+fn evict(dir: &Path, max_size: u64) -> io::Result<()> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_file() {
+            files.push((metadata.modified()?, metadata.len(), entry.path()));
+        }
+    }
+    files.sort_unstable();
+
+    let mut size = files.iter().map(|(_, len, _)| len).sum::<u64>();
+    for (_, len, path) in files {
+        if size <= max_size {
+            break;
+        }
+        fs::remove_file(path)?;
+        size -= len;
+    }
+    Ok(())
+}
+
+/// A small map which evicts the least recently used entry once it is full
+struct Lru<T> {
+    capacity: usize,
+    /// Most recently used first
+    entries: VecDeque<(String, T)>,
+}
+
+impl<T> Lru<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<&T> {
+        let i = self.entries.iter().position(|(k, _)| k == key)?;
+        let entry = self.entries.remove(i)?;
+        self.entries.push_front(entry);
+        self.entries.front().map(|(_, value)| value)
+    }
+
+    fn insert(&mut self, key: String, value: T) {
+        self.entries.retain(|(k, _)| *k != key);
+        self.entries.push_front((key, value));
+        self.entries.truncate(self.capacity);
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DocsRsError {
+    #[error("failed to download from docs.rs: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("failed to access the docs cache: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("the rustdoc JSON on docs.rs is too large")]
+    TooLarge,
+
+    #[error("building a docs index failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+
+    #[error(transparent)]
+    Rustdoc(#[from] RustdocError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{Compression, write::GzEncoder};
+    use std::{
+        io::Write,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    const DEMO: &str = r#"{
+        "root": 0,
+        "crate_version": "1.0.0",
+        "paths": {},
+        "index": {
+            "0": { "id": 0, "crate_id": 0, "name": "demo", "docs": null, "inner": { "module": { "is_crate": true, "items": [1] } } },
+            "1": { "id": 1, "crate_id": 0, "name": "Widget", "docs": "A widget.", "inner": { "struct": { "impls": [] } } }
+        }
+    }"#;
+
+    fn gzip(json: &str) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(json.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// Serves `demo` 1.0.0 like docs.rs and answers everything else with 404, counting requests
+    async fn stand_in() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                let mut request = vec![0; 4096];
+                let read = stream.read(&mut request).await.unwrap();
+                let found = request[..read].starts_with(b"GET /crate/demo/1.0.0/json.gz ");
+                if request[..read].starts_with(b"GET /crate/huge/1.0.0/json.gz ") {
+                    let head = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                        MAX_DOWNLOAD_SIZE + 1
+                    );
+                    let _ = stream.write_all(head.as_bytes()).await;
+                    continue;
+                }
+
+                let body = if found { gzip(DEMO) } else { Vec::new() };
+                let status = if found { "200 OK" } else { "404 Not Found" };
+                let head = format!(
+                    "HTTP/1.1 {status}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                    body.len()
+                );
+                stream.write_all(head.as_bytes()).await.unwrap();
+                stream.write_all(&body).await.unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    #[tokio::test]
+    async fn downloads_and_caches() {
+        let (url, requests) = stand_in().await;
+        let dir = std::env::temp_dir().join(format!("corro-docs-{}", std::process::id()));
+        let docs_rs = DocsRs::new(&url, &dir);
+
+        let index = docs_rs.index("demo", "1.0.0").await.unwrap().unwrap();
+        assert_eq!(index.name(), "demo");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert!(dir.join("demo-1.0.0.json.gz").exists());
+
+        // memory first, then the disk cache of a fresh client
+        assert!(docs_rs.index("demo", "1.0.0").await.unwrap().is_some());
+        let restarted = DocsRs::new(&url, &dir);
+        assert!(restarted.cached("demo", "1.0.0").is_none());
+        assert!(restarted.index("demo", "1.0.0").await.unwrap().is_some());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        assert!(docs_rs.index("demo", "0.1.0").await.unwrap().is_none());
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        assert!(matches!(
+            docs_rs.index("huge", "1.0.0").await,
+            Err(DocsRsError::TooLarge)
+        ));
+        assert!(!dir.join("huge-1.0.0.json.gz").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn evicts_the_oldest_downloads() {
+        let dir = std::env::temp_dir().join(format!("corro-evict-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let now = std::time::SystemTime::now();
+        for (i, name) in ["old", "middle", "new"].into_iter().enumerate() {
+            let file = fs::File::create(dir.join(name)).unwrap();
+            file.set_len(10).unwrap();
+            file.set_modified(now - std::time::Duration::from_secs(60 * (3 - i as u64)))
+                .unwrap();
+        }
+
+        evict(&dir, 30).unwrap();
+        assert!(dir.join("old").exists());
+        evict(&dir, 15).unwrap();
+        assert!(!dir.join("old").exists());
+        assert!(!dir.join("middle").exists());
+        assert!(dir.join("new").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn search_url() {
+        let docs_rs = DocsRs::default();
+        assert_eq!(
+            docs_rs.search_url("serde-json", "1.0.0", "from str"),
+            "https://docs.rs/serde-json/1.0.0/serde_json/?search=from+str"
+        );
+    }
+
+    #[test]
+    fn lru() {
+        let mut lru = Lru::new(2);
+        lru.insert("a".to_owned(), 1);
+        lru.insert("b".to_owned(), 2);
+        assert_eq!(lru.get("a"), Some(&1));
+
+        // `b` was used least recently
+        lru.insert("c".to_owned(), 3);
+        assert_eq!(lru.get("b"), None);
+        assert_eq!(lru.get("a"), Some(&1));
+        assert_eq!(lru.get("c"), Some(&3));
+
+        lru.insert("a".to_owned(), 4);
+        assert_eq!(lru.get("a"), Some(&4));
+        assert_eq!(lru.entries.len(), 2);
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{Data, cache::CacheError};
+use crate::{
+    Data, advisories::AdvisoryError, cache::CacheError, docs_rs::DocsRsError, rustdoc::RustdocError,
+};
 use log::warn;
 use poise::FrameworkError;
 
@@ -19,11 +21,26 @@ pub enum Error {
     #[error("Error interacting with poise: {0:?}")]
     Poise(#[from] poise::serenity_prelude::Error),
 
+    #[error("Error accessing the advisory database: {0:?}")]
+    Advisory(#[from] AdvisoryError),
+
+    #[error("Error loading rustdoc JSON: {0:?}")]
+    Rustdoc(#[from] RustdocError),
+
+    #[error("Error fetching docs from docs.rs: {0:?}")]
+    DocsRs(#[from] DocsRsError),
+
+    #[error("Error during a HTTP request: {0:?}")]
+    Http(#[from] reqwest::Error),
+
     #[error("Error while accessing the filesystem: {0:?}")]
     FilesystemIO(#[from] std::io::Error),
 
     #[error("Error while logging: {0:?}")]
     Log(#[from] log::SetLoggerError),
+
+    #[error("A background task failed: {0:?}")]
+    Task(#[from] tokio::task::JoinError),
 }
 
 impl Error {
@@ -61,6 +78,27 @@ pub enum CommandError {
     #[error("Invalid error code `{0}`! Please pass in a valid rustc error code.")]
     InvalidErrorCode(String),
 
+    #[error("No rustc error codes like `E0382` were found.")]
+    NoErrorCodes,
+
+    #[error("Pass either an error code or words to `search` for.")]
+    CodeOrSearch,
+
+    #[error("No rustc or clippy lint is called `{0}`.")]
+    UnknownLint(String),
+
+    #[error("No feature gate is called `{0}`.")]
+    UnknownFeature(String),
+
+    #[error("No tip is tagged `{0}`.")]
+    UnknownTipTag(String),
+
+    #[error("No tip explains `{0}` yet, `/tip` shows a random one.")]
+    NoTip(String),
+
+    #[error("Pick a channel to post the daily tip in.")]
+    NoTipChannel,
+
     #[error(
         "The ID `{0}` is invalid. Please provide a valid 32-byte hexadecimal GitHub Gist ID. Accepted formats include the raw ID, the full Gist URL, or the Gist embed snippet."
     )]
@@ -77,6 +115,32 @@ pub enum CommandError {
 
     #[error("No item matched your search: `{0}`")]
     NoMatch(String),
+
+    #[error("Failed to parse your code: {0}")]
+    ParseFailed(String),
+
+    #[error("Please provide a crate name or code to audit.")]
+    NothingToAudit,
+
+    #[error(
+        "The advisory database isn't loaded, the bot owner can download it with `refresh_advisories`."
+    )]
+    AdvisoriesNotLoaded,
+
+    #[error("`{0}` is not a valid semver version.")]
+    InvalidVersion(String),
+
+    #[error("No docs are loaded for the {0} channel.")]
+    DocsNotLoaded(&'static str),
+
+    #[error("The docs index is still loading, please try again in a moment.")]
+    DocsLoading,
+
+    #[error("Search either by `query` or by `signature`.")]
+    QueryOrSignature,
+
+    #[error("`{0}` is not a function type like `fn(&str) -> usize`.")]
+    InvalidSignature(String),
 }
 
 impl CommandError {
@@ -86,10 +150,16 @@ impl CommandError {
 }
 
 pub async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
-    if let FrameworkError::Command { error, ctx, .. } = error {
-        warn!("Error occured: {error}");
+    match error {
+        FrameworkError::Command { error, ctx, .. } => {
+            warn!("Error occured: {error}");
 
-        let user_msg = error.user_message();
-        let _ = ctx.say(user_msg).await;
+            let user_msg = error.user_message();
+            let _ = ctx.say(user_msg).await;
+        }
+        FrameworkError::EventHandler { error, event, .. } => {
+            warn!("Error handling {}: {error}", event.snake_case_name());
+        }
+        _ => {}
     }
 }
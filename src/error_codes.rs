@@ -0,0 +1,95 @@
+//! The rustc error codes and their explanations, generated from `assets/error_codes` by the
+//! build script
+
+/// A rustc error code with its explanation
+#[derive(Debug, PartialEq, Eq)]
+pub struct ErrorCode {
+    /// Like `E0382`
+    pub code: &'static str,
+    /// The first sentence of the summary
+    pub title: &'static str,
+    /// The first paragraph of the explanation
+    pub summary: &'static str,
+    /// Whether the compiler no longer emits the code
+    pub removed: bool,
+    /// The explanation as markdown
+    pub explanation: &'static str,
+    /// The code of the examples which fail to compile
+    pub examples: &'static [&'static str],
+}
+
+include!(concat!(env!("OUT_DIR"), "/error_codes.rs"));
+
+/// The error code `code`, in any case
+pub fn get(code: &str) -> Option<&'static ErrorCode> {
+    let code = code.trim().to_uppercase();
+    ERROR_CODES
+        .binary_search_by(|error_code| error_code.code.cmp(&code))
+        .ok()
+        .map(|i| &ERROR_CODES[i])
+}
+
+impl ErrorCode {
+    pub fn url(&self) -> String {
+        format!("https://doc.rust-lang.org/error_codes/{}.html", self.code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, path::Path};
+
+    #[test]
+    fn matches_the_assets() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/error_codes");
+        let mut assets = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "md"))
+            .filter_map(|path| {
+                let stem = path.file_stem()?.to_str()?.to_owned();
+                let is_code = stem.len() == 5
+                    && stem.starts_with('E')
+                    && stem[1..].bytes().all(|b| b.is_ascii_digit());
+                is_code.then_some((stem, path))
+            })
+            .collect::<Vec<_>>();
+        assets.sort();
+
+        let codes = ERROR_CODES.iter().map(|error_code| error_code.code);
+        assert!(
+            codes.eq(assets.iter().map(|(code, _)| code.as_str())),
+            "the registry is out of date, rebuild to regenerate it"
+        );
+        for (code, path) in &assets {
+            assert_eq!(
+                get(code).unwrap().explanation,
+                fs::read_to_string(path).unwrap(),
+                "the explanation of {code} is out of date"
+            );
+        }
+    }
+
+    #[test]
+    fn records_metadata() {
+        let e0382 = get("e0382").unwrap();
+        assert_eq!(e0382.code, "E0382");
+        assert_eq!(
+            e0382.title,
+            "A variable was used after its contents have been moved elsewhere"
+        );
+        assert!(!e0382.removed);
+        assert!(e0382.examples[0].contains("let y = x;"));
+
+        for removed in ["E0622", "E0699", "E0001"] {
+            assert!(get(removed).unwrap().removed, "{removed} is removed");
+        }
+        assert!(get("E0000").is_none());
+        assert!(
+            ERROR_CODES
+                .iter()
+                .all(|error_code| !error_code.title.is_empty())
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::error_codes::ErrorCode;
+use std::collections::HashMap;
+
+/// BM25 parameters, the usual defaults
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+/// How often the words of the summary are counted
+const SUMMARY_WEIGHT: u32 = 3;
+
+/// The explanations of rustc error codes, searchable by the words they use
+#[derive(Debug, Default)]
+pub struct ErrorIndex {
+    /// Sorted by code
+    error_codes: &'static [ErrorCode],
+    /// For each term, the explanations using it and how often
+    postings: HashMap<String, Vec<(usize, u32)>>,
+    /// Number of terms in each explanation
+    lengths: Vec<u32>,
+    average_length: f64,
+}
+
+impl ErrorIndex {
+    /// Indexes the explanations of `error_codes`, which are sorted by code
+    pub fn new(error_codes: &'static [ErrorCode]) -> Self {
+        let mut index = Self {
+            error_codes,
+            ..Self::default()
+        };
+        for (i, error_code) in error_codes.iter().enumerate() {
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            // the summary says what the error is about, so its words count more
+            for term in terms(&prose(error_code.explanation)) {
+                *counts.entry(term).or_default() += 1;
+            }
+            for term in terms(error_code.summary) {
+                *counts.entry(term).or_default() += SUMMARY_WEIGHT - 1;
+            }
+            index.lengths.push(counts.values().sum());
+            for (term, count) in counts {
+                index.postings.entry(term).or_default().push((i, count));
+            }
+        }
+
+        let total: u32 = index.lengths.iter().sum();
+        index.average_length = f64::from(total) / index.lengths.len().max(1) as f64;
+        index
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'static ErrorCode> {
+        self.error_codes.iter()
+    }
+
+    /// The `limit` explanations most relevant to `query`, best first
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&'static ErrorCode> {
+        let documents = self.error_codes.len() as f64;
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+
+        let mut query_terms = terms(query).collect::<Vec<_>>();
+        query_terms.sort();
+        query_terms.dedup();
+        for term in query_terms {
+            let Some(postings) = self.postings.get(&term) else {
+                continue;
+            };
+
+            let matching = postings.len() as f64;
+            let idf = (1.0 + (documents - matching + 0.5) / (matching + 0.5)).ln();
+            for &(i, count) in postings {
+                let count = f64::from(count);
+                let length = f64::from(self.lengths[i]) / self.average_length;
+                *scores.entry(i).or_default() +=
+                    idf * count * (K1 + 1.0) / (count + K1 * (1.0 - B + B * length));
+            }
+        }
+
+        let mut ranked = scores.into_iter().collect::<Vec<_>>();
+        ranked.sort_by(|(a, a_score), (b, b_score)| b_score.total_cmp(a_score).then(a.cmp(b)));
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(|(i, _)| &self.error_codes[i])
+            .collect()
+    }
+}
+
+/// The text outside of code blocks, examples would drown the explanation in identifiers
+fn prose(markdown: &str) -> String {
+    let mut in_fence = false;
+    markdown
+        .lines()
+        .filter(|line| {
+            if line.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+                return false;
+            }
+            !in_fence
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Lowercase words with their endings cut off, so "moving" finds "moved"
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() > 1)
+        .map(|word| stem(&word.to_lowercase()))
+}
+
+fn stem(word: &str) -> String {
+    let mut stem = word;
+    for suffix in ["ing", "ed", "es", "s"] {
+        if let Some(rest) = stem.strip_suffix(suffix)
+            && rest.len() >= 2
+        {
+            stem = rest;
+            break;
+        }
+    }
+    if let Some(rest) = stem.strip_suffix('e')
+        && rest.len() >= 2
+    {
+        stem = rest;
+    }
+    stem.to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_codes::ERROR_CODES;
+
+    const fn error_code(
+        code: &'static str,
+        summary: &'static str,
+        explanation: &'static str,
+    ) -> ErrorCode {
+        ErrorCode {
+            code,
+            title: summary,
+            summary,
+            removed: false,
+            explanation,
+            examples: &[],
+        }
+    }
+
+    static ERROR_CODES_FIXTURE: &[ErrorCode] = &[
+        error_code(
+            "E0001",
+            "This error suggests that the expression arm will never be reached.",
+            "## Note: this error code is no longer emitted by the compiler.\n\nThis error suggests that the expression arm will never be reached.",
+        ),
+        error_code(
+            "E0382",
+            "A variable was used after its contents have been moved elsewhere.",
+            "A variable was used after its contents have been moved elsewhere.\n\nErroneous code example:\n\n```\nlet y = x;\n```",
+        ),
+        error_code(
+            "E0499",
+            "A variable was borrowed as mutable more than once.",
+            "A variable was borrowed as mutable more than once.\n\nErroneous code example:\n\n```\nlet a = &mut x;\n```",
+        ),
+        error_code(
+            "E0507",
+            "A borrowed value was moved out.",
+            "A borrowed value was moved out.\n\nErroneous code example:\n\n```compile_fail,E0507\nlet x = borrow.move_out();\n```\n\nYou tried to move out of a borrow.",
+        ),
+    ];
+
+    #[test]
+    fn ranks_by_relevance() {
+        let index = ErrorIndex::new(ERROR_CODES_FIXTURE);
+        let codes = |query: &str| {
+            index
+                .search(query, 3)
+                .into_iter()
+                .map(|error_code| error_code.code)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(codes("moving out of a borrow")[0], "E0507");
+        assert_eq!(codes("use after move")[0], "E0382");
+        assert_eq!(codes("mutable borrows"), ["E0499", "E0507"]);
+        assert!(codes("lifetime").is_empty());
+        assert_eq!(index.iter().next().unwrap().code, "E0001");
+    }
+
+    #[test]
+    fn indexes_the_registry() {
+        let index = ErrorIndex::new(ERROR_CODES);
+
+        assert_eq!(
+            index.search("cannot borrow as mutable more than once", 1)[0].code,
+            "E0499"
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! The feature gates of the compiler and the standard library, generated from `assets/features` by
+//! the build script
+
+use crate::common::fuzzy_score;
+use std::fmt;
+
+/// A feature gate with its documentation from the Unstable Book
+#[derive(Debug, PartialEq, Eq)]
+pub struct Feature {
+    /// Like `let_chains`, as written in `#![feature(..)]`
+    pub name: &'static str,
+    pub kind: Kind,
+    pub status: Status,
+    /// The number of the tracking issue in rust-lang/rust, if it has one
+    pub issue: Option<u32>,
+    /// A short description of what the feature enables
+    pub description: &'static str,
+    /// The documentation as markdown, only the APIs and an example for stabilized features
+    pub explanation: &'static str,
+    /// The code of the first example
+    pub example: Option<&'static str>,
+}
+
+/// Whether a feature gates language syntax and semantics or library APIs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Language,
+    Library,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Only usable on nightly with `#![feature(..)]`
+    Unstable,
+    /// Stabilized in this version, the attribute isn't needed anymore
+    Stable(&'static str),
+    /// Removed from the compiler without being stabilized
+    Removed,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Language => "language",
+            Self::Library => "library",
+        })
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unstable => f.write_str("unstable"),
+            Self::Stable(since) => write!(f, "stable since {since}"),
+            Self::Removed => f.write_str("removed"),
+        }
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/features.rs"));
+
+impl Feature {
+    /// The page of the Unstable Book, which only documents unstable features
+    pub fn url(&self) -> Option<String> {
+        (self.status == Status::Unstable).then(|| {
+            format!(
+                "https://doc.rust-lang.org/nightly/unstable-book/{}-features/{}.html",
+                self.kind,
+                self.name.replace('_', "-")
+            )
+        })
+    }
+
+    pub fn issue_url(&self) -> Option<String> {
+        self.issue
+            .map(|issue| format!("https://github.com/rust-lang/rust/issues/{issue}"))
+    }
+}
+
+/// The feature called `name`, also accepting the whole `#![feature(name)]` attribute
+pub fn get(name: &str) -> Option<&'static Feature> {
+    let name = normalize(name);
+    FEATURES
+        .binary_search_by(|feature| feature.name.cmp(&name))
+        .ok()
+        .map(|i| &FEATURES[i])
+}
+
+/// The features tracked in the rust-lang/rust issue `issue`
+pub fn by_issue(issue: u32) -> impl Iterator<Item = &'static Feature> {
+    FEATURES
+        .iter()
+        .filter(move |feature| feature.issue == Some(issue))
+}
+
+/// The `limit` features whose names match `query` best, its characters have to appear in order
+pub fn search(query: &str, limit: usize) -> Vec<&'static Feature> {
+    let query = normalize(query);
+
+    let mut hits = FEATURES
+        .iter()
+        .filter_map(|feature| Some((fuzzy_score(feature.name, &query)?, feature)))
+        .collect::<Vec<_>>();
+    hits.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then((a.status != Status::Unstable).cmp(&(b.status != Status::Unstable)))
+            .then(a.name.len().cmp(&b.name.len()))
+            .then(a.name.cmp(b.name))
+    });
+    hits.into_iter()
+        .take(limit)
+        .map(|(_, feature)| feature)
+        .collect()
+}
+
+/// `#![feature(Let-Chains)]` and `let_chains` name the same feature
+fn normalize(name: &str) -> String {
+    let name = name.trim();
+    let name = name
+        .strip_prefix("#![feature(")
+        .and_then(|name| name.strip_suffix(")]"))
+        .unwrap_or(name);
+    name.trim().to_lowercase().replace('-', "_")
+}
+
+/// The features named in `E0658` errors of compiler output, either directly or, as stable
+/// compilers only link the tracking issue of language features, by their issue. Stabilized
+/// features sharing that issue are left out, the error is about one that isn't stable yet.
+pub fn in_diagnostics(output: &str) -> Vec<&'static Feature> {
+    let mut features = Vec::new();
+    let mut in_e0658 = false;
+    for line in output.lines() {
+        if line.starts_with("error") || line.starts_with("warning") {
+            in_e0658 = line.starts_with("error[E0658]");
+        }
+        if !in_e0658 {
+            continue;
+        }
+
+        let named = ["unstable library feature `", "add `#![feature("]
+            .iter()
+            .filter_map(|prefix| {
+                let (_, rest) = line.split_once(prefix)?;
+                let end = rest.find(['`', ')'])?;
+                get(&rest[..end])
+            })
+            .collect::<Vec<_>>();
+        let tracked = line
+            .split_once("see issue #")
+            .and_then(|(_, rest)| rest.split(' ').next()?.parse().ok())
+            .map(|issue| {
+                by_issue(issue)
+                    .filter(|feature| !matches!(feature.status, Status::Stable(_)))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        for feature in named.into_iter().chain(tracked) {
+            if !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+    }
+    features
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_features_by_name() {
+        let feature = get("let_chains").unwrap();
+        assert_eq!(feature.kind, Kind::Language);
+        assert_eq!(feature.status, Status::Stable("1.88.0"));
+        assert_eq!(feature.url(), None);
+        assert_eq!(feature.issue, Some(53667));
+        assert!(feature.example.unwrap().contains("&& let Some"));
+        assert_eq!(get("#![feature(Let-Chains)]"), Some(feature));
+
+        let feature = get("box_leak").unwrap();
+        assert_eq!(feature.kind, Kind::Library);
+        assert_eq!(feature.status, Status::Stable("1.26.0"));
+        assert!(feature.example.unwrap().contains("Box::leak(x)"));
+
+        let feature = get("iter_intersperse").unwrap();
+        assert_eq!(feature.kind, Kind::Library);
+        assert_eq!(feature.status, Status::Unstable);
+        assert_eq!(
+            feature.issue_url().as_deref(),
+            Some("https://github.com/rust-lang/rust/issues/79524")
+        );
+        assert_eq!(
+            feature.url().as_deref(),
+            Some(
+                "https://doc.rust-lang.org/nightly/unstable-book/library-features/iter-intersperse.html"
+            )
+        );
+        assert!(feature.example.unwrap().contains("intersperse(100)"));
+
+        assert_eq!(get("const_generics").unwrap().status, Status::Removed);
+        assert!(get("no_such_feature").is_none());
+    }
+
+    #[test]
+    fn registry_is_complete() {
+        assert!(FEATURES.windows(2).all(|pair| pair[0].name < pair[1].name));
+        assert!(FEATURES.iter().any(|feature| feature.kind == Kind::Library));
+        for feature in FEATURES {
+            assert!(!feature.description.is_empty(), "{}", feature.name);
+        }
+    }
+
+    #[test]
+    fn fuzzy_search() {
+        let names = |query: &str| {
+            search(query, 3)
+                .into_iter()
+                .map(|feature| feature.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names("try_blocks")[0], "try_blocks");
+        assert_eq!(names("#![feature(never_type)]")[0], "never_type");
+        assert_eq!(names("itrintrsprs")[0], "iter_intersperse");
+        assert!(names("zzzzzz").is_empty());
+    }
+
+    #[test]
+    fn finds_features_in_diagnostics() {
+        let nightly = "error[E0658]: `try` expression is experimental
+ --> src/main.rs:1:13
+  |
+  = note: see issue #154391 <https://github.com/rust-lang/rust/issues/154391> for more information
+  = help: add `#![feature(try_blocks)]` to the crate attributes to enable
+
+error[E0658]: use of unstable library feature `iter_intersperse`
+ --> src/main.rs:1:48
+  |
+  = note: see issue #79524 <https://github.com/rust-lang/rust/issues/79524> for more information
+";
+        let names = in_diagnostics(nightly)
+            .into_iter()
+            .map(|feature| feature.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["try_blocks", "iter_intersperse"]);
+
+        let stable = "error[E0658]: `!` is not yet stable as a type
+  = note: see issue #35121 <https://github.com/rust-lang/rust/issues/35121> for more information
+
+error[E0308]: mismatched types
+  = note: see issue #79524 <https://github.com/rust-lang/rust/issues/79524> for more information
+";
+        assert!(
+            in_diagnostics(stable)
+                .iter()
+                .any(|feature| feature.name == "never_type")
+        );
+        assert!(
+            in_diagnostics(stable)
+                .iter()
+                .all(|feature| feature.name != "iter_intersperse")
+        );
+
+        let shared = "error[E0658]: transparent unions are unstable
+  = note: see issue #60405 <https://github.com/rust-lang/rust/issues/60405> for more information
+";
+        let names = in_diagnostics(shared)
+            .into_iter()
+            .map(|feature| feature.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["transparent_unions"]);
+    }
+}
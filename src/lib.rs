@@ -1,49 +1,84 @@
+pub mod advisories;
+pub mod books;
 mod cache;
 pub mod commands;
 mod common;
+mod doc_links;
+mod doc_sets;
+mod docs_rs;
 mod error;
-
-use docsrs::Doc;
-use docsrs::Indexed;
+pub mod error_codes;
+mod error_index;
+pub mod features;
+pub mod lints;
+mod rustdoc;
+mod settings;
+pub mod tips;
+
+use advisories::{ADVISORIES_PATH, AdvisoryDb};
+use doc_sets::{DOCS_PATH, DocSets, SNAPSHOTS_PATH};
+use docs_rs::DocsRs;
 pub use error::Error;
 pub use error::on_error;
+use error_codes::ERROR_CODES;
+use error_index::ErrorIndex;
+use poise::serenity_prelude as serenity;
+use std::{
+    path::Path,
+    sync::{Arc, RwLock},
+};
 
 pub type Context<'a> = poise::Context<'a, Data, Error>;
 
+/// Handles gateway events which aren't commands
+pub async fn event_handler(
+    ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+    _framework: poise::FrameworkContext<'_, Data, Error>,
+    data: &Data,
+) -> Result<(), Error> {
+    match event {
+        serenity::FullEvent::Message { new_message } => {
+            doc_links::on_message(ctx, new_message, data).await?;
+        }
+        serenity::FullEvent::InteractionCreate { interaction } => {
+            if let Some(press) = interaction.as_message_component() {
+                commands::run_tip_example(ctx, press, data).await?;
+                commands::show_compiler_output(ctx, press, data).await?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
 pub struct Data {
     pub playground_client: playground_api::Client,
     pub redis_client: cache::Client,
     pub crates_io_client: crates_io_api::AsyncClient,
     pub max_code_size: u32,
-    pub std: Doc<Indexed>,
-    pub core: Doc<Indexed>,
-    pub alloc: Doc<Indexed>,
+    pub docs: Arc<DocSets>,
+    pub docs_rs: DocsRs,
+    pub advisories: RwLock<AdvisoryDb>,
+    pub error_index: ErrorIndex,
 }
 
 impl Default for Data {
     fn default() -> Self {
         let email = std::env::var("EMAIL").expect("no email specified in the environment");
 
-        info!("reading, parsing and building searchindex for std.json");
-        let std = Doc::from_json("./assets/docs/std.json")
-            .unwrap()
-            .parse()
-            .unwrap()
-            .build_search_index();
-
-        info!("reading, parsing and building searchindex for core.json");
-        let core = Doc::from_json("./assets/docs/core.json")
-            .unwrap()
-            .parse()
-            .unwrap()
-            .build_search_index();
-
-        info!("reading, parsing and building searchindex for alloc.json");
-        let alloc = Doc::from_json("./assets/docs/alloc.json")
-            .unwrap()
-            .parse()
-            .unwrap()
-            .build_search_index();
+        // `/docs` answers that the index is still loading until this is done
+        let docs = Arc::new(DocSets::default());
+        docs.load_in_background(Path::new(DOCS_PATH), Path::new(SNAPSHOTS_PATH));
+
+        info!("loading the advisory database");
+        let advisories = AdvisoryDb::load(ADVISORIES_PATH).unwrap_or_else(|e| {
+            warn!("failed to load the advisory database: {e}");
+            AdvisoryDb::default()
+        });
+
+        info!("indexing the error code explanations");
+        let error_index = ErrorIndex::new(ERROR_CODES);
 
         Self {
             playground_client: playground_api::Client::default(),
@@ -54,9 +89,10 @@ impl Default for Data {
             )
             .expect("failed to create an AsyncClient"),
             max_code_size: 64 * 1024,
-            std,
-            core,
-            alloc,
+            docs,
+            docs_rs: DocsRs::default(),
+            advisories: RwLock::new(advisories),
+            error_index,
         }
     }
 }
@@ -66,7 +102,7 @@ use chrono::Local;
 use fern::Dispatch;
 use fern::colors::ColoredLevelConfig;
 use log::LevelFilter;
-use log::info;
+use log::{info, warn};
 
 pub fn setup_logging() -> Result<(), Box<Error>> {
     let colors = ColoredLevelConfig::new()
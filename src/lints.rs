@@ -0,0 +1,167 @@
+//! The rustc and clippy lints and their explanations, generated from `assets/lints` by the build
+//! script
+
+use crate::common::fuzzy_score;
+use std::fmt;
+
+/// A lint with its documentation
+#[derive(Debug, PartialEq, Eq)]
+pub struct Lint {
+    /// Like `unused_must_use` or `clippy::needless_borrow`
+    pub name: &'static str,
+    /// Like `style` for clippy lints or `unused` for rustc lints, if it belongs to one
+    pub group: Option<&'static str>,
+    pub level: Level,
+    /// A short description of what the lint detects
+    pub description: &'static str,
+    /// The clippy version which added the lint like `1.66.0` or `pre 1.29.0`, if it's known
+    pub since: Option<&'static str>,
+    /// The documentation as markdown
+    pub explanation: &'static str,
+    /// The code of the first example
+    pub example: Option<&'static str>,
+}
+
+/// The level a lint has by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+    Forbid,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Allow => "allow",
+            Self::Warn => "warn",
+            Self::Deny => "deny",
+            Self::Forbid => "forbid",
+        })
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/lints.rs"));
+
+impl Lint {
+    /// The name without the tool, like `needless_borrow`
+    pub fn short_name(&self) -> &'static str {
+        self.name.strip_prefix("clippy::").unwrap_or(self.name)
+    }
+
+    pub fn is_clippy(&self) -> bool {
+        self.name.starts_with("clippy::")
+    }
+
+    pub fn url(&self) -> String {
+        if self.is_clippy() {
+            format!(
+                "https://rust-lang.github.io/rust-clippy/master/index.html#{}",
+                self.short_name()
+            )
+        } else {
+            let page = match self.level {
+                Level::Allow => "allowed-by-default",
+                Level::Warn => "warn-by-default",
+                Level::Deny | Level::Forbid => "deny-by-default",
+            };
+            format!(
+                "https://doc.rust-lang.org/rustc/lints/listing/{page}.html#{}",
+                self.name.replace('_', "-")
+            )
+        }
+    }
+}
+
+/// The lint called `name`, written like in an attribute or on the command line, with or without
+/// the `clippy::` prefix
+pub fn get(name: &str) -> Option<&'static Lint> {
+    let name = normalize(name);
+    find(&name).or_else(|| find(&format!("clippy::{name}")))
+}
+
+fn find(name: &str) -> Option<&'static Lint> {
+    LINTS
+        .binary_search_by(|lint| lint.name.cmp(name))
+        .ok()
+        .map(|i| &LINTS[i])
+}
+
+/// The `limit` lints whose names match `query` best, its characters have to appear in order
+pub fn search(query: &str, limit: usize) -> Vec<&'static Lint> {
+    let query = normalize(query);
+    let query = query.strip_prefix("clippy::").unwrap_or(&query);
+
+    let mut hits = LINTS
+        .iter()
+        .filter_map(|lint| Some((fuzzy_score(lint.short_name(), query)?, lint)))
+        .collect::<Vec<_>>();
+    hits.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then(a.name.len().cmp(&b.name.len()))
+            .then(a.name.cmp(b.name))
+    });
+    hits.into_iter().take(limit).map(|(_, lint)| lint).collect()
+}
+
+/// `clippy::Needless-Borrow` and `needless_borrow` name the same lint
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase().replace('-', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_lints_by_name() {
+        let lint = get("clippy::needless_borrow").unwrap();
+        assert_eq!(lint.group, Some("style"));
+        assert_eq!(lint.level, Level::Warn);
+        assert_eq!(lint.since, Some("pre 1.29.0"));
+        assert!(lint.example.is_some());
+        assert_eq!(get("needless-borrow"), Some(lint));
+        assert_eq!(
+            lint.url(),
+            "https://rust-lang.github.io/rust-clippy/master/index.html#needless_borrow"
+        );
+        assert_eq!(get("clippy::box_default").unwrap().since, Some("1.66.0"));
+
+        let lint = get("UNUSED_MUST_USE").unwrap();
+        assert_eq!(lint.name, "unused_must_use");
+        assert_eq!(lint.group, Some("unused"));
+        assert_eq!(
+            lint.url(),
+            "https://doc.rust-lang.org/rustc/lints/listing/warn-by-default.html#unused-must-use"
+        );
+        assert!(get("no_such_lint").is_none());
+    }
+
+    #[test]
+    fn registry_is_complete() {
+        assert!(LINTS.windows(2).all(|pair| pair[0].name < pair[1].name));
+        assert!(LINTS.iter().any(|lint| !lint.is_clippy()));
+        for lint in LINTS {
+            assert!(!lint.description.is_empty(), "{}", lint.name);
+            assert!(!lint.explanation.is_empty(), "{}", lint.name);
+            assert!(!lint.is_clippy() || lint.since.is_some(), "{}", lint.name);
+        }
+    }
+
+    #[test]
+    fn fuzzy_search() {
+        let names = |query: &str| {
+            search(query, 3)
+                .into_iter()
+                .map(|lint| lint.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names("needless_borrow")[0], "clippy::needless_borrow");
+        assert_eq!(names("unused_must")[0], "unused_must_use");
+        assert_eq!(names("clippy::ndlsbrw")[0], "clippy::needless_borrow");
+        assert!(names("zzzzzz").is_empty());
+    }
+}
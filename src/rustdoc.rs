@@ -0,0 +1,13 @@
+mod members;
+mod model;
+mod render;
+mod search;
+mod signatures;
+mod snapshot;
+mod types;
+
+pub use members::{Category, Member, MemberList, resolve};
+pub use model::{Crate, Id, Inner, Item, Kind, RustdocError};
+pub use render::{Sections, sections};
+pub use search::{DocIndex, Hit, search};
+pub use signatures::{Signature, search_signature};
@@ -0,0 +1,385 @@
+use super::{DocIndex, Hit, Id, Inner, Item, Kind, types::Type};
+use std::fmt;
+
+/// The lists which can be browsed from an item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Variants,
+    Methods,
+    TraitImpls,
+    RequiredMethods,
+    ProvidedMethods,
+    AssocItems,
+    Implementors,
+    Items,
+}
+
+/// An entry of a member list, `id` is the item it leads to if it has a page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub label: String,
+    pub kind: Kind,
+    pub id: Option<Id>,
+}
+
+#[derive(Debug)]
+pub struct MemberList {
+    pub category: Category,
+    pub members: Vec<Member>,
+}
+
+impl DocIndex {
+    /// The members of `item` grouped like on its page, empty lists are left out. Auto trait and
+    /// blanket impls are skipped since nearly every type has them.
+    pub fn members(&self, item: &Item) -> Vec<MemberList> {
+        let mut lists = Vec::new();
+
+        match &item.inner {
+            Inner::Enum {
+                variants, impls, ..
+            } => {
+                lists.push(MemberList::new(Category::Variants, self.named(variants)));
+                self.impl_members(impls, &mut lists);
+            }
+            Inner::Struct { impls, .. }
+            | Inner::Union { impls, .. }
+            | Inner::Primitive { impls } => {
+                self.impl_members(impls, &mut lists);
+            }
+            Inner::Trait {
+                items,
+                implementations,
+                ..
+            } => {
+                let (functions, others): (Vec<_>, Vec<_>) = self
+                    .named(items)
+                    .into_iter()
+                    .partition(|member| member.kind == Kind::Method);
+                let (required, provided) = functions.into_iter().partition(|member| {
+                    matches!(
+                        member.id.as_ref().and_then(|id| self.item(id)).map(|item| &item.inner),
+                        Some(Inner::Function(function)) if !function.has_body
+                    )
+                });
+
+                lists.push(MemberList::new(Category::RequiredMethods, required));
+                lists.push(MemberList::new(Category::ProvidedMethods, provided));
+                lists.push(MemberList::new(Category::AssocItems, others));
+                lists.push(MemberList::new(
+                    Category::Implementors,
+                    self.implementors(implementations),
+                ));
+            }
+            Inner::Module { items } => {
+                lists.push(MemberList::new(Category::Items, self.module_items(items)));
+            }
+            _ => {}
+        }
+
+        lists.retain(|list| !list.members.is_empty());
+        lists
+    }
+
+    /// Inherent methods and implemented traits
+    fn impl_members(&self, impls: &[Id], lists: &mut Vec<MemberList>) {
+        let mut methods = Vec::new();
+        let mut traits = Vec::new();
+
+        for id in impls {
+            let Some(Inner::Impl {
+                trait_,
+                items,
+                is_negative,
+                is_synthetic: false,
+                is_blanket: false,
+                ..
+            }) = self.item(id).map(|item| &item.inner)
+            else {
+                continue;
+            };
+
+            match trait_ {
+                None => methods.extend(self.named(items)),
+                Some(path) => traits.push(Member {
+                    label: negated(path, *is_negative),
+                    kind: Kind::Trait,
+                    id: path.id.clone(),
+                }),
+            }
+        }
+
+        lists.push(MemberList::new(Category::Methods, methods));
+        lists.push(MemberList::new(Category::TraitImpls, sorted(traits)));
+    }
+
+    /// The types the impls in `implementations` are for
+    fn implementors(&self, implementations: &[Id]) -> Vec<Member> {
+        let implementors = implementations
+            .iter()
+            .filter_map(|id| match &self.item(id)?.inner {
+                Inner::Impl {
+                    for_,
+                    is_negative,
+                    is_synthetic: false,
+                    is_blanket: false,
+                    ..
+                } => Some(Member {
+                    label: negated(for_, *is_negative),
+                    kind: match for_ {
+                        Type::Primitive(_) => Kind::Primitive,
+                        Type::Path(path) => {
+                            path.id.as_ref().map_or(Kind::Other, |id| self.kind(id))
+                        }
+                        _ => Kind::Other,
+                    },
+                    id: match for_ {
+                        Type::Path(path) => path.id.clone(),
+                        _ => None,
+                    },
+                }),
+                _ => None,
+            })
+            .collect();
+
+        sorted(implementors)
+    }
+
+    /// Named items of a module with re-exports leading to their target, impls and globs are left
+    /// out
+    fn module_items(&self, ids: &[Id]) -> Vec<Member> {
+        let items = ids
+            .iter()
+            .filter_map(|id| {
+                let item = self.item(id)?;
+                match &item.inner {
+                    Inner::Use {
+                        name,
+                        id: Some(target),
+                        is_glob: false,
+                    } => Some(Member {
+                        label: name.clone(),
+                        kind: self.kind(target),
+                        id: Some(target.clone()),
+                    }),
+                    Inner::Use { .. } | Inner::Impl { .. } => None,
+                    _ => Some(Member {
+                        label: item.name.clone()?,
+                        kind: item.kind(),
+                        id: Some(id.clone()),
+                    }),
+                }
+            })
+            .collect();
+
+        sorted(items)
+    }
+
+    /// Members of a type or trait in the order they are declared
+    fn named(&self, ids: &[Id]) -> Vec<Member> {
+        ids.iter()
+            .filter_map(|id| {
+                let item = self.item(id)?;
+                Some(Member {
+                    label: item.name.clone()?,
+                    kind: match item.kind() {
+                        Kind::Function => Kind::Method,
+                        kind => kind,
+                    },
+                    id: Some(id.clone()),
+                })
+            })
+            .collect()
+    }
+
+    fn kind(&self, id: &Id) -> Kind {
+        self.item(id)
+            .map(Item::kind)
+            .or_else(|| self.paths.get(id).map(|summary| summary.kind))
+            .unwrap_or(Kind::Other)
+    }
+}
+
+impl MemberList {
+    fn new(category: Category, members: Vec<Member>) -> Self {
+        Self { category, members }
+    }
+}
+
+/// The hit for an item referenced from `index`. Items of other crates are looked up in the
+/// crate of `indices` which defines them, the path stays the one `index` knows them by.
+pub fn resolve<'a>(indices: &[&'a DocIndex], index: &'a DocIndex, id: &Id) -> Option<Hit<'a>> {
+    let local = index.entry(id);
+    if let Some(entry) = local
+        && index.item(id).is_some()
+    {
+        return Some(Hit {
+            path: &entry.path,
+            entry,
+            index,
+        });
+    }
+
+    let origin = match local {
+        Some(entry) => entry.origin.clone(),
+        None => index.paths.get(id)?.path.join("::"),
+    };
+    let defined = indices
+        .iter()
+        .find_map(|&other| Some((other, other.defined(&origin)?)));
+
+    match (local, defined) {
+        (Some(local), Some((other, entry))) => Some(Hit {
+            path: &local.path,
+            entry,
+            index: other,
+        }),
+        (None, Some((other, entry))) => Some(Hit {
+            path: &entry.path,
+            entry,
+            index: other,
+        }),
+        (Some(entry), None) => Some(Hit {
+            path: &entry.path,
+            entry,
+            index,
+        }),
+        (None, None) => None,
+    }
+}
+
+/// Negative impls like `impl !Send for T` are labelled with the `!`
+fn negated(implemented: impl fmt::Display, is_negative: bool) -> String {
+    format!("{}{implemented}", if is_negative { "!" } else { "" })
+}
+
+fn sorted(mut members: Vec<Member>) -> Vec<Member> {
+    members.sort_by(|a, b| a.label.cmp(&b.label));
+    members.dedup_by(|a, b| a.label == b.label);
+    members
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Variants => "Variants",
+            Self::Methods => "Methods",
+            Self::TraitImpls => "Trait implementations",
+            Self::RequiredMethods => "Required methods",
+            Self::ProvidedMethods => "Provided methods",
+            Self::AssocItems => "Associated items",
+            Self::Implementors => "Implementors",
+            Self::Items => "Items",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rustdoc::Crate;
+
+    const STD: &str = r#"{
+        "root": 0,
+        "paths": {
+            "2": { "crate_id": 0, "path": ["std", "vec", "Vec"], "kind": "struct" },
+            "20": { "crate_id": 1, "path": ["core", "clone", "Clone"], "kind": "trait" }
+        },
+        "index": {
+            "0": { "id": 0, "crate_id": 0, "name": "std", "docs": null, "inner": { "module": { "is_crate": true, "items": [1, 9] } } },
+            "1": { "id": 1, "crate_id": 0, "name": "vec", "docs": null, "inner": { "module": { "is_crate": false, "items": [2, 7] } } },
+            "2": { "id": 2, "crate_id": 0, "name": "Vec", "docs": null, "inner": { "struct": { "impls": [3, 5, 6, 8] } } },
+            "3": { "id": 3, "crate_id": 0, "name": null, "docs": null, "inner": { "impl": { "trait": null, "for": { "resolved_path": { "path": "Vec", "id": 2 } }, "items": [4] } } },
+            "4": { "id": 4, "crate_id": 0, "name": "push", "docs": null, "inner": { "function": {} } },
+            "5": { "id": 5, "crate_id": 0, "name": null, "docs": null, "inner": { "impl": { "trait": { "path": "Clone", "id": 20 }, "for": { "resolved_path": { "path": "Vec", "id": 2 } }, "items": [] } } },
+            "6": { "id": 6, "crate_id": 0, "name": null, "docs": null, "inner": { "impl": { "trait": { "path": "Send", "id": 21 }, "for": { "resolved_path": { "path": "Vec", "id": 2 } }, "items": [], "is_synthetic": true } } },
+            "7": { "id": 7, "crate_id": 0, "name": null, "docs": null, "inner": { "use": { "source": "core::clone::Clone", "name": "Clone", "id": 20, "is_glob": false } } },
+            "8": { "id": 8, "crate_id": 0, "name": null, "docs": null, "inner": { "impl": { "trait": { "path": "From", "id": 22, "args": { "angle_bracketed": { "args": [{ "type": { "generic": "T" } }], "constraints": [] } } }, "for": { "generic": "T" }, "items": [], "blanket_impl": { "generic": "T" } } } },
+            "9": { "id": 9, "crate_id": 0, "name": null, "docs": null, "inner": { "impl": { "trait": null, "for": { "primitive": "u8" }, "items": [] } } }
+        }
+    }"#;
+
+    const CORE: &str = r#"{
+        "root": 0,
+        "paths": {
+            "1": { "crate_id": 0, "path": ["core", "clone", "Clone"], "kind": "trait" }
+        },
+        "index": {
+            "0": { "id": 0, "crate_id": 0, "name": "core", "docs": null, "inner": { "module": { "is_crate": true, "items": [5] } } },
+            "5": { "id": 5, "crate_id": 0, "name": "clone", "docs": null, "inner": { "module": { "is_crate": false, "items": [1] } } },
+            "1": { "id": 1, "crate_id": 0, "name": "Clone", "docs": null, "inner": { "trait": { "items": [2, 3], "implementations": [4, 6] } } },
+            "2": { "id": 2, "crate_id": 0, "name": "clone", "docs": null, "inner": { "function": { "has_body": false } } },
+            "3": { "id": 3, "crate_id": 0, "name": "clone_from", "docs": null, "inner": { "function": { "has_body": true } } },
+            "4": { "id": 4, "crate_id": 0, "name": null, "docs": null, "inner": { "impl": { "trait": { "path": "Clone", "id": 1 }, "for": { "primitive": "u8" }, "items": [] } } },
+            "6": { "id": 6, "crate_id": 0, "name": null, "docs": null, "inner": { "impl": { "trait": { "path": "Clone", "id": 1 }, "for": { "borrowed_ref": { "lifetime": null, "is_mutable": false, "type": { "generic": "T" } } }, "items": [] } } }
+        }
+    }"#;
+
+    fn index(json: &str) -> DocIndex {
+        DocIndex::new(Crate::from_slice(json.as_bytes()).unwrap())
+    }
+
+    fn labels(index: &DocIndex, id: &str) -> Vec<(Category, Vec<String>)> {
+        let item = index.item(&Id(id.to_owned())).unwrap();
+        index
+            .members(item)
+            .into_iter()
+            .map(|list| {
+                let labels = list.members.into_iter().map(|member| member.label);
+                (list.category, labels.collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn type_members() {
+        let std = index(STD);
+
+        // the auto trait and the blanket impl are left out
+        assert_eq!(
+            labels(&std, "2"),
+            [
+                (Category::Methods, vec!["push".to_owned()]),
+                (Category::TraitImpls, vec!["Clone".to_owned()]),
+            ]
+        );
+        assert_eq!(
+            labels(&std, "1"),
+            [(Category::Items, vec!["Clone".to_owned(), "Vec".to_owned()])]
+        );
+    }
+
+    #[test]
+    fn trait_members() {
+        let core = index(CORE);
+
+        assert_eq!(
+            labels(&core, "1"),
+            [
+                (Category::RequiredMethods, vec!["clone".to_owned()]),
+                (Category::ProvidedMethods, vec!["clone_from".to_owned()]),
+                (
+                    Category::Implementors,
+                    vec!["&T".to_owned(), "u8".to_owned()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_across_crates() {
+        let std = index(STD);
+        let core = index(CORE);
+        let indices = [&std, &core];
+
+        let clone = resolve(&indices, &std, &Id("20".to_owned())).unwrap();
+        assert_eq!(clone.path, "std::vec::Clone");
+        assert_eq!(clone.index.name(), "core");
+        assert_eq!(clone.item().unwrap().kind(), Kind::Trait);
+
+        let push = resolve(&indices, &std, &Id("4".to_owned())).unwrap();
+        assert_eq!(push.path, "std::vec::Vec::push");
+        assert_eq!(push.entry.kind, Kind::Method);
+
+        assert!(resolve(&indices, &std, &Id("21".to_owned())).is_none());
+    }
+}
@@ -0,0 +1,467 @@
+use super::types::{Bound, Generics, Path, Type, signature};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, fmt};
+
+/// The parts of a rustdoc JSON file needed to search and render items
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Crate {
+    pub name: String,
+    pub version: Option<String>,
+    pub root: Id,
+    pub items: HashMap<Id, Item>,
+    pub paths: HashMap<Id, Summary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Id(pub String);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Item {
+    pub id: Id,
+    pub name: Option<String>,
+    pub docs: Option<String>,
+    /// Targets of the intra-doc links in `docs`, keyed by the link as written
+    pub links: HashMap<String, Id>,
+    pub inner: Inner,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Inner {
+    Module {
+        items: Vec<Id>,
+    },
+    Struct {
+        generics: Generics,
+        impls: Vec<Id>,
+    },
+    Enum {
+        generics: Generics,
+        variants: Vec<Id>,
+        impls: Vec<Id>,
+    },
+    Union {
+        generics: Generics,
+        impls: Vec<Id>,
+    },
+    Trait {
+        generics: Generics,
+        bounds: Vec<Bound>,
+        items: Vec<Id>,
+        is_unsafe: bool,
+        /// The impls of this trait
+        implementations: Vec<Id>,
+    },
+    Impl {
+        /// The implemented trait, `None` for inherent impls
+        trait_: Option<Path>,
+        for_: Type,
+        items: Vec<Id>,
+        is_negative: bool,
+        /// Auto trait impls like `Send` which rustdoc derives itself
+        is_synthetic: bool,
+        /// Impls for every type meeting some bounds, like `impl<T> From<T> for T`
+        is_blanket: bool,
+    },
+    Primitive {
+        impls: Vec<Id>,
+    },
+    Use {
+        name: String,
+        id: Option<Id>,
+        is_glob: bool,
+    },
+    Function(Box<Function>),
+    TypeAlias {
+        generics: Generics,
+        ty: Type,
+    },
+    Constant {
+        ty: Type,
+    },
+    Static {
+        ty: Type,
+        mutable: bool,
+    },
+    Macro(String),
+    Other(Kind),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Function {
+    pub generics: Generics,
+    pub inputs: Vec<(String, Type)>,
+    pub output: Option<Type>,
+    pub header: Header,
+    /// Trait methods without a body are required
+    pub has_body: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Header {
+    pub is_const: bool,
+    pub is_unsafe: bool,
+    pub is_async: bool,
+    /// The ABI if it isn't the Rust ABI
+    pub abi: Option<String>,
+}
+
+/// Path and kind of an item with its own page, including items of other crates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Summary {
+    pub path: Vec<String>,
+    pub kind: Kind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Kind {
+    Module,
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    Function,
+    Method,
+    TypeAlias,
+    Constant,
+    Static,
+    Macro,
+    Primitive,
+    Variant,
+    Field,
+    AssocType,
+    AssocConst,
+    Keyword,
+    Other,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RustdocError {
+    #[error("failed to read the rustdoc JSON: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("failed to parse the rustdoc JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("the rustdoc JSON has no root module")]
+    MissingRoot,
+
+    #[error("failed to write the index snapshot: {0}")]
+    Snapshot(#[from] postcard::Error),
+}
+
+#[derive(Deserialize)]
+struct RawCrate {
+    root: RawId,
+    crate_version: Option<String>,
+    index: HashMap<RawId, RawItem>,
+    paths: HashMap<RawId, RawSummary>,
+}
+
+#[derive(Deserialize)]
+struct RawItem {
+    id: RawId,
+    name: Option<String>,
+    docs: Option<String>,
+    #[serde(default)]
+    links: HashMap<String, RawId>,
+    inner: Value,
+}
+
+#[derive(Deserialize)]
+struct RawSummary {
+    path: Vec<String>,
+    kind: String,
+}
+
+/// An id as rustdoc writes it, see the `Deserialize` impl
+#[derive(PartialEq, Eq, Hash)]
+struct RawId(Id);
+
+impl Crate {
+    pub fn from_json(path: impl AsRef<std::path::Path>) -> Result<Self, RustdocError> {
+        Self::from_slice(&std::fs::read(path)?)
+    }
+
+    pub fn from_slice(json: &[u8]) -> Result<Self, RustdocError> {
+        let raw: RawCrate = serde_json::from_slice(json)?;
+
+        let items = raw
+            .index
+            .into_iter()
+            .map(|(id, item)| (id.0, Item::from(item)))
+            .collect::<HashMap<_, _>>();
+        let name = items
+            .get(&raw.root.0)
+            .and_then(|root| root.name.clone())
+            .ok_or(RustdocError::MissingRoot)?;
+        let paths = raw
+            .paths
+            .into_iter()
+            .map(|(id, summary)| {
+                let kind = Kind::from_tag(&summary.kind);
+                (
+                    id.0,
+                    Summary {
+                        path: summary.path,
+                        kind,
+                    },
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            name,
+            version: raw.crate_version,
+            root: raw.root.0,
+            items,
+            paths,
+        })
+    }
+}
+
+impl Item {
+    pub fn kind(&self) -> Kind {
+        match self.inner {
+            Inner::Module { .. } => Kind::Module,
+            Inner::Struct { .. } => Kind::Struct,
+            Inner::Enum { .. } => Kind::Enum,
+            Inner::Union { .. } => Kind::Union,
+            Inner::Trait { .. } => Kind::Trait,
+            Inner::Primitive { .. } => Kind::Primitive,
+            Inner::Function(_) => Kind::Function,
+            Inner::TypeAlias { .. } => Kind::TypeAlias,
+            Inner::Constant { .. } => Kind::Constant,
+            Inner::Static { .. } => Kind::Static,
+            Inner::Macro(_) => Kind::Macro,
+            Inner::Impl { .. } | Inner::Use { .. } => Kind::Other,
+            Inner::Other(kind) => kind,
+        }
+    }
+}
+
+impl From<RawItem> for Item {
+    fn from(raw: RawItem) -> Self {
+        Self {
+            id: raw.id.0,
+            name: raw.name,
+            docs: raw.docs,
+            links: raw
+                .links
+                .into_iter()
+                .map(|(link, id)| (link, id.0))
+                .collect(),
+            inner: Inner::from_value(raw.inner),
+        }
+    }
+}
+
+impl Inner {
+    /// Keeps the relations between items and what is needed to render signatures
+    fn from_value(value: Value) -> Self {
+        let (tag, body) = match value {
+            Value::Object(map) => match map.into_iter().next() {
+                Some(pair) => pair,
+                None => return Self::Other(Kind::Other),
+            },
+            Value::String(tag) => (tag, Value::Null),
+            _ => return Self::Other(Kind::Other),
+        };
+
+        match tag.as_str() {
+            "module" => Self::Module {
+                items: ids(&body["items"]),
+            },
+            "struct" => Self::Struct {
+                generics: Generics::from_value(&body["generics"]),
+                impls: ids(&body["impls"]),
+            },
+            "enum" => Self::Enum {
+                generics: Generics::from_value(&body["generics"]),
+                variants: ids(&body["variants"]),
+                impls: ids(&body["impls"]),
+            },
+            "union" => Self::Union {
+                generics: Generics::from_value(&body["generics"]),
+                impls: ids(&body["impls"]),
+            },
+            "trait" => Self::Trait {
+                generics: Generics::from_value(&body["generics"]),
+                bounds: body["bounds"]
+                    .as_array()
+                    .map(|bounds| bounds.iter().map(Bound::from_value).collect())
+                    .unwrap_or_default(),
+                items: ids(&body["items"]),
+                is_unsafe: body["is_unsafe"].as_bool().unwrap_or_default(),
+                implementations: ids(&body["implementations"]),
+            },
+            "impl" => {
+                let flag = |new: &str, old: &str| {
+                    body[new]
+                        .as_bool()
+                        .or_else(|| body[old].as_bool())
+                        .unwrap_or_default()
+                };
+                Self::Impl {
+                    trait_: (!body["trait"].is_null()).then(|| Path::from_value(&body["trait"])),
+                    for_: Type::from_value(&body["for"]),
+                    items: ids(&body["items"]),
+                    is_negative: flag("is_negative", "negative"),
+                    is_synthetic: flag("is_synthetic", "synthetic"),
+                    is_blanket: !body["blanket_impl"].is_null(),
+                }
+            }
+            "primitive" => Self::Primitive {
+                impls: ids(&body["impls"]),
+            },
+            "use" => Self::Use {
+                name: body["name"].as_str().unwrap_or_default().to_owned(),
+                id: Id::from_value(&body["id"]),
+                is_glob: body["is_glob"].as_bool().unwrap_or_default(),
+            },
+            "function" => Self::Function(Box::new(Function::from_value(&body))),
+            "type_alias" | "typedef" => Self::TypeAlias {
+                generics: Generics::from_value(&body["generics"]),
+                ty: Type::from_value(&body["type"]),
+            },
+            "constant" if !body["type"].is_null() => Self::Constant {
+                ty: Type::from_value(&body["type"]),
+            },
+            "static" => Self::Static {
+                ty: Type::from_value(&body["type"]),
+                mutable: body["is_mutable"]
+                    .as_bool()
+                    .or_else(|| body["mutable"].as_bool())
+                    .unwrap_or_default(),
+            },
+            "macro" => Self::Macro(body.as_str().unwrap_or_default().to_owned()),
+            other => Self::Other(Kind::from_tag(other)),
+        }
+    }
+}
+
+impl Function {
+    fn from_value(body: &Value) -> Self {
+        let sig = signature(body);
+        let header = &body["header"];
+        let flag = |new: &str, old: &str| {
+            header[new]
+                .as_bool()
+                .or_else(|| header[old].as_bool())
+                .unwrap_or_default()
+        };
+        let abi = match &header["abi"] {
+            Value::String(abi) if abi == "Rust" => None,
+            Value::String(abi) => Some(abi.clone()),
+            Value::Object(abi) => abi.keys().next().cloned(),
+            _ => None,
+        };
+
+        Self {
+            generics: Generics::from_value(&body["generics"]),
+            inputs: sig["inputs"]
+                .as_array()
+                .map(|inputs| {
+                    inputs
+                        .iter()
+                        .map(|input| {
+                            let name = input[0].as_str().unwrap_or("_").to_owned();
+                            (name, Type::from_value(&input[1]))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            output: (!sig["output"].is_null()).then(|| Type::from_value(&sig["output"])),
+            header: Header {
+                is_const: flag("is_const", "const"),
+                is_unsafe: flag("is_unsafe", "unsafe"),
+                is_async: flag("is_async", "async"),
+                abi,
+            },
+            has_body: body["has_body"].as_bool().unwrap_or(true),
+        }
+    }
+}
+
+impl Id {
+    pub(super) fn from_value(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(id) => Some(Self(id.clone())),
+            Value::Number(id) => Some(Self(id.to_string())),
+            _ => None,
+        }
+    }
+}
+
+// Ids are strings in older format versions and integers since format version 40
+impl<'de> Deserialize<'de> for RawId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            String(String),
+            Number(u64),
+        }
+
+        Ok(Self(match Raw::deserialize(deserializer)? {
+            Raw::String(id) => Id(id),
+            Raw::Number(id) => Id(id.to_string()),
+        }))
+    }
+}
+
+fn ids(value: &Value) -> Vec<Id> {
+    value
+        .as_array()
+        .map(|ids| ids.iter().filter_map(Id::from_value).collect())
+        .unwrap_or_default()
+}
+
+impl Kind {
+    fn from_tag(tag: &str) -> Self {
+        match tag {
+            "module" => Self::Module,
+            "struct" => Self::Struct,
+            "enum" => Self::Enum,
+            "union" => Self::Union,
+            "trait" | "trait_alias" => Self::Trait,
+            "function" => Self::Function,
+            "type_alias" | "typedef" => Self::TypeAlias,
+            "constant" => Self::Constant,
+            "static" => Self::Static,
+            "macro" | "proc_macro" | "proc_attribute" | "proc_derive" => Self::Macro,
+            "primitive" => Self::Primitive,
+            "variant" => Self::Variant,
+            "struct_field" => Self::Field,
+            "assoc_type" => Self::AssocType,
+            "assoc_const" => Self::AssocConst,
+            "keyword" => Self::Keyword,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Module => "module",
+            Self::Struct => "struct",
+            Self::Enum => "enum",
+            Self::Union => "union",
+            Self::Trait => "trait",
+            Self::Function => "function",
+            Self::Method => "method",
+            Self::TypeAlias => "type alias",
+            Self::Constant => "constant",
+            Self::Static => "static",
+            Self::Macro => "macro",
+            Self::Primitive => "primitive",
+            Self::Variant => "variant",
+            Self::Field => "field",
+            Self::AssocType => "associated type",
+            Self::AssocConst => "associated constant",
+            Self::Keyword => "keyword",
+            Self::Other => "item",
+        })
+    }
+}
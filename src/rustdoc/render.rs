@@ -0,0 +1,631 @@
+use super::{
+    DocIndex, Hit, Id, Inner, Item, Kind,
+    model::Function,
+    types::{Bound, Type},
+};
+use regex::Regex;
+use std::{collections::HashMap, sync::LazyLock};
+
+/// Where the documentation of the standard library is hosted
+pub const DOC_RUST_LANG: &str = "https://doc.rust-lang.org";
+
+/// Signatures longer than this put every argument on its own line
+const MAX_SIGNATURE_LINE: usize = 80;
+
+static LINK_RE: LazyLock<Regex> = LazyLock::new(|| unsafe {
+    Regex::new(r"\[([^\[\]]+)\](?:\(([^()\s]+)\)|\[([^\[\]]+)\])?").unwrap_unchecked()
+});
+
+static DEFINITION_RE: LazyLock<Regex> =
+    LazyLock::new(|| unsafe { Regex::new(r"^\s*\[([^\[\]]+)\]:\s*(\S+)\s*$").unwrap_unchecked() });
+
+/// Documentation of an item split at its top level headings
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Sections {
+    /// Everything before the first heading
+    pub intro: String,
+    /// Heading and content of every section
+    pub sections: Vec<(String, String)>,
+}
+
+impl DocIndex {
+    /// Links to the docs of `channel` for toolchain crates, stable is the default
+    pub fn with_channel(mut self, channel: &str) -> Self {
+        self.channel = (channel != "stable").then(|| channel.to_owned());
+        self
+    }
+
+    /// Root of the documentation of this crate
+    pub fn base_url(&self) -> String {
+        base_url(&self.name, self.version.as_deref(), self.channel.as_deref())
+    }
+
+    /// URL of the page documenting `id`, which may belong to another crate
+    pub fn link_url(&self, id: &Id) -> Option<String> {
+        if let Some(entry) = self.entry(id) {
+            let page = page(
+                &entry.path,
+                entry.kind,
+                entry.parent,
+                is_required(self.item(id)),
+            );
+            return Some(format!("{}/{page}", self.base_url()));
+        }
+
+        let summary = self.paths.get(id)?;
+        let name = summary.path.first()?;
+        let version = (*name == self.name)
+            .then_some(self.version.as_deref())
+            .flatten();
+        let page = page(&summary.path.join("::"), summary.kind, None, false);
+
+        Some(format!(
+            "{}/{page}",
+            base_url(name, version, self.channel.as_deref())
+        ))
+    }
+}
+
+impl Hit<'_> {
+    pub fn url(&self) -> String {
+        let page = page(
+            self.path,
+            self.entry.kind,
+            self.entry.parent,
+            is_required(self.item()),
+        );
+        format!("{}/{page}", self.index.base_url())
+    }
+
+    /// The declaration of the item as it would be written in code
+    pub fn signature(&self) -> Option<String> {
+        let name = self.path.rsplit("::").next().unwrap_or(self.path);
+        signature(self.item()?, name, self.entry.parent)
+    }
+
+    /// The docs with resolved intra-doc links and cleaned up code blocks
+    pub fn docs(&self) -> Option<String> {
+        let item = self.item()?;
+        let docs = item.docs.as_deref()?;
+        Some(clean_code(&resolve_links(docs, &item.links, self.index)))
+    }
+
+    /// The first sentence of the docs as plain text
+    pub fn summary(&self) -> Option<String> {
+        self.item()?.docs.as_deref().map(summary)
+    }
+}
+
+/// Root of the documentation of a crate, `channel` only applies to toolchain crates
+pub fn base_url(name: &str, version: Option<&str>, channel: Option<&str>) -> String {
+    if !matches!(name, "std" | "core" | "alloc" | "proc_macro" | "test") {
+        return format!("https://docs.rs/{name}/{}", version.unwrap_or("latest"));
+    }
+
+    match channel {
+        Some(channel) => format!("{DOC_RUST_LANG}/{channel}"),
+        None => DOC_RUST_LANG.to_owned(),
+    }
+}
+
+/// Trait methods without a default implementation are linked as `tymethod`
+fn is_required(item: Option<&Item>) -> bool {
+    matches!(item.map(|item| &item.inner), Some(Inner::Function(function)) if !function.has_body)
+}
+
+/// Path of the page documenting an item relative to the documentation root
+fn page(path: &str, kind: Kind, parent: Option<Kind>, required: bool) -> String {
+    let segments = path.split("::").collect::<Vec<_>>();
+    let Some((name, parents)) = segments.split_last() else {
+        return String::new();
+    };
+
+    if let Some(parent) = parent {
+        let anchor = match kind {
+            Kind::Method if required => "tymethod",
+            Kind::Variant => "variant",
+            Kind::Field => "structfield",
+            Kind::AssocType => "associatedtype",
+            Kind::AssocConst | Kind::Constant => "associatedconstant",
+            _ => "method",
+        };
+        return format!(
+            "{}#{anchor}.{name}",
+            page(&parents.join("::"), parent, None, false)
+        );
+    }
+
+    let dir = parents.join("/");
+    let prefix = match kind {
+        Kind::Module => return format!("{}/index.html", segments.join("/")),
+        Kind::Struct => "struct",
+        Kind::Enum => "enum",
+        Kind::Union => "union",
+        Kind::Trait => "trait",
+        Kind::Function | Kind::Method => "fn",
+        Kind::TypeAlias => "type",
+        Kind::Constant => "constant",
+        Kind::Static => "static",
+        Kind::Macro => "macro",
+        Kind::Primitive => "primitive",
+        Kind::Keyword => "keyword",
+        _ => return format!("{dir}/index.html"),
+    };
+
+    format!("{dir}/{prefix}.{name}.html")
+}
+
+/// Renders the declaration of an item, members of traits are written without `pub`
+pub fn signature(item: &Item, name: &str, parent: Option<Kind>) -> Option<String> {
+    let vis = if parent == Some(Kind::Trait) {
+        ""
+    } else {
+        "pub "
+    };
+
+    let signature = match &item.inner {
+        Inner::Function(function) => function_signature(vis, name, function),
+        Inner::Struct { generics, .. } => {
+            format!(
+                "{vis}struct {name}{}{}",
+                generics.params(),
+                generics.where_clause()
+            )
+        }
+        Inner::Enum { generics, .. } => {
+            format!(
+                "{vis}enum {name}{}{}",
+                generics.params(),
+                generics.where_clause()
+            )
+        }
+        Inner::Union { generics, .. } => {
+            format!(
+                "{vis}union {name}{}{}",
+                generics.params(),
+                generics.where_clause()
+            )
+        }
+        Inner::Trait {
+            generics,
+            bounds,
+            is_unsafe,
+            ..
+        } => {
+            let supertraits = if bounds.is_empty() {
+                String::new()
+            } else {
+                format!(": {}", join_bounds(bounds))
+            };
+            format!(
+                "{vis}{}trait {name}{}{supertraits}{}",
+                if *is_unsafe { "unsafe " } else { "" },
+                generics.params(),
+                generics.where_clause()
+            )
+        }
+        Inner::TypeAlias { generics, ty } => {
+            format!("{vis}type {name}{} = {ty};", generics.params())
+        }
+        Inner::Constant { ty } => format!("{vis}const {name}: {ty}"),
+        Inner::Static { ty, mutable } => {
+            format!(
+                "{vis}static {}{name}: {ty}",
+                if *mutable { "mut " } else { "" }
+            )
+        }
+        Inner::Macro(source) => source.clone(),
+        _ => return None,
+    };
+
+    Some(signature)
+}
+
+fn function_signature(vis: &str, name: &str, function: &Function) -> String {
+    let header = &function.header;
+    let mut head = vis.to_owned();
+    if header.is_const {
+        head += "const ";
+    }
+    if header.is_async {
+        head += "async ";
+    }
+    if header.is_unsafe {
+        head += "unsafe ";
+    }
+    if let Some(abi) = &header.abi {
+        head += &format!("extern \"{abi}\" ");
+    }
+    head += &format!("fn {name}{}", function.generics.params());
+
+    let inputs = function
+        .inputs
+        .iter()
+        .map(|(name, ty)| input(name, ty))
+        .collect::<Vec<_>>();
+    let output = function
+        .output
+        .as_ref()
+        .map(|ty| format!(" -> {ty}"))
+        .unwrap_or_default();
+
+    let single_line = format!("{head}({}){output}", inputs.join(", "));
+    let signature = if single_line.len() <= MAX_SIGNATURE_LINE {
+        single_line
+    } else {
+        let inputs = inputs
+            .iter()
+            .map(|input| format!("    {input},"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!("{head}(\n{inputs}\n){output}")
+    };
+
+    signature + &function.generics.where_clause()
+}
+
+/// A function argument, receivers are written in their short form
+fn input(name: &str, ty: &Type) -> String {
+    let is_self = |ty: &Type| matches!(ty, Type::Generic(name) if name == "Self");
+    if name != "self" {
+        return format!("{name}: {ty}");
+    }
+
+    match ty {
+        ty if is_self(ty) => "self".to_owned(),
+        Type::Ref {
+            lifetime,
+            mutable,
+            inner,
+        } if is_self(inner) => {
+            let lifetime = lifetime
+                .as_ref()
+                .map(|lifetime| format!("{lifetime} "))
+                .unwrap_or_default();
+            format!("&{lifetime}{}self", if *mutable { "mut " } else { "" })
+        }
+        ty => format!("self: {ty}"),
+    }
+}
+
+fn join_bounds(bounds: &[Bound]) -> String {
+    bounds
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" + ")
+}
+
+/// Turns intra-doc and reference links into inline links, since Discord only renders those
+pub fn resolve_links(docs: &str, links: &HashMap<String, Id>, index: &DocIndex) -> String {
+    let resolve = |target: &str| {
+        let bare = target.trim_matches('`');
+        let id = links
+            .get(target)
+            .or_else(|| links.get(bare))
+            .or_else(|| links.get(&format!("`{bare}`")))?;
+        index.link_url(id)
+    };
+
+    let mut definitions = HashMap::new();
+    let mut lines = Vec::new();
+    let mut in_fence = false;
+    for line in docs.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if !in_fence && let Some(cap) = DEFINITION_RE.captures(line) {
+            definitions.insert(cap[1].to_owned(), cap[2].to_owned());
+            continue;
+        }
+        lines.push((in_fence, line));
+    }
+
+    lines
+        .into_iter()
+        .map(|(in_fence, line)| {
+            if in_fence {
+                return line.to_owned();
+            }
+
+            LINK_RE
+                .replace_all(line, |cap: &regex::Captures| {
+                    let text = &cap[1];
+                    let (target, defined) = match (cap.get(2), cap.get(3)) {
+                        (Some(inline), _) => (inline.as_str(), true),
+                        (None, label) => {
+                            let label = label.map_or(text, |label| label.as_str());
+                            match definitions.get(label) {
+                                Some(target) => (target.as_str(), true),
+                                None => (label, false),
+                            }
+                        }
+                    };
+
+                    if target.contains("://") {
+                        format!("[{text}]({target})")
+                    } else if let Some(url) = resolve(target) {
+                        format!("[{text}]({url})")
+                    } else if defined {
+                        // relative links to rustdoc pages can't be followed from Discord
+                        text.to_owned()
+                    } else {
+                        cap[0].to_owned()
+                    }
+                })
+                .into_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The first sentence of `docs` on one line, with links reduced to their text
+pub fn summary(docs: &str) -> String {
+    let paragraph = docs.trim().split("\n\n").next().unwrap_or_default();
+    let line = paragraph
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let text = LINK_RE.replace_all(&line, "$1");
+    let end = text.find(". ").map_or(text.len(), |i| i + 1);
+    text[..end].to_owned()
+}
+
+/// Tags Rust code blocks for highlighting and removes the lines hidden by rustdoc
+pub fn clean_code(docs: &str) -> String {
+    let mut lines = Vec::new();
+    // whether the current code block is Rust
+    let mut fence: Option<bool> = None;
+
+    for line in docs.lines() {
+        let trimmed = line.trim_start();
+        if let Some(info) = trimmed.strip_prefix("```") {
+            if fence.is_some() {
+                fence = None;
+                lines.push(line.to_owned());
+            } else if is_rust_fence(info) {
+                fence = Some(true);
+                lines.push("```rust".to_owned());
+            } else {
+                fence = Some(false);
+                lines.push(line.to_owned());
+            }
+            continue;
+        }
+
+        if fence == Some(true) {
+            if trimmed == "#" || trimmed.starts_with("# ") {
+                continue;
+            }
+            if let Some(escaped) = trimmed.strip_prefix("##") {
+                lines.push(format!("{}#{escaped}", &line[..line.len() - trimmed.len()]));
+                continue;
+            }
+        }
+        lines.push(line.to_owned());
+    }
+
+    lines.join("\n")
+}
+
+/// Code blocks are Rust unless their first tag names another language
+fn is_rust_fence(info: &str) -> bool {
+    let language = info.split(',').next().unwrap_or_default().trim();
+    !matches!(
+        language,
+        "text" | "txt" | "plain" | "console" | "sh" | "bash" | "shell" | "toml" | "json" | "html"
+    )
+}
+
+/// Splits docs at their top level `# ` headings
+pub fn sections(docs: &str) -> Sections {
+    let mut sections = Sections::default();
+    let mut current: Option<(String, Vec<&str>)> = None;
+    let mut intro = Vec::new();
+    let mut in_fence = false;
+
+    for line in docs.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+
+        if !in_fence && let Some(heading) = line.strip_prefix("# ") {
+            if let Some((title, body)) = current.take() {
+                sections
+                    .sections
+                    .push((title, body.join("\n").trim().to_owned()));
+            }
+            current = Some((heading.trim().to_owned(), Vec::new()));
+            continue;
+        }
+
+        match &mut current {
+            Some((_, body)) => body.push(line),
+            None => intro.push(line),
+        }
+    }
+
+    if let Some((title, body)) = current {
+        sections
+            .sections
+            .push((title, body.join("\n").trim().to_owned()));
+    }
+    sections.intro = intro.join("\n").trim().to_owned();
+
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rustdoc::{Crate, search};
+
+    const FIXTURE: &str = r#"{
+        "root": 0,
+        "crate_version": "1.88.0",
+        "paths": {
+            "1": { "crate_id": 0, "path": ["std", "option", "Option"], "kind": "enum" },
+            "9": { "crate_id": 0, "path": ["std", "vec", "Vec"], "kind": "struct" },
+            "20": { "crate_id": 1, "path": ["core", "fmt", "Debug"], "kind": "trait" }
+        },
+        "index": {
+            "0": { "id": 0, "name": "std", "inner": { "module": { "items": [5, 6, 10] } } },
+            "5": { "id": 5, "name": "option", "inner": { "module": { "items": [1] } } },
+            "6": { "id": 6, "name": "vec", "inner": { "module": { "items": [9] } } },
+            "1": { "id": 1, "name": "Option", "docs": "Optional values, see [`Option::map`] and [`Debug`].\n\n[`Debug`]: crate::fmt::Debug",
+                   "links": { "`Option::map`": 3, "crate::fmt::Debug": 20 },
+                   "inner": { "enum": { "generics": { "params": [{ "name": "T", "kind": { "type": { "bounds": [], "default": null, "is_synthetic": false } } }], "where_predicates": [] }, "variants": [], "impls": [2] } } },
+            "2": { "id": 2, "name": null, "inner": { "impl": { "trait": null, "items": [3] } } },
+            "3": { "id": 3, "name": "map", "docs": null, "inner": { "function": {
+                "sig": {
+                    "inputs": [["self", { "generic": "Self" }], ["f", { "generic": "F" }]],
+                    "output": { "resolved_path": { "path": "Option", "id": 1, "args": { "angle_bracketed": { "args": [{ "type": { "generic": "U" } }], "constraints": [] } } } },
+                    "is_c_variadic": false
+                },
+                "generics": {
+                    "params": [
+                        { "name": "U", "kind": { "type": { "bounds": [], "default": null, "is_synthetic": false } } },
+                        { "name": "F", "kind": { "type": { "bounds": [], "default": null, "is_synthetic": false } } }
+                    ],
+                    "where_predicates": [{ "bound_predicate": {
+                        "type": { "generic": "F" },
+                        "bounds": [{ "trait_bound": { "trait": { "path": "FnOnce", "id": 30, "args": { "parenthesized": { "inputs": [{ "generic": "T" }], "output": { "generic": "U" } } } }, "generic_params": [], "modifier": "none" } }],
+                        "generic_params": []
+                    } }]
+                },
+                "header": { "is_const": true, "is_unsafe": false, "is_async": false, "abi": "Rust" },
+                "has_body": true
+            } } },
+            "9": { "id": 9, "name": "Vec", "inner": { "struct": { "generics": { "params": [], "where_predicates": [] }, "impls": [11] } } },
+            "10": { "id": 10, "name": "fmt", "inner": { "module": { "items": [] } } },
+            "11": { "id": 11, "name": null, "inner": { "impl": { "trait": null, "items": [12] } } },
+            "12": { "id": 12, "name": "push", "inner": { "function": {
+                "sig": { "inputs": [["self", { "borrowed_ref": { "lifetime": null, "is_mutable": true, "type": { "generic": "Self" } } }], ["value", { "generic": "T" }]], "output": null },
+                "generics": { "params": [], "where_predicates": [] },
+                "header": { "is_const": false, "is_unsafe": false, "is_async": false, "abi": "Rust" },
+                "has_body": true
+            } } }
+        }
+    }"#;
+
+    fn index() -> DocIndex {
+        DocIndex::new(Crate::from_slice(FIXTURE.as_bytes()).unwrap())
+    }
+
+    #[test]
+    fn signatures() {
+        let index = index();
+
+        let map = search(&[&index], "Option::map", 1)[0];
+        assert_eq!(
+            map.signature().unwrap(),
+            "pub const fn map<U, F>(self, f: F) -> Option<U>\nwhere\n    F: FnOnce(T) -> U,"
+        );
+
+        let push = search(&[&index], "Vec::push", 1)[0];
+        assert_eq!(
+            push.signature().unwrap(),
+            "pub fn push(&mut self, value: T)"
+        );
+
+        let option = search(&[&index], "Option", 1)[0];
+        assert_eq!(option.signature().unwrap(), "pub enum Option<T>");
+    }
+
+    #[test]
+    fn urls() {
+        let index = index();
+
+        let map = search(&[&index], "Option::map", 1)[0];
+        assert_eq!(
+            map.url(),
+            "https://doc.rust-lang.org/std/option/enum.Option.html#method.map"
+        );
+        assert_eq!(
+            page("std::iter", Kind::Module, None, false),
+            "std/iter/index.html"
+        );
+        assert_eq!(
+            page(
+                "core::iter::Iterator::next",
+                Kind::Method,
+                Some(Kind::Trait),
+                true
+            ),
+            "core/iter/trait.Iterator.html#tymethod.next"
+        );
+        assert_eq!(
+            base_url("serde", Some("1.0.219"), Some("nightly")),
+            "https://docs.rs/serde/1.0.219"
+        );
+
+        let nightly = self::index().with_channel("nightly");
+        let map = search(&[&nightly], "Option::map", 1)[0];
+        assert_eq!(
+            map.url(),
+            "https://doc.rust-lang.org/nightly/std/option/enum.Option.html#method.map"
+        );
+    }
+
+    #[test]
+    fn links() {
+        let index = index();
+        let option = search(&[&index], "Option", 1)[0];
+
+        assert_eq!(
+            option.docs().unwrap(),
+            "Optional values, see \
+             [`Option::map`](https://doc.rust-lang.org/std/option/enum.Option.html#method.map) \
+             and [`Debug`](https://doc.rust-lang.org/core/fmt/trait.Debug.html)."
+        );
+
+        let links = HashMap::new();
+        let docs = "See [the book](https://doc.rust-lang.org/book) and [`Foo`](struct.Foo.html).\n\
+                    ```\nlet x = [1, 2];\n```\n[0, 1] stays";
+        assert_eq!(
+            resolve_links(docs, &links, &index),
+            "See [the book](https://doc.rust-lang.org/book) and `Foo`.\n\
+             ```\nlet x = [1, 2];\n```\n[0, 1] stays"
+        );
+    }
+
+    #[test]
+    fn summaries() {
+        assert_eq!(
+            summary(
+                "Retains only the elements\nspecified by [the predicate][pred]. More.\n\nDetails."
+            ),
+            "Retains only the elements specified by the predicate."
+        );
+        assert_eq!(summary("\nA [`Vec`](Vec) of bytes"), "A `Vec` of bytes");
+        assert_eq!(summary(""), "");
+    }
+
+    #[test]
+    fn code_blocks() {
+        let docs = "Example:\n```\n# use std::fmt;\nlet x = 1;\n## not hidden\n#\n```\n```text\n# shown\n```";
+        assert_eq!(
+            clean_code(docs),
+            "Example:\n```rust\nlet x = 1;\n# not hidden\n```\n```text\n# shown\n```"
+        );
+    }
+
+    #[test]
+    fn split_sections() {
+        let docs = "Intro.\n\n# Examples\n\n```\n# hidden\nfoo();\n```\n\n# Panics\n\nWhen empty.";
+        let sections = sections(docs);
+
+        assert_eq!(sections.intro, "Intro.");
+        assert_eq!(
+            sections.sections,
+            [
+                (
+                    "Examples".to_owned(),
+                    "```\n# hidden\nfoo();\n```".to_owned()
+                ),
+                ("Panics".to_owned(), "When empty.".to_owned()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,654 @@
+use super::{Crate, Id, Inner, Item, Kind, model::Summary};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::OnceLock,
+};
+
+/// A crate together with the public paths of all of its items
+#[derive(Debug)]
+pub struct DocIndex {
+    pub(super) name: String,
+    pub(super) version: Option<String>,
+    /// Path and kind of every item with its own page, including items of other crates
+    pub(super) paths: HashMap<Id, Summary>,
+    /// Read from `source` when they are first needed if the index was restored from a snapshot
+    items: OnceLock<HashMap<Id, Item>>,
+    source: Option<PathBuf>,
+    pub(super) entries: Vec<Entry>,
+    /// The entry with the shortest path for each item
+    pub(super) by_id: HashMap<Id, usize>,
+    /// Release channel of a toolchain crate, used in its URLs
+    pub(super) channel: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    /// Public path under which the item can be used
+    pub path: String,
+    pub kind: Kind,
+    pub id: Id,
+    /// Path of the item in the crate which defines it, differs from `path` for re-exports
+    pub origin: String,
+    /// Kind of the type or trait this item is a member of
+    pub parent: Option<Kind>,
+    /// Whether the item is defined in this crate, known without reading the items
+    local: bool,
+    lower: String,
+}
+
+impl DocIndex {
+    pub fn new(krate: Crate) -> Self {
+        let mut entries = Vec::new();
+        let mut walking = HashSet::new();
+        walk(&krate, &krate.root, &krate.name, &mut entries, &mut walking);
+
+        let mut index = Self {
+            name: krate.name,
+            version: krate.version,
+            paths: krate.paths,
+            items: OnceLock::from(krate.items),
+            source: None,
+            entries,
+            by_id: HashMap::new(),
+            channel: None,
+        };
+        index.sort();
+        index
+    }
+
+    /// An index of the rustdoc JSON at `source` whose entries were already built, its items are
+    /// only read once they are needed
+    pub(super) fn restore(
+        name: String,
+        version: Option<String>,
+        paths: HashMap<Id, Summary>,
+        entries: Vec<Entry>,
+        source: PathBuf,
+    ) -> Self {
+        let mut index = Self {
+            name,
+            version,
+            paths,
+            items: OnceLock::new(),
+            source: Some(source),
+            entries,
+            by_id: HashMap::new(),
+            channel: None,
+        };
+        index.sort();
+        index
+    }
+
+    /// Adds the members of the items this crate re-exports from `other` under the re-exported
+    /// path, so `std::option::Option::is_some` is found although only core defines `Option`.
+    /// They keep the id of the re-export, as the items only exist in `other`.
+    pub fn link(&mut self, other: &DocIndex) {
+        let mut defined = other.entries.iter().collect::<Vec<_>>();
+        defined.sort_by(|a, b| a.origin.cmp(&b.origin));
+
+        let mut members = Vec::new();
+        for reexport in &self.entries {
+            if reexport.parent.is_some() || reexport.local {
+                continue;
+            }
+            let prefix = format!("{}::", reexport.origin);
+            let start = defined.partition_point(|entry| entry.origin < prefix);
+            for member in defined[start..]
+                .iter()
+                .take_while(|entry| entry.origin.starts_with(&prefix))
+            {
+                members.push(Entry::new(
+                    format!("{}::{}", reexport.path, &member.origin[prefix.len()..]),
+                    member.kind,
+                    reexport.id.clone(),
+                    member.origin.clone(),
+                    member.parent,
+                    false,
+                ));
+            }
+        }
+
+        self.entries.extend(members);
+        self.sort();
+    }
+
+    /// Sorts the entries by path without duplicates and points every id at its shortest entry
+    fn sort(&mut self) {
+        self.entries.sort_by(|a, b| a.path.cmp(&b.path));
+        self.entries
+            .dedup_by(|a, b| a.path == b.path && a.kind == b.kind);
+
+        self.by_id.clear();
+        for (i, entry) in self.entries.iter().enumerate() {
+            let shortest = self.by_id.entry(entry.id.clone()).or_insert(i);
+            if entry.path.len() < self.entries[*shortest].path.len() {
+                *shortest = i;
+            }
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub fn item(&self, id: &Id) -> Option<&Item> {
+        self.items().get(id)
+    }
+
+    /// Every item of the crate, parsing the rustdoc JSON on first use if the index came from a
+    /// snapshot. A JSON which can no longer be read leaves the index without items.
+    pub fn items(&self) -> &HashMap<Id, Item> {
+        self.items.get_or_init(|| {
+            let Some(source) = &self.source else {
+                return HashMap::new();
+            };
+            match Crate::from_json(source) {
+                Ok(krate) => krate.items,
+                Err(e) => {
+                    warn!("failed to read the items of {}: {e}", self.name);
+                    HashMap::new()
+                }
+            }
+        })
+    }
+
+    pub(super) fn entry(&self, id: &Id) -> Option<&Entry> {
+        self.by_id.get(id).map(|&i| &self.entries[i])
+    }
+
+    /// The shortest entry of an item this crate defines, looked up by its path in the defining
+    /// crate
+    pub(super) fn defined(&self, origin: &str) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.origin == origin && entry.local)
+            .min_by_key(|entry| entry.path.len())
+    }
+
+    /// Scores every entry, an exact match of the name including its case gets a bonus so
+    /// `Option` ranks the enum above the module
+    fn scored<'a, 'q>(
+        &'a self,
+        terms: &'q [String],
+        name: &'q str,
+    ) -> impl Iterator<Item = (i64, &'a Entry)> {
+        self.entries.iter().filter_map(move |entry| {
+            let score = score(&entry.lower, terms)?;
+            let exact = entry.path.rsplit("::").next() == Some(name);
+            Some((score + if exact { 50 } else { 0 }, entry))
+        })
+    }
+}
+
+/// A search result, which may stand for several re-exports of the same item
+#[derive(Debug, Clone, Copy)]
+pub struct Hit<'a> {
+    /// The preferred public path
+    pub path: &'a str,
+    /// The entry in the crate which defines the item if it was searched, otherwise the entry of
+    /// the re-export
+    pub entry: &'a Entry,
+    pub index: &'a DocIndex,
+}
+
+impl Hit<'_> {
+    pub fn item(&self) -> Option<&Item> {
+        self.index.item(&self.entry.id)
+    }
+
+    /// The crate which defines the item
+    pub fn origin_crate(&self) -> &str {
+        self.entry.origin.split("::").next().unwrap_or_default()
+    }
+}
+
+/// The best `limit` matches for `query` in all of `indices`. Re-exports of the same item are
+/// collapsed into one hit which prefers a `std::` path and the entry of the defining crate.
+pub fn search<'a>(indices: &[&'a DocIndex], query: &str, limit: usize) -> Vec<Hit<'a>> {
+    let terms = terms(query);
+    let Some(name) = query.rsplit(is_separator).find(|term| !term.is_empty()) else {
+        return Vec::new();
+    };
+
+    let hits = indices
+        .iter()
+        .flat_map(|&index| {
+            index
+                .scored(&terms, name)
+                .map(move |(score, entry)| (score, index, entry))
+        })
+        .collect();
+    collapse(hits, limit)
+}
+
+/// Sorts scored entries best first and collapses re-exports of the same item into one hit
+pub(super) fn collapse<'a>(
+    mut hits: Vec<(i64, &'a DocIndex, &'a Entry)>,
+    limit: usize,
+) -> Vec<Hit<'a>> {
+    hits.sort_by(|(a_score, _, a), (b_score, _, b)| {
+        b_score
+            .cmp(a_score)
+            .then(is_std(b).cmp(&is_std(a)))
+            .then(a.path.len().cmp(&b.path.len()))
+            .then(a.path.cmp(&b.path))
+    });
+
+    let mut collapsed: Vec<Hit<'a>> = Vec::new();
+    let mut groups = HashMap::new();
+    for (_, index, entry) in hits {
+        let Some(&i) = groups.get(entry.origin.as_str()) else {
+            groups.insert(entry.origin.as_str(), collapsed.len());
+            collapsed.push(Hit {
+                path: &entry.path,
+                entry,
+                index,
+            });
+            continue;
+        };
+
+        let hit = &mut collapsed[i];
+        if is_std(entry) && !hit.path.starts_with("std::") {
+            hit.path = &entry.path;
+        }
+        if !hit.entry.local && entry.local {
+            hit.entry = entry;
+            hit.index = index;
+        }
+    }
+
+    collapsed.truncate(limit);
+    collapsed
+}
+
+fn is_std(entry: &Entry) -> bool {
+    entry.path.starts_with("std::")
+}
+
+impl Entry {
+    fn new(
+        path: String,
+        kind: Kind,
+        id: Id,
+        origin: String,
+        parent: Option<Kind>,
+        local: bool,
+    ) -> Self {
+        let lower = path.to_lowercase();
+        Self {
+            path,
+            kind,
+            id,
+            origin,
+            parent,
+            local,
+            lower,
+        }
+    }
+}
+
+/// Collects the items of a module under `prefix`, `walking` holds the modules currently being
+/// walked so glob re-exports can't recurse forever
+fn walk(
+    krate: &Crate,
+    module: &Id,
+    prefix: &str,
+    entries: &mut Vec<Entry>,
+    walking: &mut HashSet<Id>,
+) {
+    let Some(Item {
+        inner: Inner::Module { items },
+        ..
+    }) = krate.items.get(module)
+    else {
+        return;
+    };
+    if !walking.insert(module.clone()) {
+        return;
+    }
+
+    for child in items {
+        let Some(item) = krate.items.get(child) else {
+            continue;
+        };
+
+        match &item.inner {
+            Inner::Use {
+                id: Some(target),
+                is_glob: true,
+                ..
+            } => walk(krate, target, prefix, entries, walking),
+            Inner::Use {
+                name,
+                id: Some(target),
+                ..
+            } => {
+                let path = format!("{prefix}::{name}");
+                match krate.items.get(target) {
+                    Some(target_item) => add(krate, target_item, path, entries, walking),
+                    // items of other crates are only known by their path and kind
+                    None => {
+                        if let Some(summary) = krate.paths.get(target) {
+                            let origin = summary.path.join("::");
+                            entries.push(Entry::new(
+                                path,
+                                summary.kind,
+                                target.clone(),
+                                origin,
+                                None,
+                                false,
+                            ));
+                        }
+                    }
+                }
+            }
+            Inner::Use { .. } | Inner::Impl { .. } => {}
+            _ => {
+                if let Some(name) = &item.name {
+                    add(krate, item, format!("{prefix}::{name}"), entries, walking);
+                }
+            }
+        }
+    }
+
+    walking.remove(module);
+}
+
+/// Adds a local item and its members
+fn add(
+    krate: &Crate,
+    item: &Item,
+    path: String,
+    entries: &mut Vec<Entry>,
+    walking: &mut HashSet<Id>,
+) {
+    let origin = krate
+        .paths
+        .get(&item.id)
+        .map(|summary| summary.path.join("::"))
+        .unwrap_or_else(|| path.clone());
+    let kind = item.kind();
+
+    match &item.inner {
+        Inner::Module { .. } => walk(krate, &item.id, &path, entries, walking),
+        Inner::Enum {
+            variants, impls, ..
+        } => {
+            members(krate, variants, &path, &origin, kind, entries);
+            methods(krate, impls, &path, &origin, kind, entries);
+        }
+        Inner::Struct { impls, .. } | Inner::Union { impls, .. } | Inner::Primitive { impls } => {
+            methods(krate, impls, &path, &origin, kind, entries);
+        }
+        Inner::Trait { items, .. } => members(krate, items, &path, &origin, kind, entries),
+        _ => {}
+    }
+
+    entries.push(Entry::new(path, kind, item.id.clone(), origin, None, true));
+}
+
+/// Adds the items of the inherent impls in `impls`
+fn methods(
+    krate: &Crate,
+    impls: &[Id],
+    path: &str,
+    origin: &str,
+    parent: Kind,
+    entries: &mut Vec<Entry>,
+) {
+    for id in impls {
+        if let Some(Item {
+            inner:
+                Inner::Impl {
+                    trait_: None,
+                    items,
+                    ..
+                },
+            ..
+        }) = krate.items.get(id)
+        {
+            members(krate, items, path, origin, parent, entries);
+        }
+    }
+}
+
+fn members(
+    krate: &Crate,
+    ids: &[Id],
+    path: &str,
+    origin: &str,
+    parent: Kind,
+    entries: &mut Vec<Entry>,
+) {
+    for id in ids {
+        let Some(item) = krate.items.get(id) else {
+            continue;
+        };
+        let Some(name) = &item.name else {
+            continue;
+        };
+        let kind = match item.kind() {
+            Kind::Function => Kind::Method,
+            kind => kind,
+        };
+
+        entries.push(Entry::new(
+            format!("{path}::{name}"),
+            kind,
+            id.clone(),
+            format!("{origin}::{name}"),
+            Some(parent),
+            true,
+        ));
+    }
+}
+
+/// Lowercased query terms, `std::iter`, `std iter` and `std.iter` are all equivalent
+fn terms(query: &str) -> Vec<String> {
+    query
+        .split(is_separator)
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || c == ':' || c == '.'
+}
+
+/// How well a lowercased path matches the query terms. The last term has to match the item's
+/// name, the other terms have to match earlier path segments in order.
+fn score(path: &str, terms: &[String]) -> Option<i64> {
+    let segments = path.split("::").collect::<Vec<_>>();
+    let (name, mut parents) = segments.split_last()?;
+    let (last, rest) = terms.split_last()?;
+
+    let mut score = if *name == last.as_str() {
+        1000
+    } else if name.starts_with(last.as_str()) {
+        600
+    } else if name.contains(last.as_str()) {
+        300
+    } else {
+        return None;
+    };
+
+    for term in rest {
+        let position = parents
+            .iter()
+            .position(|segment| segment.starts_with(term.as_str()))?;
+        score += if parents[position] == term { 100 } else { 50 };
+        parents = &parents[position + 1..];
+    }
+
+    Some(score - 10 * segments.len() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STD: &str = r#"{
+        "root": 0,
+        "crate_version": "1.0.0",
+        "format_version": 45,
+        "external_crates": { "1": { "name": "core", "html_root_url": "https://doc.rust-lang.org/nightly/" } },
+        "paths": {
+            "2": { "crate_id": 0, "path": ["std", "vec", "Vec"], "kind": "struct" },
+            "20": { "crate_id": 1, "path": ["core", "option", "Option"], "kind": "enum" },
+            "21": { "crate_id": 1, "path": ["core", "option"], "kind": "module" }
+        },
+        "index": {
+            "0": { "id": 0, "crate_id": 0, "name": "std", "docs": null, "inner": { "module": { "is_crate": true, "items": [1, 7, 8, 9, 14] } } },
+            "1": { "id": 1, "crate_id": 0, "name": "vec", "docs": null, "inner": { "module": { "is_crate": false, "items": [2] } } },
+            "2": { "id": 2, "crate_id": 0, "name": "Vec", "docs": "A vector.", "links": {}, "inner": { "struct": { "impls": [3, 10] } } },
+            "3": { "id": 3, "crate_id": 0, "name": null, "docs": null, "inner": { "impl": { "trait": null, "items": [4, 5] } } },
+            "4": { "id": 4, "crate_id": 0, "name": "push", "docs": "Appends an element.", "inner": { "function": {} } },
+            "5": { "id": 5, "crate_id": 0, "name": "new", "docs": null, "deprecation": { "since": "1.0" }, "inner": { "function": {} } },
+            "6": { "id": 6, "crate_id": 0, "name": null, "docs": null, "inner": { "use": { "source": "core::option::Option", "name": "Option", "id": 20, "is_glob": false } } },
+            "7": { "id": 7, "crate_id": 0, "name": "prelude", "docs": null, "inner": { "module": { "items": [6, 11, 12] } } },
+            "8": { "id": 8, "crate_id": 0, "name": "vec_macro", "docs": null, "inner": { "macro": "macro_rules! vec_macro {}" } },
+            "9": { "id": 9, "crate_id": 0, "name": null, "docs": null, "inner": { "use": { "source": "self::vec::Vec", "name": "Vec", "id": 2, "is_glob": false } } },
+            "10": { "id": 10, "crate_id": 0, "name": null, "docs": null, "inner": { "impl": { "trait": { "path": "Clone", "id": 30 }, "items": [13] } } },
+            "11": { "id": 11, "crate_id": 0, "name": null, "docs": null, "inner": { "use": { "source": "crate::vec", "name": "vec", "id": 1, "is_glob": true } } },
+            "12": { "id": 12, "crate_id": 0, "name": null, "docs": null, "inner": { "use": { "source": "crate", "name": "std", "id": 0, "is_glob": true } } },
+            "13": { "id": 13, "crate_id": 0, "name": "clone", "docs": null, "inner": { "function": {} } },
+            "14": { "id": 14, "crate_id": 0, "name": null, "docs": null, "inner": { "use": { "source": "core::option", "name": "option", "id": 21, "is_glob": false } } }
+        }
+    }"#;
+
+    const CORE: &str = r#"{
+        "root": 0,
+        "paths": {
+            "2": { "crate_id": 0, "path": ["core", "option", "Option"], "kind": "enum" }
+        },
+        "index": {
+            "0": { "id": 0, "crate_id": 0, "name": "core", "docs": null, "inner": { "module": { "is_crate": true, "items": [1] } } },
+            "1": { "id": 1, "crate_id": 0, "name": "option", "docs": null, "inner": { "module": { "is_crate": false, "items": [2] } } },
+            "2": { "id": 2, "crate_id": 0, "name": "Option", "docs": "Optional values.", "inner": { "enum": { "variants": [3], "impls": [4] } } },
+            "3": { "id": 3, "crate_id": 0, "name": "Some", "docs": null, "inner": { "variant": { "kind": "plain" } } },
+            "4": { "id": 4, "crate_id": 0, "name": null, "docs": null, "inner": { "impl": { "trait": null, "items": [5] } } },
+            "5": { "id": 5, "crate_id": 0, "name": "is_some", "docs": null, "inner": { "function": {} } }
+        }
+    }"#;
+
+    fn index(json: &str) -> DocIndex {
+        DocIndex::new(Crate::from_slice(json.as_bytes()).unwrap())
+    }
+
+    fn paths(hits: &[Hit]) -> Vec<String> {
+        hits.iter().map(|hit| hit.path.to_owned()).collect()
+    }
+
+    #[test]
+    fn parses() {
+        let krate = Crate::from_slice(STD.as_bytes()).unwrap();
+
+        assert_eq!(krate.name, "std");
+        assert_eq!(krate.items[&Id("8".to_owned())].kind(), Kind::Macro);
+        assert_eq!(krate.paths[&Id("20".to_owned())].kind, Kind::Enum);
+    }
+
+    #[test]
+    fn methods_and_reexports() {
+        let std = index(STD);
+        let push = search(&[&std], "Vec::push", 5);
+
+        assert_eq!(push[0].path, "std::Vec::push");
+        assert_eq!(push[0].entry.origin, "std::vec::Vec::push");
+        assert_eq!(push[0].entry.kind, Kind::Method);
+        // trait impl items are not inherent methods
+        assert!(search(&[&std], "clone", 5).is_empty());
+
+        let option = search(&[&std], "Option", 5);
+        assert_eq!(paths(&option), ["std::prelude::Option", "std::option"]);
+        assert_eq!(option[0].entry.origin, "core::option::Option");
+        assert_eq!(option[0].entry.kind, Kind::Enum);
+        assert!(option[0].item().is_none());
+    }
+
+    #[test]
+    fn collapses_reexports() {
+        let std = index(STD);
+        let vec = search(&[&std], "Vec", 10);
+
+        // the shortest path is kept, the glob re-export in the prelude is collapsed
+        assert_eq!(vec[0].path, "std::Vec");
+        assert_eq!(vec[0].entry.origin, "std::vec::Vec");
+        assert_eq!(
+            vec.iter()
+                .filter(|hit| hit.entry.origin == "std::vec::Vec")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn merges_crates() {
+        let mut std = index(STD);
+        let core = index(CORE);
+        std.link(&core);
+        let option = search(&[&core, &std], "Option", 10);
+
+        // the std path is shown, the docs come from core which defines the item
+        assert_eq!(option[0].path, "std::option::Option");
+        assert_eq!(option[0].origin_crate(), "core");
+        assert_eq!(option[0].index.name(), "core");
+        assert_eq!(
+            option[0].item().and_then(|item| item.docs.as_deref()),
+            Some("Optional values.")
+        );
+        assert_eq!(
+            option
+                .iter()
+                .filter(|hit| hit.entry.origin == "core::option::Option")
+                .count(),
+            1
+        );
+
+        // members are found under the path std re-exports their parent by
+        let is_some = search(&[&std, &core], "is_some", 10);
+        assert_eq!(paths(&is_some), ["std::option::Option::is_some"]);
+        assert_eq!(is_some[0].index.name(), "core");
+        assert_eq!(is_some[0].entry.kind, Kind::Method);
+    }
+
+    #[test]
+    fn links_restored_without_items() {
+        let built = index(STD);
+        let mut std = DocIndex::restore(
+            built.name.clone(),
+            built.version.clone(),
+            built.paths.clone(),
+            built.entries.clone(),
+            PathBuf::from("missing/std.json"),
+        );
+        std.link(&index(CORE));
+
+        assert_eq!(
+            paths(&search(&[&std], "is_some", 10)),
+            ["std::option::Option::is_some"]
+        );
+        assert!(std.items.get().is_none());
+    }
+
+    #[test]
+    fn scoring() {
+        let terms = terms("std iter");
+        assert_eq!(terms, ["std", "iter"]);
+
+        let module = score("std::iter", &terms).unwrap();
+        let iterator = score("std::iter::iterator", &terms).unwrap();
+        assert!(module > iterator);
+        assert_eq!(score("core::iter", &terms), None);
+        assert_eq!(score("std::vec::vec", &terms), None);
+        assert!(super::terms(" ::. ").is_empty());
+    }
+}
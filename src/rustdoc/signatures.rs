@@ -0,0 +1,471 @@
+use super::{
+    DocIndex, Inner, Kind,
+    search::{Hit, collapse},
+    types::{GenericArg, GenericArgs, Path, Type},
+};
+use std::collections::HashMap;
+
+/// Arguments are also tried in every other order up to this many of them
+const MAX_REORDERED: usize = 4;
+
+/// Cost of a match which needs the arguments in another order
+const REORDERED: u32 = 1;
+
+const PRIMITIVES: &[&str] = &[
+    "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64",
+    "i128", "isize", "f32", "f64",
+];
+
+/// A simplified function type to search for, like `fn(&str) -> usize` or `(Vec<T>, T) -> bool`.
+/// Single uppercase letters are type variables.
+#[derive(Debug, PartialEq)]
+pub struct Signature {
+    inputs: Vec<Type>,
+    output: Option<Type>,
+}
+
+impl Signature {
+    /// `None` if the query is not a function type. The `fn` and the parentheses around a single
+    /// argument may be left out.
+    pub fn parse(query: &str) -> Option<Self> {
+        let query = query.trim();
+        let source = if query.starts_with("fn(") || query.starts_with("fn (") {
+            query.to_owned()
+        } else if query.starts_with('(') {
+            format!("fn{query}")
+        } else {
+            match query.split_once("->") {
+                Some((inputs, output)) => format!("fn({inputs}) -> {output}"),
+                None => format!("fn({query})"),
+            }
+        };
+
+        let function = syn::parse_str::<syn::TypeBareFn>(&source).ok()?;
+        Some(Self {
+            inputs: function
+                .inputs
+                .iter()
+                .map(|input| convert(&input.ty))
+                .collect::<Option<_>>()?,
+            output: match &function.output {
+                syn::ReturnType::Default => None,
+                syn::ReturnType::Type(_, ty) => Some(convert(ty)?),
+            },
+        })
+    }
+}
+
+/// The functions and methods in `indices` whose signature fits `signature`, closest fits first.
+/// Re-exports are collapsed like in [`search`](super::search).
+pub fn search_signature<'a>(
+    indices: &[&'a DocIndex],
+    signature: &Signature,
+    limit: usize,
+) -> Vec<Hit<'a>> {
+    let hits = indices
+        .iter()
+        .flat_map(|&index| {
+            index.entries.iter().filter_map(move |entry| {
+                let Inner::Function(function) = &index.item(&entry.id)?.inner else {
+                    return None;
+                };
+                // `Self` of a trait method can be any type
+                let parent = match entry.parent {
+                    Some(Kind::Trait) | None => None,
+                    Some(_) => entry.path.rsplit("::").nth(1),
+                };
+                let inputs = function.inputs.iter().map(|(_, ty)| ty).collect::<Vec<_>>();
+                let cost = fit(signature, &inputs, function.output.as_ref(), parent)?;
+                Some((-i64::from(cost), index, entry))
+            })
+        })
+        .collect();
+    collapse(hits, limit)
+}
+
+/// The cost of the cheapest way to unify the signature with a function, lower is closer
+fn fit(
+    signature: &Signature,
+    inputs: &[&Type],
+    output: Option<&Type>,
+    parent: Option<&str>,
+) -> Option<u32> {
+    if signature.inputs.len() != inputs.len() {
+        return None;
+    }
+
+    let unit = Type::Tuple(Vec::new());
+    orders(inputs.len())
+        .into_iter()
+        .filter_map(|order| {
+            let mut unifier = Unifier::new(parent);
+            let reordered = order.iter().enumerate().any(|(i, &j)| i != j);
+            let mut cost = if reordered { REORDERED } else { 0 };
+            for (query, &i) in signature.inputs.iter().zip(&order) {
+                cost += unifier.unify(query, inputs[i])?;
+            }
+            cost += unifier.unify(
+                signature.output.as_ref().unwrap_or(&unit),
+                output.unwrap_or(&unit),
+            )?;
+            Some(cost)
+        })
+        .min()
+}
+
+/// Every order of `n` arguments, in order first
+fn orders(n: usize) -> Vec<Vec<usize>> {
+    if n > MAX_REORDERED {
+        return vec![(0..n).collect()];
+    }
+
+    let mut orders = vec![Vec::new()];
+    for _ in 0..n {
+        let mut longer = Vec::new();
+        for order in &orders {
+            for i in (0..n).filter(|i| !order.contains(i)) {
+                let mut next = order.clone();
+                next.push(i);
+                longer.push(next);
+            }
+        }
+        orders = longer;
+    }
+    orders
+}
+
+/// Binds type variables on both sides while matching types, each variable has to stand for the
+/// same type everywhere
+struct Unifier<'a> {
+    /// Name of the type `Self` stands for, in traits it is a variable like any other
+    parent: Option<&'a str>,
+    query: HashMap<String, String>,
+    candidate: HashMap<String, String>,
+}
+
+impl<'a> Unifier<'a> {
+    fn new(parent: Option<&'a str>) -> Self {
+        Self {
+            parent,
+            query: HashMap::new(),
+            candidate: HashMap::new(),
+        }
+    }
+
+    /// The cost of matching `query` against `candidate`, `None` if they can't match
+    fn unify(&mut self, query: &Type, candidate: &Type) -> Option<u32> {
+        match (query, candidate) {
+            (Type::Infer, _) => Some(0),
+            (_, Type::Generic(name)) if name == "Self" && self.parent.is_some() => {
+                let parent = self.parent?;
+                match query {
+                    Type::Generic(var) => {
+                        bind(&mut self.query, var, parent)?;
+                        Some(1)
+                    }
+                    _ => (name_of(query) == Some(parent)).then_some(0),
+                }
+            }
+            (Type::Generic(var), Type::Generic(name)) => {
+                bind(&mut self.query, var, &candidate.to_string())?;
+                bind(&mut self.candidate, name, &query.to_string())?;
+                Some(0)
+            }
+            (Type::Generic(var), _) => {
+                bind(&mut self.query, var, &candidate.to_string())?;
+                Some(1)
+            }
+            (_, Type::Generic(name)) => {
+                bind(&mut self.candidate, name, &query.to_string())?;
+                Some(1)
+            }
+            // nothing is known about these, so they fit anything but worse than a variable
+            (_, Type::ImplTrait(_) | Type::Qualified { .. }) => Some(2),
+            (
+                Type::Ref {
+                    mutable: query_mutable,
+                    inner: query,
+                    ..
+                },
+                Type::Ref {
+                    mutable: candidate_mutable,
+                    inner: candidate,
+                    ..
+                },
+            ) => {
+                let mutability = if query_mutable == candidate_mutable {
+                    0
+                } else {
+                    2
+                };
+                Some(self.unify(query, candidate)? + mutability)
+            }
+            (Type::Ref { inner, .. }, _) => Some(self.unify(inner, candidate)? + 3),
+            (_, Type::Ref { inner, .. }) => Some(self.unify(query, inner)? + 3),
+            (Type::Primitive(query), Type::Primitive(candidate)) => {
+                (query == candidate).then_some(0)
+            }
+            (Type::Path(query), Type::Path(candidate)) => self.unify_paths(query, candidate),
+            (Type::Slice(query), Type::Slice(candidate))
+            | (
+                Type::Array { inner: query, .. },
+                Type::Array {
+                    inner: candidate, ..
+                },
+            ) => self.unify(query, candidate),
+            (
+                Type::Slice(query),
+                Type::Array {
+                    inner: candidate, ..
+                },
+            )
+            | (Type::Array { inner: query, .. }, Type::Slice(candidate)) => {
+                Some(self.unify(query, candidate)? + 1)
+            }
+            (Type::Tuple(query), Type::Tuple(candidate)) if query.len() == candidate.len() => query
+                .iter()
+                .zip(candidate)
+                .map(|(query, candidate)| self.unify(query, candidate))
+                .sum(),
+            _ => (query.to_string() == candidate.to_string()).then_some(0),
+        }
+    }
+
+    /// Paths match by their last segment, arguments left out in the query match anything
+    fn unify_paths(&mut self, query: &Path, candidate: &Path) -> Option<u32> {
+        if query.short_name() != candidate.short_name() {
+            return None;
+        }
+
+        let query = type_args(&query.args);
+        let candidate = type_args(&candidate.args);
+        if query.len() > candidate.len() {
+            return None;
+        }
+        query
+            .into_iter()
+            .zip(candidate)
+            .map(|(query, candidate)| self.unify(query, candidate))
+            .sum()
+    }
+}
+
+fn bind(bindings: &mut HashMap<String, String>, name: &str, ty: &str) -> Option<()> {
+    match bindings.get(name) {
+        Some(bound) => (bound == ty).then_some(()),
+        None => {
+            bindings.insert(name.to_owned(), ty.to_owned());
+            Some(())
+        }
+    }
+}
+
+/// The name of the type in the docs, to compare it with the type `Self` stands for
+fn name_of(ty: &Type) -> Option<&str> {
+    match ty {
+        Type::Path(path) => Some(path.short_name()),
+        Type::Primitive(name) => Some(name),
+        Type::Slice(_) => Some("slice"),
+        Type::Array { .. } => Some("array"),
+        Type::Tuple(_) => Some("tuple"),
+        _ => None,
+    }
+}
+
+fn type_args(args: &GenericArgs) -> Vec<&Type> {
+    match args {
+        GenericArgs::Angle { args, .. } => args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArg::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Converts a type of the query into the model of the rustdoc JSON
+fn convert(ty: &syn::Type) -> Option<Type> {
+    Some(match ty {
+        syn::Type::Reference(reference) => Type::Ref {
+            lifetime: None,
+            mutable: reference.mutability.is_some(),
+            inner: Box::new(convert(&reference.elem)?),
+        },
+        syn::Type::Ptr(pointer) => Type::Pointer {
+            mutable: pointer.mutability.is_some(),
+            inner: Box::new(convert(&pointer.elem)?),
+        },
+        syn::Type::Slice(slice) => Type::Slice(Box::new(convert(&slice.elem)?)),
+        syn::Type::Array(array) => Type::Array {
+            inner: Box::new(convert(&array.elem)?),
+            len: "_".to_owned(),
+        },
+        syn::Type::Tuple(tuple) => {
+            Type::Tuple(tuple.elems.iter().map(convert).collect::<Option<_>>()?)
+        }
+        syn::Type::Paren(paren) => convert(&paren.elem)?,
+        syn::Type::Infer(_) | syn::Type::ImplTrait(_) => Type::Infer,
+        syn::Type::Path(path) if path.qself.is_none() => {
+            let segment = path.path.segments.last()?;
+            let name = segment.ident.to_string();
+            if path.path.segments.len() == 1 && segment.arguments.is_none() {
+                if PRIMITIVES.contains(&name.as_str()) {
+                    return Some(Type::Primitive(name));
+                }
+                if is_variable(&name) {
+                    return Some(Type::Generic(name));
+                }
+            }
+
+            let args = match &segment.arguments {
+                syn::PathArguments::AngleBracketed(args) => args
+                    .args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        syn::GenericArgument::Type(ty) => convert(ty).map(GenericArg::Type),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            Type::Path(Path {
+                name,
+                id: None,
+                args: if args.is_empty() {
+                    GenericArgs::None
+                } else {
+                    GenericArgs::Angle {
+                        args,
+                        constraints: Vec::new(),
+                    }
+                },
+            })
+        }
+        _ => return None,
+    })
+}
+
+fn is_variable(name: &str) -> bool {
+    name.len() == 1 && name.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rustdoc::Crate;
+
+    const STD: &str = r#"{
+        "root": 0,
+        "paths": {
+            "2": { "crate_id": 0, "path": ["std", "vec", "Vec"], "kind": "struct" }
+        },
+        "index": {
+            "0": { "id": 0, "crate_id": 0, "name": "std", "docs": null, "inner": { "module": { "is_crate": true, "items": [1, 10, 20, 30] } } },
+            "1": { "id": 1, "crate_id": 0, "name": "vec", "docs": null, "inner": { "module": { "is_crate": false, "items": [2] } } },
+            "2": { "id": 2, "crate_id": 0, "name": "Vec", "docs": null, "inner": { "struct": { "impls": [3] } } },
+            "3": { "id": 3, "crate_id": 0, "name": null, "docs": null, "inner": { "impl": { "trait": null, "for": { "resolved_path": { "path": "Vec", "id": 2, "args": { "angle_bracketed": { "args": [{ "type": { "generic": "T" } }], "constraints": [] } } } }, "items": [4, 5, 6] } } },
+            "4": { "id": 4, "crate_id": 0, "name": "push", "docs": null, "inner": { "function": { "sig": {
+                "inputs": [["self", { "borrowed_ref": { "lifetime": null, "is_mutable": true, "type": { "generic": "Self" } } }], ["value", { "generic": "T" }]],
+                "output": null
+            } } } },
+            "5": { "id": 5, "crate_id": 0, "name": "len", "docs": null, "inner": { "function": { "sig": {
+                "inputs": [["self", { "borrowed_ref": { "lifetime": null, "is_mutable": false, "type": { "generic": "Self" } } }]],
+                "output": { "primitive": "usize" }
+            } } } },
+            "6": { "id": 6, "crate_id": 0, "name": "insert", "docs": null, "inner": { "function": { "sig": {
+                "inputs": [["self", { "borrowed_ref": { "lifetime": null, "is_mutable": true, "type": { "generic": "Self" } } }], ["index", { "primitive": "usize" }], ["element", { "generic": "T" }]],
+                "output": null
+            } } } },
+            "10": { "id": 10, "crate_id": 0, "name": "cmp", "docs": null, "inner": { "module": { "is_crate": false, "items": [11] } } },
+            "11": { "id": 11, "crate_id": 0, "name": "max", "docs": null, "inner": { "function": { "sig": {
+                "inputs": [["v1", { "generic": "T" }], ["v2", { "generic": "T" }]],
+                "output": { "generic": "T" }
+            } } } },
+            "20": { "id": 20, "crate_id": 0, "name": "str", "docs": null, "inner": { "module": { "is_crate": false, "items": [21] } } },
+            "21": { "id": 21, "crate_id": 0, "name": "from_utf8", "docs": null, "inner": { "function": { "sig": {
+                "inputs": [["v", { "borrowed_ref": { "lifetime": null, "is_mutable": false, "type": { "slice": { "primitive": "u8" } } } }]],
+                "output": { "resolved_path": { "path": "Result", "id": 40, "args": { "angle_bracketed": { "args": [
+                    { "type": { "borrowed_ref": { "lifetime": null, "is_mutable": false, "type": { "primitive": "str" } } } },
+                    { "type": { "resolved_path": { "path": "Utf8Error", "id": 41 } } }
+                ], "constraints": [] } } } }
+            } } } },
+            "30": { "id": 30, "crate_id": 0, "name": "mem", "docs": null, "inner": { "module": { "is_crate": false, "items": [31] } } },
+            "31": { "id": 31, "crate_id": 0, "name": "swap", "docs": null, "inner": { "function": { "sig": {
+                "inputs": [
+                    ["x", { "borrowed_ref": { "lifetime": null, "is_mutable": true, "type": { "generic": "T" } } }],
+                    ["y", { "borrowed_ref": { "lifetime": null, "is_mutable": true, "type": { "generic": "T" } } }]
+                ],
+                "output": null
+            } } } }
+        }
+    }"#;
+
+    fn paths(index: &DocIndex, query: &str) -> Vec<String> {
+        let signature = Signature::parse(query).unwrap();
+        search_signature(&[index], &signature, 10)
+            .into_iter()
+            .map(|hit| hit.path.to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn parses_queries() {
+        let str_ref = Type::Ref {
+            lifetime: None,
+            mutable: false,
+            inner: Box::new(Type::Primitive("str".to_owned())),
+        };
+        let usize = Some(Type::Primitive("usize".to_owned()));
+
+        let signature = Signature::parse("fn(&str) -> usize").unwrap();
+        assert_eq!(signature.inputs, [str_ref]);
+        assert_eq!(signature.output, usize);
+        assert_eq!(Signature::parse("&str -> usize"), Some(signature));
+
+        let signature = Signature::parse("(Vec<T>, T) -> bool").unwrap();
+        assert_eq!(signature.inputs.len(), 2);
+        assert_eq!(signature.inputs[0].to_string(), "Vec<T>");
+        assert_eq!(signature.inputs[1], Type::Generic("T".to_owned()));
+        assert_eq!(signature.output, Some(Type::Primitive("bool".to_owned())));
+
+        assert_eq!(Signature::parse("&mut Vec<T>").unwrap().output, None);
+        assert_eq!(Signature::parse("fn("), None);
+    }
+
+    #[test]
+    fn unifies_methods() {
+        let index = DocIndex::new(Crate::from_slice(STD.as_bytes()).unwrap());
+
+        assert_eq!(paths(&index, "(&Vec<T>) -> usize")[0], "std::vec::Vec::len");
+        assert_eq!(paths(&index, "(&mut Vec<T>, T)")[0], "std::vec::Vec::push");
+        assert_eq!(
+            paths(&index, "(&mut Vec<u8>, usize, u8)")[0],
+            "std::vec::Vec::insert"
+        );
+        // arguments in another order still fit
+        assert_eq!(paths(&index, "(T, &mut Vec<T>)")[0], "std::vec::Vec::push");
+        // `Self` is the type of the impl
+        assert!(paths(&index, "(&String) -> usize").is_empty());
+    }
+
+    #[test]
+    fn unifies_generics_and_references() {
+        let index = DocIndex::new(Crate::from_slice(STD.as_bytes()).unwrap());
+
+        assert_eq!(paths(&index, "(T, T) -> T"), ["std::cmp::max"]);
+        assert_eq!(paths(&index, "(u8, u8) -> u8")[0], "std::cmp::max");
+        // a variable stands for one type
+        assert!(!paths(&index, "(u8, u16) -> u8").contains(&"std::cmp::max".to_owned()));
+
+        assert_eq!(
+            paths(&index, "&[u8] -> Result<&str, _>"),
+            ["std::str::from_utf8"]
+        );
+        assert_eq!(paths(&index, "(&mut T, &mut T)")[0], "std::mem::swap");
+
+        // a shared reference still fits a mutable one
+        assert_eq!(paths(&index, "(&mut u8, &u8)"), ["std::mem::swap"]);
+    }
+}
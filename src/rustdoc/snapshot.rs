@@ -0,0 +1,176 @@
+use super::{Crate, DocIndex, Id, RustdocError, model::Summary, search::Entry};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    time::SystemTime,
+};
+
+/// Bumped whenever the serialized index changes, older snapshots are rebuilt
+const SNAPSHOT_FORMAT: u32 = 3;
+
+/// The search entries of a built index together with the rustdoc JSON they were built from. The
+/// items are left out, they are read from the JSON when they are first needed.
+#[derive(Serialize, Deserialize)]
+struct Snapshot<'a> {
+    format: u32,
+    source: Source,
+    name: Cow<'a, str>,
+    version: Option<Cow<'a, str>>,
+    paths: Cow<'a, HashMap<Id, Summary>>,
+    entries: Cow<'a, [Entry]>,
+}
+
+/// Identifies a version of the rustdoc JSON without reading all of it
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Source {
+    len: u64,
+    modified: SystemTime,
+}
+
+impl DocIndex {
+    /// The index of the rustdoc JSON at `json`, read from the snapshot at `snapshot` if that was
+    /// built from the same file. Otherwise the JSON is parsed and a new snapshot is written, a
+    /// failure to write it only costs time on the next start.
+    pub fn load(json: &Path, snapshot: &Path) -> Result<Self, RustdocError> {
+        let source = Source::of(json)?;
+        if let Some(index) = read(snapshot, &source, json) {
+            return Ok(index);
+        }
+
+        let index = Self::new(Crate::from_json(json)?);
+        if let Err(e) = write(snapshot, source, &index) {
+            warn!(
+                "failed to write the index snapshot {}: {e}",
+                snapshot.display()
+            );
+        }
+        Ok(index)
+    }
+}
+
+impl Source {
+    fn of(json: &Path) -> Result<Self, RustdocError> {
+        let metadata = fs::metadata(json)?;
+        Ok(Self {
+            len: metadata.len(),
+            modified: metadata.modified()?,
+        })
+    }
+}
+
+/// The index in the snapshot if it is current, anything unreadable counts as outdated
+fn read(snapshot: &Path, source: &Source, json: &Path) -> Option<DocIndex> {
+    let bytes = fs::read(snapshot).ok()?;
+    let snapshot: Snapshot = postcard::from_bytes(&bytes).ok()?;
+    if snapshot.format != SNAPSHOT_FORMAT || snapshot.source != *source {
+        return None;
+    }
+
+    Some(DocIndex::restore(
+        snapshot.name.into_owned(),
+        snapshot.version.map(Cow::into_owned),
+        snapshot.paths.into_owned(),
+        snapshot.entries.into_owned(),
+        json.to_path_buf(),
+    ))
+}
+
+fn write(snapshot: &Path, source: Source, index: &DocIndex) -> Result<(), RustdocError> {
+    if let Some(dir) = snapshot.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    // written next to it first so a crash never leaves half a snapshot behind
+    let partial = snapshot.with_extension("partial");
+    let content = Snapshot {
+        format: SNAPSHOT_FORMAT,
+        source,
+        name: Cow::Borrowed(&index.name),
+        version: index.version.as_deref().map(Cow::Borrowed),
+        paths: Cow::Borrowed(&index.paths),
+        entries: Cow::Borrowed(&index.entries),
+    };
+    let mut writer = postcard::to_io(&content, BufWriter::new(File::create(&partial)?))?;
+    writer.flush()?;
+    fs::rename(partial, snapshot)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rustdoc::search;
+    use std::time::Duration;
+
+    fn json(name: &str, item: &str) -> String {
+        format!(
+            r#"{{ "root": 0, "paths": {{}}, "index": {{
+                "0": {{ "id": 0, "name": "{name}", "docs": null, "inner": {{ "module": {{ "items": [1] }} }} }},
+                "1": {{ "id": 1, "name": "{item}", "docs": null, "inner": {{ "struct": {{ "impls": [] }} }} }}
+            }} }}"#
+        )
+    }
+
+    #[test]
+    fn reuses_current_snapshots() {
+        let dir = std::env::temp_dir().join(format!("corro-snapshot-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("std.json");
+        let snapshot = dir.join("index").join("std.index");
+
+        fs::write(&source, json("std", "Vec")).unwrap();
+        let index = DocIndex::load(&source, &snapshot).unwrap();
+        assert_eq!(search(&[&index], "Vec", 1)[0].path, "std::Vec");
+        assert!(snapshot.exists());
+
+        // a snapshot for the same source is used as is, even if it differs from the JSON
+        let other = DocIndex::new(Crate::from_slice(json("std", "Box").as_bytes()).unwrap());
+        write(&snapshot, Source::of(&source).unwrap(), &other).unwrap();
+        let index = DocIndex::load(&source, &snapshot).unwrap();
+        assert_eq!(search(&[&index], "Box", 1)[0].path, "std::Box");
+
+        // a changed source is parsed again
+        fs::write(&source, json("std", "HashMap")).unwrap();
+        let index = DocIndex::load(&source, &snapshot).unwrap();
+        assert_eq!(search(&[&index], "HashMap", 1)[0].path, "std::HashMap");
+        assert!(search(&[&index], "Box", 1).is_empty());
+
+        // also when only a fraction of a second passed and the length stayed the same
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        DocIndex::load(&source, &snapshot).unwrap();
+        fs::write(&source, json("std", "HashSet")).unwrap();
+        File::options()
+            .write(true)
+            .open(&source)
+            .unwrap()
+            .set_modified(modified + Duration::from_millis(500))
+            .unwrap();
+        let index = DocIndex::load(&source, &snapshot).unwrap();
+        assert_eq!(search(&[&index], "HashSet", 1)[0].path, "std::HashSet");
+
+        // the items of a current snapshot are read from the JSON
+        let index = DocIndex::load(&source, &snapshot).unwrap();
+        let hit = search(&[&index], "HashSet", 1)[0];
+        assert_eq!(
+            hit.item().and_then(|item| item.name.as_deref()),
+            Some("HashSet")
+        );
+
+        fs::write(&snapshot, "not a snapshot").unwrap();
+        assert!(DocIndex::load(&source, &snapshot).is_ok());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,593 @@
+use super::Id;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::{self, Display, Formatter, Write};
+
+/// A type as it appears in a signature
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Type {
+    Path(Path),
+    Generic(String),
+    Primitive(String),
+    Ref {
+        lifetime: Option<String>,
+        mutable: bool,
+        inner: Box<Type>,
+    },
+    Pointer {
+        mutable: bool,
+        inner: Box<Type>,
+    },
+    Tuple(Vec<Type>),
+    Slice(Box<Type>),
+    Array {
+        inner: Box<Type>,
+        len: String,
+    },
+    ImplTrait(Vec<Bound>),
+    DynTrait {
+        traits: Vec<Path>,
+        lifetime: Option<String>,
+    },
+    Qualified {
+        name: String,
+        self_type: Box<Type>,
+        trait_: Option<Path>,
+    },
+    FnPointer {
+        inputs: Vec<Type>,
+        output: Option<Box<Type>>,
+    },
+    Infer,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Path {
+    pub name: String,
+    pub id: Option<Id>,
+    pub args: GenericArgs,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GenericArgs {
+    None,
+    Angle {
+        args: Vec<GenericArg>,
+        constraints: Vec<Constraint>,
+    },
+    Paren {
+        inputs: Vec<Type>,
+        output: Option<Box<Type>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GenericArg {
+    Lifetime(String),
+    Type(Type),
+    Const(String),
+    Infer,
+}
+
+/// An associated item constraint like `Item = T` or `Item: Debug`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Constraint {
+    pub name: String,
+    pub equals: Option<Type>,
+    pub bounds: Vec<Bound>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Bound {
+    Trait { path: Path, maybe: bool },
+    Outlives(String),
+    Other,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Generics {
+    pub params: Vec<GenericParam>,
+    pub where_predicates: Vec<WherePredicate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GenericParam {
+    pub name: String,
+    pub kind: ParamKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ParamKind {
+    Lifetime {
+        outlives: Vec<String>,
+    },
+    Type {
+        bounds: Vec<Bound>,
+        default: Option<Type>,
+        /// `impl Trait` in argument position
+        synthetic: bool,
+    },
+    Const {
+        ty: Type,
+        default: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WherePredicate {
+    Bound {
+        ty: Type,
+        bounds: Vec<Bound>,
+    },
+    Lifetime {
+        lifetime: String,
+        outlives: Vec<String>,
+    },
+    Eq {
+        lhs: Type,
+        rhs: Type,
+    },
+}
+
+/// Splits an externally tagged value like `{"generic": "T"}` into its tag and content
+fn tagged(value: &Value) -> (&str, &Value) {
+    match value {
+        Value::Object(map) if map.len() == 1 => {
+            let (tag, content) = map.iter().next().expect("map has one entry");
+            (tag, content)
+        }
+        Value::String(tag) => (tag, &Value::Null),
+        _ => ("", &Value::Null),
+    }
+}
+
+fn string(value: &Value) -> String {
+    value.as_str().unwrap_or_default().to_owned()
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    list(value, string)
+}
+
+fn list<T>(value: &Value, f: impl Fn(&Value) -> T) -> Vec<T> {
+    value
+        .as_array()
+        .map(|values| values.iter().map(f).collect())
+        .unwrap_or_default()
+}
+
+fn boxed(value: &Value) -> Box<Type> {
+    Box::new(Type::from_value(value))
+}
+
+fn optional(value: &Value) -> Option<Type> {
+    (!value.is_null()).then(|| Type::from_value(value))
+}
+
+impl Type {
+    pub fn from_value(value: &Value) -> Self {
+        let (tag, content) = tagged(value);
+        match tag {
+            "resolved_path" => Self::Path(Path::from_value(content)),
+            "generic" => Self::Generic(string(content)),
+            "primitive" => Self::Primitive(string(content)),
+            "borrowed_ref" => Self::Ref {
+                lifetime: content["lifetime"].as_str().map(str::to_owned),
+                mutable: content["is_mutable"]
+                    .as_bool()
+                    .or_else(|| content["mutable"].as_bool())
+                    .unwrap_or_default(),
+                inner: boxed(&content["type"]),
+            },
+            "raw_pointer" => Self::Pointer {
+                mutable: content["is_mutable"]
+                    .as_bool()
+                    .or_else(|| content["mutable"].as_bool())
+                    .unwrap_or_default(),
+                inner: boxed(&content["type"]),
+            },
+            "tuple" => Self::Tuple(list(content, Self::from_value)),
+            "slice" => Self::Slice(boxed(content)),
+            "array" => Self::Array {
+                inner: boxed(&content["type"]),
+                len: string(&content["len"]),
+            },
+            "impl_trait" => Self::ImplTrait(list(content, Bound::from_value)),
+            "dyn_trait" => Self::DynTrait {
+                traits: list(&content["traits"], |poly| Path::from_value(&poly["trait"])),
+                lifetime: content["lifetime"].as_str().map(str::to_owned),
+            },
+            "qualified_path" => Self::Qualified {
+                name: string(&content["name"]),
+                self_type: boxed(&content["self_type"]),
+                trait_: (!content["trait"].is_null()).then(|| Path::from_value(&content["trait"])),
+            },
+            "function_pointer" => {
+                let sig = signature(content);
+                Self::FnPointer {
+                    inputs: list(&sig["inputs"], |input| Self::from_value(&input[1])),
+                    output: optional(&sig["output"]).map(Box::new),
+                }
+            }
+            "infer" => Self::Infer,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// The signature of a function or function pointer, called `decl` before format version 37
+pub(super) fn signature(value: &Value) -> &Value {
+    if value["sig"].is_null() {
+        &value["decl"]
+    } else {
+        &value["sig"]
+    }
+}
+
+impl Path {
+    pub fn from_value(value: &Value) -> Self {
+        let name = if value["path"].is_string() {
+            string(&value["path"])
+        } else {
+            string(&value["name"])
+        };
+
+        Self {
+            name,
+            id: Id::from_value(&value["id"]),
+            args: GenericArgs::from_value(&value["args"]),
+        }
+    }
+
+    /// The last segment of the path
+    pub fn short_name(&self) -> &str {
+        self.name.rsplit("::").next().unwrap_or(&self.name)
+    }
+}
+
+impl GenericArgs {
+    fn from_value(value: &Value) -> Self {
+        let (tag, content) = tagged(value);
+        match tag {
+            "angle_bracketed" => {
+                let constraints = if content["constraints"].is_array() {
+                    &content["constraints"]
+                } else {
+                    &content["bindings"]
+                };
+                Self::Angle {
+                    args: list(&content["args"], GenericArg::from_value),
+                    constraints: list(constraints, Constraint::from_value),
+                }
+            }
+            "parenthesized" => Self::Paren {
+                inputs: list(&content["inputs"], Type::from_value),
+                output: optional(&content["output"]).map(Box::new),
+            },
+            _ => Self::None,
+        }
+    }
+}
+
+impl GenericArg {
+    fn from_value(value: &Value) -> Self {
+        let (tag, content) = tagged(value);
+        match tag {
+            "lifetime" => Self::Lifetime(string(content)),
+            "type" => Self::Type(Type::from_value(content)),
+            "const" => Self::Const(string(&content["expr"])),
+            _ => Self::Infer,
+        }
+    }
+}
+
+impl Constraint {
+    fn from_value(value: &Value) -> Self {
+        let (tag, content) = tagged(&value["binding"]);
+        let (equals, bounds) = match tag {
+            "equality" => {
+                let (term, ty) = tagged(content);
+                let ty = if term == "type" { ty } else { content };
+                (Some(Type::from_value(ty)), Vec::new())
+            }
+            "constraint" => (None, list(content, Bound::from_value)),
+            _ => (None, Vec::new()),
+        };
+
+        Self {
+            name: string(&value["name"]),
+            equals,
+            bounds,
+        }
+    }
+}
+
+impl Bound {
+    pub fn from_value(value: &Value) -> Self {
+        let (tag, content) = tagged(value);
+        match tag {
+            "trait_bound" => Self::Trait {
+                path: Path::from_value(&content["trait"]),
+                maybe: content["modifier"] == "maybe",
+            },
+            "outlives" => Self::Outlives(string(content)),
+            _ => Self::Other,
+        }
+    }
+}
+
+impl Generics {
+    pub fn from_value(value: &Value) -> Self {
+        Self {
+            params: list(&value["params"], GenericParam::from_value),
+            where_predicates: list(&value["where_predicates"], WherePredicate::from_value),
+        }
+    }
+
+    /// The parameters as written between angle brackets, without `impl Trait` arguments
+    pub fn params(&self) -> String {
+        let params = self
+            .params
+            .iter()
+            .filter(|param| {
+                !matches!(
+                    param.kind,
+                    ParamKind::Type {
+                        synthetic: true,
+                        ..
+                    }
+                )
+            })
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", params.join(", "))
+        }
+    }
+
+    /// The where clause on its own lines, empty if there are no predicates
+    pub fn where_clause(&self) -> String {
+        if self.where_predicates.is_empty() {
+            return String::new();
+        }
+
+        let mut clause = String::from("\nwhere");
+        for predicate in &self.where_predicates {
+            let _ = write!(clause, "\n    {predicate},");
+        }
+        clause
+    }
+}
+
+impl GenericParam {
+    fn from_value(value: &Value) -> Self {
+        let (tag, content) = tagged(&value["kind"]);
+        let kind = match tag {
+            "lifetime" => ParamKind::Lifetime {
+                outlives: strings(&content["outlives"]),
+            },
+            "const" => ParamKind::Const {
+                ty: Type::from_value(&content["type"]),
+                default: content["default"].as_str().map(str::to_owned),
+            },
+            _ => ParamKind::Type {
+                bounds: list(&content["bounds"], Bound::from_value),
+                default: optional(&content["default"]),
+                synthetic: content["is_synthetic"]
+                    .as_bool()
+                    .or_else(|| content["synthetic"].as_bool())
+                    .unwrap_or_default(),
+            },
+        };
+
+        Self {
+            name: string(&value["name"]),
+            kind,
+        }
+    }
+}
+
+impl WherePredicate {
+    fn from_value(value: &Value) -> Self {
+        let (tag, content) = tagged(value);
+        match tag {
+            "lifetime_predicate" => Self::Lifetime {
+                lifetime: string(&content["lifetime"]),
+                outlives: strings(&content["outlives"]),
+            },
+            "eq_predicate" => {
+                let (_, rhs) = tagged(&content["rhs"]);
+                Self::Eq {
+                    lhs: Type::from_value(&content["lhs"]),
+                    rhs: Type::from_value(rhs),
+                }
+            }
+            _ => Self::Bound {
+                ty: Type::from_value(&content["type"]),
+                bounds: list(&content["bounds"], Bound::from_value),
+            },
+        }
+    }
+}
+
+fn join<T: Display>(items: &[T], separator: &str) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "{path}"),
+            Self::Generic(name) | Self::Primitive(name) => f.write_str(name),
+            Self::Ref {
+                lifetime,
+                mutable,
+                inner,
+            } => {
+                f.write_str("&")?;
+                if let Some(lifetime) = lifetime {
+                    write!(f, "{lifetime} ")?;
+                }
+                if *mutable {
+                    f.write_str("mut ")?;
+                }
+                write!(f, "{inner}")
+            }
+            Self::Pointer { mutable, inner } => {
+                write!(f, "*{} {inner}", if *mutable { "mut" } else { "const" })
+            }
+            Self::Tuple(types) if types.len() == 1 => write!(f, "({},)", types[0]),
+            Self::Tuple(types) => write!(f, "({})", join(types, ", ")),
+            Self::Slice(inner) => write!(f, "[{inner}]"),
+            Self::Array { inner, len } => write!(f, "[{inner}; {len}]"),
+            Self::ImplTrait(bounds) => write!(f, "impl {}", join(bounds, " + ")),
+            Self::DynTrait { traits, lifetime } => {
+                write!(f, "dyn {}", join(traits, " + "))?;
+                if let Some(lifetime) = lifetime {
+                    write!(f, " + {lifetime}")?;
+                }
+                Ok(())
+            }
+            Self::Qualified {
+                name,
+                self_type,
+                trait_,
+            } => match trait_ {
+                Some(trait_) if !trait_.name.is_empty() => {
+                    write!(f, "<{self_type} as {trait_}>::{name}")
+                }
+                _ => write!(f, "{self_type}::{name}"),
+            },
+            Self::FnPointer { inputs, output } => {
+                write!(f, "fn({})", join(inputs, ", "))?;
+                if let Some(output) = output {
+                    write!(f, " -> {output}")?;
+                }
+                Ok(())
+            }
+            Self::Infer => f.write_str("_"),
+            Self::Unknown => f.write_str("…"),
+        }
+    }
+}
+
+impl Display for Path {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.short_name(), self.args)
+    }
+}
+
+impl Display for GenericArgs {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::None => Ok(()),
+            Self::Angle { args, constraints } => {
+                let all = args
+                    .iter()
+                    .map(ToString::to_string)
+                    .chain(constraints.iter().map(ToString::to_string))
+                    .collect::<Vec<_>>();
+                if all.is_empty() {
+                    Ok(())
+                } else {
+                    write!(f, "<{}>", all.join(", "))
+                }
+            }
+            Self::Paren { inputs, output } => {
+                write!(f, "({})", join(inputs, ", "))?;
+                if let Some(output) = output {
+                    write!(f, " -> {output}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for GenericArg {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lifetime(lifetime) => f.write_str(lifetime),
+            Self::Type(ty) => write!(f, "{ty}"),
+            Self::Const(expr) => f.write_str(expr),
+            Self::Infer => f.write_str("_"),
+        }
+    }
+}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.equals {
+            Some(ty) => write!(f, "{} = {ty}", self.name),
+            None => write!(f, "{}: {}", self.name, join(&self.bounds, " + ")),
+        }
+    }
+}
+
+impl Display for Bound {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Trait { path, maybe } => {
+                if *maybe {
+                    f.write_str("?")?;
+                }
+                write!(f, "{path}")
+            }
+            Self::Outlives(lifetime) => f.write_str(lifetime),
+            Self::Other => f.write_str("…"),
+        }
+    }
+}
+
+impl Display for GenericParam {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParamKind::Lifetime { outlives } => {
+                f.write_str(&self.name)?;
+                if !outlives.is_empty() {
+                    write!(f, ": {}", outlives.join(" + "))?;
+                }
+                Ok(())
+            }
+            ParamKind::Type {
+                bounds, default, ..
+            } => {
+                f.write_str(&self.name)?;
+                if !bounds.is_empty() {
+                    write!(f, ": {}", join(bounds, " + "))?;
+                }
+                if let Some(default) = default {
+                    write!(f, " = {default}")?;
+                }
+                Ok(())
+            }
+            ParamKind::Const { ty, default } => {
+                write!(f, "const {}: {ty}", self.name)?;
+                if let Some(default) = default {
+                    write!(f, " = {default}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for WherePredicate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bound { ty, bounds } => write!(f, "{ty}: {}", join(bounds, " + ")),
+            Self::Lifetime { lifetime, outlives } => {
+                write!(f, "{lifetime}: {}", outlives.join(" + "))
+            }
+            Self::Eq { lhs, rhs } => write!(f, "{lhs} = {rhs}"),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crate::cache::{CacheError, Client};
+use poise::serenity_prelude::{ChannelId, GuildId};
+use serde::{Deserialize, Serialize};
+
+/// Replies longer than this many characters are moved into a thread unless configured otherwise
+pub const DEFAULT_THREAD_THRESHOLD: usize = 4096;
+
+/// Daily tips are posted at this hour in UTC unless configured otherwise
+pub const DEFAULT_TIP_HOUR: u8 = 9;
+
+/// The set of the guilds which configured a daily tip
+const DAILY_TIP_GUILDS: &str = "daily_tip::guilds";
+
+/// Per-guild configuration, stored in redis without expiration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuildSettings {
+    /// `None` disables posting long replies into threads
+    pub thread_threshold: Option<usize>,
+    /// Whether `[[path]]` references in messages are answered with links to the docs
+    pub doc_links: bool,
+    /// Where and when a tip is posted every day, `None` disables it
+    pub daily_tip: Option<DailyTip>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyTip {
+    pub channel: ChannelId,
+    /// The hour of the day in UTC from which on the tip of the day is posted
+    pub hour: u8,
+    /// Only tips with this tag are posted
+    pub tag: Option<String>,
+}
+
+impl Default for GuildSettings {
+    fn default() -> Self {
+        Self {
+            thread_threshold: Some(DEFAULT_THREAD_THRESHOLD),
+            doc_links: false,
+            daily_tip: None,
+        }
+    }
+}
+
+impl GuildSettings {
+    fn key(guild_id: GuildId) -> String {
+        format!("guild_settings::{guild_id}")
+    }
+
+    pub async fn load(client: &Client, guild_id: GuildId) -> Result<Self, CacheError> {
+        Ok(client.get(&Self::key(guild_id)).await?.unwrap_or_default())
+    }
+
+    /// Saves the settings and keeps track of the guilds which want a daily tip
+    pub async fn save(&self, client: &Client, guild_id: GuildId) -> Result<(), CacheError> {
+        client.set_persistent(&Self::key(guild_id), self).await?;
+        let member = guild_id.to_string();
+        match self.daily_tip {
+            Some(_) => client.add_member(DAILY_TIP_GUILDS, &member).await,
+            None => client.remove_member(DAILY_TIP_GUILDS, &member).await,
+        }
+    }
+
+    /// The guilds whose settings have a [`DailyTip`]
+    pub async fn with_daily_tip(client: &Client) -> Result<Vec<GuildId>, CacheError> {
+        Ok(client
+            .members(DAILY_TIP_GUILDS)
+            .await?
+            .iter()
+            .filter_map(|id| id.parse().ok())
+            .collect())
+    }
+}
@@ -0,0 +1,240 @@
+//! The curated tips and explanations of why Rust works the way it does, generated from
+//! `assets/tips` by the build script
+
+use std::{
+    collections::hash_map::RandomState,
+    fmt,
+    hash::{BuildHasher, Hasher},
+};
+
+/// A short explanation of a concept with an example which runs on the playground
+#[derive(Debug, PartialEq, Eq)]
+pub struct Tip {
+    /// The file name, like `question_mark`
+    pub name: &'static str,
+    /// Like "The ? operator"
+    pub topic: &'static str,
+    /// Lowercase keywords like `error-handling`, the first one is the main one
+    pub tags: &'static [&'static str],
+    pub difficulty: Difficulty,
+    /// The explanation as markdown, without the example
+    pub explanation: &'static str,
+    /// A whole program with `fn main`
+    pub example: &'static str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Advanced,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Beginner => "beginner",
+            Self::Intermediate => "intermediate",
+            Self::Advanced => "advanced",
+        })
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/tips.rs"));
+
+/// Words which say nothing about the concept in questions like "why does rust have no null?"
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "can", "do", "does", "doesn't", "dont", "don't", "for", "have", "how",
+    "i", "in", "is", "it", "need", "no", "not", "of", "on", "or", "rust", "rust's", "the", "there",
+    "to", "use", "we", "what", "when", "why", "with",
+];
+
+/// The tip called `name`
+pub fn get(name: &str) -> Option<&'static Tip> {
+    TIPS.iter().find(|tip| tip.name == name.trim())
+}
+
+/// Every tag, sorted
+pub fn tags() -> Vec<&'static str> {
+    let mut tags = TIPS
+        .iter()
+        .flat_map(|tip| tip.tags.iter().copied())
+        .collect::<Vec<_>>();
+    tags.sort_unstable();
+    tags.dedup();
+    tags
+}
+
+/// The tips tagged `tag`, or every tip without one
+pub fn with_tag(tag: Option<&str>) -> impl Iterator<Item = &'static Tip> {
+    let tag = tag.map(normalize_tag);
+    TIPS.iter().filter(move |tip| {
+        tag.as_ref()
+            .is_none_or(|tag| tip.tags.contains(&tag.as_str()))
+    })
+}
+
+/// A random tip tagged `tag` whose name isn't in `exclude`, `None` once all of them are excluded
+pub fn random(tag: Option<&str>, exclude: &[String]) -> Option<&'static Tip> {
+    let candidates = with_tag(tag)
+        .filter(|tip| !exclude.iter().any(|name| name == tip.name))
+        .collect::<Vec<_>>();
+    if candidates.is_empty() {
+        return None;
+    }
+    // every `RandomState` is seeded differently, which is random enough to pick a tip
+    let index = RandomState::new().build_hasher().finish() as usize % candidates.len();
+    Some(candidates[index])
+}
+
+/// `Error Handling` and `error-handling` are the same tag
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The `limit` tips which answer a question like "why lifetimes?" best, matching its words against
+/// topics, names and tags before the explanations
+pub fn lookup(query: &str, limit: usize) -> Vec<&'static Tip> {
+    let words = words(query)
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+        .map(|word| singular(&word).to_owned())
+        .collect::<Vec<_>>();
+    if words.is_empty() {
+        return Vec::new();
+    }
+
+    let mut hits = TIPS
+        .iter()
+        .enumerate()
+        .filter_map(|(position, tip)| Some((score(tip, &words)?, position, tip)))
+        .collect::<Vec<_>>();
+    hits.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    hits.into_iter()
+        .take(limit)
+        .map(|(_, _, tip)| tip)
+        .collect()
+}
+
+/// At least half of the words have to be keywords of the tip or appear in its explanation, and
+/// keywords count more the closer they match
+fn score(tip: &Tip, words: &[String]) -> Option<i64> {
+    let topic = words_of(tip.topic)
+        .into_iter()
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+        .collect::<Vec<_>>();
+    let names = words_of(tip.name);
+    let main_tag = words_of(tip.tags[0]);
+    let tags = tip
+        .tags
+        .iter()
+        .flat_map(|tag| words_of(tag).into_iter().chain([singular(tag).to_owned()]))
+        .collect::<Vec<_>>();
+    let explanation = words_of(tip.explanation);
+
+    let mut score = 0;
+    let mut matched = 0;
+    for word in words {
+        let points = if topic.contains(word) || names.contains(word) {
+            100
+        } else if main_tag.contains(word) {
+            80
+        } else if tags.contains(word) {
+            60
+        } else if word.len() >= 3 && tip.tags[0].starts_with(word.as_str()) {
+            40
+        } else if word.len() >= 3 && tags.iter().any(|tag| tag.starts_with(word.as_str())) {
+            30
+        } else if explanation.contains(word) {
+            5
+        } else {
+            continue;
+        };
+        score += points;
+        matched += 1;
+    }
+    if matched == 0 || matched * 2 < words.len() {
+        return None;
+    }
+    // the tip about exactly the asked concept comes first
+    if topic == words {
+        score += 1000;
+    }
+    Some(score)
+}
+
+/// The lowercase words of `text` in singular
+fn words_of(text: &str) -> Vec<String> {
+    words(text).map(|word| singular(&word).to_owned()).collect()
+}
+
+fn words(text: &str) -> impl Iterator<Item = String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+}
+
+/// Good enough to match "lifetimes" with "lifetime", but not to turn "class" into "clas"
+fn singular(word: &str) -> &str {
+    match word.strip_suffix('s') {
+        Some(stem) if stem.len() >= 3 && !stem.ends_with('s') => stem,
+        _ => word,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_complete() {
+        assert!(TIPS.len() >= 30);
+        assert!(TIPS.windows(2).all(|pair| pair[0].name < pair[1].name));
+        for tip in TIPS {
+            assert!(!tip.topic.is_empty(), "{}", tip.name);
+            assert!(!tip.tags.is_empty(), "{}", tip.name);
+            for tag in tip.tags {
+                assert_eq!(*tag, normalize_tag(tag), "{}", tip.name);
+            }
+            assert!(!tip.explanation.contains("```"), "{}", tip.name);
+            assert!(tip.example.contains("fn main()"), "{}", tip.name);
+        }
+    }
+
+    #[test]
+    fn filters_by_tag() {
+        let names = |tag| with_tag(Some(tag)).map(|tip| tip.name).collect::<Vec<_>>();
+        assert_eq!(names("Error Handling"), ["question_mark", "result"]);
+        assert!(names("no-such-tag").is_empty());
+        assert_eq!(with_tag(None).count(), TIPS.len());
+        assert!(tags().contains(&"lifetimes"));
+    }
+
+    #[test]
+    fn picks_tips_not_posted_yet() {
+        let posted = ["result".to_owned(), "question_mark".to_owned()];
+        let tip = random(Some("error-handling"), &posted[..1]).unwrap();
+        assert_eq!(tip.name, "question_mark");
+        assert_eq!(random(Some("error-handling"), &posted), None);
+        assert!(random(None, &[]).is_some());
+    }
+
+    #[test]
+    fn answers_questions() {
+        let first = |query| lookup(query, 3).first().map(|tip| tip.name);
+        assert_eq!(first("lifetimes"), Some("lifetimes"));
+        assert_eq!(first("Why does Rust have lifetimes?"), Some("lifetimes"));
+        assert_eq!(first("why no null"), Some("option"));
+        assert_eq!(first("rc refcell"), Some("rc_refcell"));
+        assert_eq!(first("interior mutability"), Some("rc_refcell"));
+        assert_eq!(first("why no exceptions?"), Some("result"));
+        assert_eq!(first("the orphan rule"), Some("orphan_rule"));
+        assert_eq!(first("why no inheritance"), Some("traits"));
+        assert_eq!(first("borrow checker"), Some("borrowing"));
+        assert_eq!(first("why?"), None);
+        assert_eq!(first("quantum entanglement"), None);
+    }
+}